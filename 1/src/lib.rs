@@ -0,0 +1,46 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+extern crate aoc2015_lib;
+
+use alloc::string::{String, ToString};
+use aoc2015_lib::{AocError, Solver};
+
+// Follows the instructions, returning the floor they end on and the
+// position (counting from 1) of the first one to reach the basement.
+pub fn walk(input: &str) -> Result<(i32, Option<usize>), AocError> {
+   let mut floor = 0;
+   let mut entered_basement = None;
+   for (counter, c) in input.trim().chars().enumerate() {
+      match c {
+         '(' => floor += 1,
+         ')' => floor -= 1,
+         _ => return Err(AocError::parse(1, Some(counter + 1), format!("expected ( or ), not {:?}", c))),
+      }
+      if entered_basement.is_none() && floor < 0 {
+         entered_basement = Some(counter + 1);
+      }
+   }
+   return Ok((floor, entered_basement));
+}
+
+// The puzzle itself: the floor Santa ends on, then when he first gets to
+// the basement.  Both come out of the one walk up and down.
+pub struct Day01;
+
+impl Solver for Day01 {
+   type Parsed = (i32, Option<usize>);
+
+   fn parse(input: &str) -> Result<(i32, Option<usize>), AocError> {
+      return walk(input);
+   }
+
+   fn part1(walked: &(i32, Option<usize>)) -> Result<String, AocError> {
+      return Ok(walked.0.to_string());
+   }
+
+   fn part2(walked: &(i32, Option<usize>)) -> Result<String, AocError> {
+      return walked.1.map(|p| p.to_string())
+         .ok_or(AocError::Unsolvable("never enters the basement".to_string()));
+   }
+}
@@ -1,25 +1,20 @@
-use std::io::prelude::*;
-use std::fs::File;
+extern crate aoc2015_lib;
+extern crate day01;
 
-fn main() {
-   let mut f = File::open("input.txt").unwrap();
+use aoc2015_lib::AocError;
 
-   let mut input = String::new();
-   let size = f.read_to_string(&mut input).unwrap();
-   let mut floor = 0;
-   let mut counter = 0;
-   let mut entered_basement = false;
-   for c in input.chars() {
-      match c {
-         '(' => floor += 1,
-         ')' => floor -= 1,
-         _ => panic!("Invalid input char {}"),
-      }
-      counter += 1;
-      if !entered_basement && floor < 0 {
-         println!("Entered basement on position {}", counter);
-         entered_basement = true;
+fn main() -> Result<(), AocError> {
+   let parts = aoc2015_lib::parts();
+   let input = aoc2015_lib::input()?;
+   let (floor, entered_basement) = day01::walk(&input)?;
+   if parts.contains(&1) {
+      println!("With {} instructions we ended on floor {}", input.len(), floor);
+   }
+   if parts.contains(&2) {
+      match entered_basement {
+         Some(counter) => println!("Entered basement on position {}", counter),
+         None => println!("Never entered the basement"),
       }
    }
-   println!("With {} instructions we ended on floor {}", size, floor);
+   return Ok(());
 }
@@ -0,0 +1,90 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+extern crate aoc2015_lib;
+
+use aoc2015_lib::{parse_lines, AocError, Solver};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+// The paper and ribbon one present needs, from its dimensions like 2x3x4.
+// It's all worked out in u64s, which sides that fit in a u16 can't
+// overflow, and then has to fit in a u32.
+pub fn present(curr: &str) -> Result<(u32, u32), String> {
+   let dimensions: Result<Vec<u64>, _> = curr.split("x").map(|s| s.parse::<u16>().map(u64::from)).collect();
+   let dimensions = match dimensions {
+      Ok(d) if d.len() == 3 => d,
+      _ => return Err(format!("expected dimensions like 2x3x4, not {:?}", curr)),
+   };
+   let (l, w, h) = (dimensions[0], dimensions[1], dimensions[2]);
+
+   // Store each side.
+   let sides = [(l, w), (w, h), (h, l)];
+
+   // area of each side is x * y
+   let mut areas: Vec<u64> = sides.iter().map(|x| x.0*x.1).collect();
+   areas.sort();
+
+   // perimeter of each side is 2x + 2y
+   let mut perimeters: Vec<u64> = sides.iter().map(|x| x.0*2 + x.1*2).collect();
+   perimeters.sort();
+
+   // smallest side by area.
+   let slack = areas[0];
+
+   // surfase area of the whole is 2* each side's area.
+   let area = areas.iter().fold(0, |a, b| a+2*b);
+
+   // volume is product of dimensions.
+   let volume: u64 = dimensions.iter().product();
+
+   // smallest side by perimeter;
+   let min_perimeter = perimeters[0];
+
+   // ribbon is min_perimeter face + volume;
+   let ribbon = min_perimeter + volume;
+
+   // paper required is area + slack
+   let paper = area + slack;
+
+   let too_big = |_| format!("{:?} needs more paper than there is", curr);
+   return Ok((u32::try_from(paper).map_err(too_big)?, u32::try_from(ribbon).map_err(too_big)?));
+}
+
+// The paper and ribbon for every present in input, one per line.
+pub fn presents(input: &str) -> Result<Vec<(u32, u32)>, AocError> {
+   return parse_lines(input, present);
+}
+
+// Total paper and ribbon for every present in input.
+pub fn total(input: &str) -> Result<(u64, u64), AocError> {
+   return Ok(presents(input)?.into_iter().fold((0, 0), |a, b| (a.0 + u64::from(b.0), a.1 + u64::from(b.1))));
+}
+
+// The puzzle itself: all the paper, then all the ribbon.
+pub struct Day02;
+
+impl Solver for Day02 {
+   type Parsed = Vec<(u32, u32)>;
+
+   fn parse(input: &str) -> Result<Vec<(u32, u32)>, AocError> {
+      return presents(input);
+   }
+
+   fn part1(presents: &Vec<(u32, u32)>) -> Result<String, AocError> {
+      return Ok(presents.iter().map(|p| u64::from(p.0)).sum::<u64>().to_string());
+   }
+
+   fn part2(presents: &Vec<(u32, u32)>) -> Result<String, AocError> {
+      return Ok(presents.iter().map(|p| u64::from(p.1)).sum::<u64>().to_string());
+   }
+}
+
+#[test]
+fn test_present() {
+   assert_eq!(present("2x3x4"), Ok((58, 34)));
+   assert_eq!(present("1x1x10"), Ok((43, 14)));
+   assert!(present("2x3").is_err());
+   assert!(present("65535x65535x65535").unwrap_err().contains("more paper than there is"));
+}
@@ -0,0 +1,76 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+extern crate aoc2015_lib;
+
+use aoc2015_lib::{AocError, Solver};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+// Each of the directions in input as the step it takes, like (0, 1) for ^.
+pub fn directions(input: &str) -> Result<Vec<(i32, i32)>, AocError> {
+   let mut deltas = Vec::new();
+   for (count, c) in input.trim().chars().enumerate() {
+      deltas.push(match c {
+         '^' => (0, 1),
+         '>' => (1, 0),
+         'v' => (0, -1),
+         '<' => (-1, 0),
+         _ => return Err(AocError::parse(1, Some(count + 1), format!("expected one of ^>v<, not {:?}", c))),
+      });
+   }
+   return Ok(deltas);
+}
+
+// How many houses get at least one present when santas take turns
+// following the directions: 1 for Santa alone, 2 with Robo-Santa.
+pub fn houses(input: &str, santas: usize) -> Result<usize, AocError> {
+   return Ok(visit(&directions(input)?, santas));
+}
+
+// The same, for directions that have already been read.
+pub fn visit(deltas: &[(i32, i32)], santas: usize) -> usize {
+   let mut map = BTreeMap::new();
+
+   let mut positions = vec![(0, 0); santas];
+   map.insert((0, 0), santas);
+
+   for (count, delta) in deltas.iter().enumerate() {
+      let curr = &mut positions[count % santas];
+      *curr = (curr.0 + delta.0, curr.1 + delta.1);
+      let value = match map.get(&*curr) {
+         Some(&v) => v + 1,
+         None => 1,
+      };
+      map.insert(*curr, value);
+   }
+   return map.len();
+}
+
+// The puzzle itself: Santa alone, then with Robo-Santa.
+pub struct Day03;
+
+impl Solver for Day03 {
+   type Parsed = Vec<(i32, i32)>;
+
+   fn parse(input: &str) -> Result<Vec<(i32, i32)>, AocError> {
+      return directions(input);
+   }
+
+   fn part1(deltas: &Vec<(i32, i32)>) -> Result<String, AocError> {
+      return Ok(visit(deltas, 1).to_string());
+   }
+
+   fn part2(deltas: &Vec<(i32, i32)>) -> Result<String, AocError> {
+      return Ok(visit(deltas, 2).to_string());
+   }
+}
+
+#[test]
+fn check_houses() {
+   assert_eq!(houses("^>v<", 1).unwrap(), 4);
+   assert_eq!(houses("^>v<", 2).unwrap(), 3);
+   assert_eq!(houses("^v^v^v^v^v", 2).unwrap(), 11);
+   assert_eq!(houses("^^x", 1).unwrap_err().to_string(), "line 1, column 3: expected one of ^>v<, not 'x'");
+}
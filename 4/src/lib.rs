@@ -0,0 +1,58 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+extern crate aoc2015_lib;
+extern crate md5;
+
+use alloc::string::{String, ToString};
+use aoc2015_lib::{AocError, Progress, Solver};
+
+pub fn calculate_md5(input: &str, num: u64) -> String {
+   return format!("{:x}", md5::compute(format!("{}{}", input, num)));
+}
+
+// The first number that, appended to the secret key, gives a hash
+// starting with zeros zeroes.  progress hears about every 100000th try.
+pub fn mine<F: FnMut(u64)>(key: &str, zeros: usize, mut progress: F) -> u64 {
+   let prefix = "0".repeat(zeros);
+   let mut i = 0u64;
+   loop {
+      if calculate_md5(key, i).starts_with(&prefix) {
+         return i;
+      }
+      if i.is_multiple_of(100000) {
+         progress(i);
+      }
+      i += 1;
+   }
+}
+
+// The puzzle itself: the first coin with five zeros, then with six.
+pub struct Day04;
+
+impl Solver for Day04 {
+   type Parsed = String;
+
+   fn parse(input: &str) -> Result<String, AocError> {
+      return Ok(input.trim().to_string());
+   }
+
+   fn part1(key: &String) -> Result<String, AocError> {
+      return Day04::part_with_progress(key, 1, &mut ());
+   }
+
+   fn part2(key: &String) -> Result<String, AocError> {
+      return Day04::part_with_progress(key, 2, &mut ());
+   }
+
+   // Mining goes on until it finds a coin, so there's no telling how far
+   // it has to go, only how far it has got.
+   fn part_with_progress(key: &String, part: u32, progress: &mut dyn Progress) -> Result<String, AocError> {
+      let zeros = match part {
+         1 => 5,
+         2 => 6,
+         _ => return Err(AocError::Unsolvable(format!("there's no part {}", part))),
+      };
+      return Ok(mine(key, zeros, |i| progress.set_position(i)).to_string());
+   }
+}
@@ -1,27 +1,18 @@
-extern crate crypto;
+extern crate aoc2015_lib;
+extern crate day04;
+#[macro_use]
+extern crate tracing;
 
-use crypto::digest::Digest;
-use crypto::md5::Md5;
+use aoc2015_lib::AocError;
 
-fn calculate_md5(digest: &mut Digest, input: &str, num: u64) -> String {
-   digest.reset();
-   digest.input(&format!("{}{}", input, num).into_bytes()[..]);
-   return digest.result_str();
-}
-
-fn main() {
-   let input = "yzbqklnj";
-   let mut digest = Md5::new();
-   let mut i = 0u64;
-   loop {
-      let result = calculate_md5(&mut digest, input, i);
-      if result.starts_with("000000") {
-         println!("Found a coin at {} = {}", i, result);
-         break;
-      }
-      if i % 100000 == 0 {
-         println!("{}", i);
-      }
-      i += 1;
+fn main() -> Result<(), AocError> {
+   aoc2015_lib::init_tracing("warn");
+   let input = aoc2015_lib::input()?;
+   let input = input.trim();
+   for part in aoc2015_lib::parts() {
+      // Five zeros for part 1, six for part 2.
+      let i = day04::mine(input, part as usize + 4, |i| debug!("tried {}", i));
+      println!("Found a coin at {} = {}", i, day04::calculate_md5(input, i));
    }
+   return Ok(());
 }
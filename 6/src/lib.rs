@@ -0,0 +1,184 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use aoc2015_lib::{parse_lines, AocError, Solver};
+use core::str::FromStr;
+use core::fmt;
+
+extern crate aoc2015_lib;
+
+#[derive(Debug)]
+enum Operation {
+   On,
+   Off,
+   Toggle,
+}
+
+#[derive(Debug)]
+struct Coordinate {
+   x: usize,
+   y: usize,
+}
+
+#[derive(Debug)]
+struct ParseCoordinateError;
+
+impl FromStr for Coordinate {
+   type Err = ParseCoordinateError;
+   fn from_str(s: &str) -> Result<Coordinate, ParseCoordinateError> {
+      let parts: Vec<_> = s.split(",").collect();
+      if parts.len() != 2 {
+         Err(ParseCoordinateError)
+      } else {
+         match (parts[0].parse::<usize>(), parts[1].parse::<usize>()) {
+            (Ok(x), Ok(y)) if x < 1000 && y < 1000 => Ok(Coordinate{x: x, y: y}),
+            _ => Err(ParseCoordinateError),
+         }
+                     
+      }
+   }
+}
+
+impl fmt::Display for Coordinate {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      write!(f, "{},{}", self.x, self.y)
+   }
+}
+
+// Works for both boolean and brigtness.
+trait Light {
+   fn on(&mut self);
+   fn off(&mut self);
+   fn toggle(&mut self);
+   fn value(&self) -> u32;
+}
+
+// On/Off style lights.  value is 1 if on
+impl Light for bool {
+   fn toggle(&mut self) {
+      *self = !*self;
+   }
+   fn on(&mut self) {
+      *self = true;
+   }
+   fn off(&mut self) {
+      *self = false;
+   }
+   fn value(&self) -> u32 {
+      return if *self { 1 } else { 0 };
+   }
+}
+
+// Brightness style lights, value is brigtness.
+impl Light for u32 {
+   fn toggle(&mut self) {
+      *self += 2;
+   }
+   fn on(&mut self) {
+      *self += 1;
+   }
+   fn off(&mut self) {
+      if *self > 0 {
+         *self -= 1;
+      }
+   }
+   fn value(&self) -> u32 {
+      return *self;
+   }
+}
+
+fn apply<T: Light>(matrix: &mut [[T; 1000]], cmd: &Operation, start: &Coordinate, end: &Coordinate) {
+   for i in start.x..end.x+1 {
+      for j in start.y..end.y+1 {
+         match *cmd {
+            Operation::On => matrix[i][j].on(),
+            Operation::Off => matrix[i][j].off(),
+            Operation::Toggle => matrix[i][j].toggle(),
+         };
+      }
+   }
+}
+
+fn total_value<T: Light>(matrix: &[[T; 1000]]) -> u32 {
+   return matrix.iter()
+      .fold(0, |sum, i| sum + i.iter()
+         .fold(0, |inner_sum, j| inner_sum + j.value()));
+}
+
+// Every instruction in input, in order.
+pub struct Instructions(Vec<(Operation, Coordinate, Coordinate)>);
+
+pub fn instructions(input: &str) -> Result<Instructions, AocError> {
+   let instructions = parse_lines(input, |curr| {
+      let invalid = || format!("expected an instruction like `toggle 0,0 through 999,0`, not {:?}", curr);
+      let words: Vec<&str> = curr.split_whitespace().collect();
+      let (cmd, start, end) = match words[..] {
+         ["turn", "on", start, "through", end] => (Operation::On, start, end),
+         ["turn", "off", start, "through", end] => (Operation::Off, start, end),
+         ["toggle", start, "through", end] => (Operation::Toggle, start, end),
+         _ => return Err(invalid()),
+      };
+      let start = start.parse::<Coordinate>().map_err(|_| invalid())?;
+      let end = end.parse::<Coordinate>().map_err(|_| invalid())?;
+      return Ok((cmd, start, end));
+   })?;
+   return Ok(Instructions(instructions));
+}
+
+// Follows every instruction with lights that start off, and returns
+// their total value.
+fn follow<T: Light + Copy + Default>(instructions: &Instructions) -> u32 {
+   let mut matrix = vec![[T::default(); 1000]; 1000];
+   for &(ref cmd, ref start, ref end) in &instructions.0 {
+      apply(&mut matrix, cmd, start, end);
+   }
+   return total_value(&matrix);
+}
+
+// Follows every instruction in input, returning how many lights are lit
+// with on/off lights, and how bright they are with dimmable ones.
+pub fn run(input: &str) -> Result<(u32, u32), AocError> {
+   let instructions = instructions(input)?;
+   return Ok((follow::<bool>(&instructions), follow::<u32>(&instructions)));
+}
+
+// The puzzle itself: on/off lights, then dimmable ones.
+pub struct Day06;
+
+impl Solver for Day06 {
+   type Parsed = Instructions;
+
+   fn parse(input: &str) -> Result<Instructions, AocError> {
+      return instructions(input);
+   }
+
+   fn part1(instructions: &Instructions) -> Result<String, AocError> {
+      return Ok(follow::<bool>(instructions).to_string());
+   }
+
+   fn part2(instructions: &Instructions) -> Result<String, AocError> {
+      return Ok(follow::<u32>(instructions).to_string());
+   }
+}
+
+#[test]
+fn try_tests() {
+   let mut matrix = vec![[false; 1000]; 1000];
+   apply(&mut matrix, &Operation::On, &Coordinate{x: 0, y: 0}, &Coordinate{x: 999, y:999});
+   assert_eq!(1000000, total_value(&matrix));
+   apply(&mut matrix, &Operation::Toggle, &Coordinate{x: 1, y: 1}, &Coordinate{x: 10, y:10});
+   assert_eq!(999900, total_value(&matrix));
+}
+
+#[test]
+fn try_part2() {
+   let mut matrix = vec![[0u32; 1000]; 1000];
+   apply(&mut matrix, &Operation::On, &Coordinate{x: 0, y: 0}, &Coordinate{x: 999, y:999});
+   assert_eq!(1000000, total_value(&matrix));
+   apply(&mut matrix, &Operation::Toggle, &Coordinate{x: 1, y: 1}, &Coordinate{x: 10, y:10});
+   // Toggling brightens by 2.
+   assert_eq!(1000200, total_value(&matrix));
+}
@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate criterion;
+extern crate circuit;
+
+use circuit::{random_netlist, Circuit};
+use criterion::{BatchSize, Criterion};
+
+const PUZZLE: &str = include_str!("../input.txt");
+
+fn netlists() -> Vec<(String, String)> {
+   return vec![
+      ("puzzle".to_string(), PUZZLE.to_string()),
+      ("10k gates".to_string(), random_netlist(10000, 100, 1)),
+      ("100k gates".to_string(), random_netlist(100000, 300, 1)),
+   ];
+}
+
+fn parsing(c: &mut Criterion) {
+   let mut group = c.benchmark_group("parse");
+   group.sample_size(10);
+   for (name, netlist) in netlists() {
+      group.bench_function(&name[..], |b| b.iter(|| netlist.parse::<Circuit>().unwrap()));
+   }
+   group.finish();
+}
+
+fn evaluation(c: &mut Criterion) {
+   let mut group = c.benchmark_group("resolve_all");
+   group.sample_size(10);
+   for (name, netlist) in netlists() {
+      let circuit = netlist.parse::<Circuit>().unwrap();
+      group.bench_function(&name[..], |b| b.iter_batched(
+         || circuit.clone(), |mut circuit| circuit.resolve_all().unwrap(), BatchSize::LargeInput));
+   }
+   group.finish();
+
+   // Part 2 of the puzzle: how much does forcing b and resolving again cost
+   // compared to the first pass?
+   let mut circuit = PUZZLE.parse::<Circuit>().unwrap();
+   let a = circuit.get_value("a").unwrap();
+   c.bench_function("puzzle force b", |b| b.iter(|| {
+      circuit.force_value("b", a);
+      circuit.get_value("a").unwrap()
+   }));
+}
+
+criterion_group!(benches, parsing, evaluation);
+criterion_main!(benches);
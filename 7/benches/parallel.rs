@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate criterion;
+extern crate circuit;
+
+use circuit::Circuit;
+use criterion::{BatchSize, Criterion};
+
+// A wide, layered netlist: every gate in a layer only reads from the layer
+// before it, so each layer can be evaluated in parallel.
+fn layered(width: usize, depth: usize) -> Circuit {
+   let mut circuit = Circuit::new();
+   for j in 0..width {
+      circuit.add_element(&format!("l0_{}", j), &format!("{}", j * 7919 % 65536)).unwrap();
+   }
+   let ops = ["AND", "OR", "XOR", "NAND"];
+   for d in 1..depth {
+      for j in 0..width {
+         let spec = format!("l{}_{} {} l{}_{}", d - 1, j, ops[(d + j) % ops.len()], d - 1, (j + 1) % width);
+         circuit.add_element(&format!("l{}_{}", d, j), &spec).unwrap();
+      }
+   }
+   return circuit;
+}
+
+fn evaluation(c: &mut Criterion) {
+   let mut group = c.benchmark_group("layered 1000x50");
+   group.sample_size(10);
+   group.bench_function("serial", |b| b.iter_batched(
+      || layered(1000, 50), |mut circuit| circuit.resolve_all().unwrap(), BatchSize::LargeInput));
+   group.bench_function("parallel", |b| b.iter_batched(
+      || layered(1000, 50), |mut circuit| circuit.par_resolve_all().unwrap(), BatchSize::LargeInput));
+   group.finish();
+}
+
+criterion_group!(benches, evaluation);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate circuit;
+
+use circuit::{Circuit, ElementSpec, Limits};
+use std::str;
+
+// Throws arbitrary text at every stage of loading a netlist.  None of it
+// should panic, however malformed; bad lines are just errors.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   let mut circuit = Circuit::new();
+   let mut names = Vec::new();
+   for (i, line) in text.lines().enumerate() {
+      let _ = line.parse::<ElementSpec>();
+      if let Some(arrow) = line.find(" -> ") {
+         let _ = circuit.add_element(&line[arrow + 4..], &line[..arrow]);
+         names.push(&line[arrow + 4..]);
+      }
+      let _ = circuit.add_line(i + 1, line);
+   }
+   let _ = text.parse::<Circuit>();
+
+   // Neither kind of simulation can overflow, so both are safe to run on
+   // anything that loaded, and the step limit keeps a huge netlist from
+   // stalling the fuzzer.
+   circuit.set_limits(Limits{max_steps: Some(100000), timeout: None});
+   for name in names {
+      let _ = circuit.get_logic(name);
+      let _ = circuit.get_value(name);
+   }
+});
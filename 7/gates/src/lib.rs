@@ -0,0 +1,369 @@
+// The gates of day 7's circuits, and the signals that flow through them,
+// on their own without std so that they build for a board with nothing
+// but an allocator.  Everything else about circuits, their files, caches
+// and threads, is in the circuit crate, which uses these and the wires
+// module's simulate, so there's only ever the one evaluator.  Netlist is
+// just enough of a circuit on top of that to work the puzzle out here.
+#![no_std]
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+
+mod netlist;
+mod wires;
+
+pub use netlist::{puzzle, Netlist, NetlistError};
+pub use wires::{simulate, Names, Operand, Unsettled, Wiring};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Input {
+   Value(u16),
+   Element(String),
+   None,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidInput;
+
+impl fmt::Display for Input {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         Input::Value(v) => write!(f, "{}", v),
+         Input::Element(ref e) => write!(f, "{}", e),
+         Input::None => Ok(()),
+      }
+   }
+}
+
+impl From<u16> for Input {
+   fn from(v: u16) -> Input {
+      Input::Value(v)
+   }
+}
+
+// Always a wire, even for "123"; parse one instead to have constants read
+// as constants.  The circuit refuses wires with names like that.
+impl<'a> From<&'a str> for Input {
+   fn from(wire: &'a str) -> Input {
+      Input::Element(wire.to_string())
+   }
+}
+
+// A constant in decimal, or in hex or binary like 0xff or 0b1010, which
+// read better as masks.  None if s isn't a number at all, so it can be a
+// wire name; Some(Err) for a malformed hex or binary one like 0x1g.
+pub fn parse_literal(s: &str) -> Option<Result<u16, InvalidInput>> {
+   let (digits, radix) = if s.starts_with("0x") || s.starts_with("0X") {
+      (&s[2..], 16)
+   } else if s.starts_with("0b") || s.starts_with("0B") {
+      (&s[2..], 2)
+   } else {
+      return s.parse::<u16>().ok().map(Ok);
+   };
+   return Some(u16::from_str_radix(digits, radix).map_err(|_| InvalidInput));
+}
+
+impl FromStr for Input {
+   type Err = InvalidInput;
+   fn from_str(s: &str) -> Result<Input, InvalidInput> {
+      if s.is_empty() {
+         return Err(InvalidInput);
+      }
+      return match parse_literal(s) {
+         Some(v) => v.map(Input::Value),
+         None => Ok(Input::Element(s.to_string())),
+      };
+   }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Operation {
+   Value,
+   Not,
+   And,
+   Or,
+   LShift,
+   RShift,
+   Xor,
+   Nand,
+   Nor,
+   // Arithmetic, wrapping at 16 bits.
+   Add,
+   Sub,
+   Mul,
+}
+
+impl fmt::Display for Operation {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      let name = match *self {
+         Operation::Value => "VALUE",
+         Operation::Not => "NOT",
+         Operation::And => "AND",
+         Operation::Or => "OR",
+         Operation::LShift => "LSHIFT",
+         Operation::RShift => "RSHIFT",
+         Operation::Xor => "XOR",
+         Operation::Nand => "NAND",
+         Operation::Nor => "NOR",
+         Operation::Add => "ADD",
+         Operation::Sub => "SUB",
+         Operation::Mul => "MUL",
+      };
+      write!(f, "{}", name)
+   }
+}
+
+impl FromStr for Operation {
+   type Err = InvalidInput;
+   fn from_str(s: &str) -> Result<Operation, InvalidInput> {
+      return match s {
+         "OR" => Ok(Operation::Or),
+         "AND" => Ok(Operation::And),
+         "LSHIFT" => Ok(Operation::LShift),
+         "RSHIFT" => Ok(Operation::RShift),
+         "XOR" => Ok(Operation::Xor),
+         "NAND" => Ok(Operation::Nand),
+         "NOR" => Ok(Operation::Nor),
+         "ADD" => Ok(Operation::Add),
+         "SUB" => Ok(Operation::Sub),
+         "MUL" => Ok(Operation::Mul),
+         _ => Err(InvalidInput)
+      }
+   }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ElementSpec {
+   pub left: Input,
+   pub right: Input,
+   pub op: Operation,
+}
+
+impl FromStr for ElementSpec {
+   type Err = InvalidInput;
+   fn from_str(s: &str) -> Result<ElementSpec, InvalidInput> {
+      let parts = s.split_whitespace().collect::<Vec<_>>();
+
+      // Either passthru or Value
+      if parts.len() == 1 {
+         return Ok(ElementSpec{
+            left: parts[0].parse::<Input>()?,
+            right: Input::None,
+            op: Operation::Value,
+         });
+      } else if parts.len() == 2 && parts[0] == "NOT" {
+         return Ok(ElementSpec {
+            left: parts[1].parse::<Input>()?,
+            right: Input::None,
+            op: Operation::Not,
+         });
+      } else if parts.len() == 3 {
+         return Ok(ElementSpec {
+            left:  parts[0].parse::<Input>()?,
+            right:  parts[2].parse::<Input>()?,
+            op: parts[1].parse::<Operation>()?,
+         });
+      } else {
+         return Err(InvalidInput)
+      }
+   }
+}
+
+impl fmt::Display for ElementSpec {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match self.op {
+         Operation::Value => write!(f, "{}", self.left),
+         Operation::Not => write!(f, "NOT {}", self.left),
+         ref op => write!(f, "{} {} {}", self.left, op, self.right),
+      }
+   }
+}
+
+impl ElementSpec {
+   pub fn value(input: Input) -> ElementSpec {
+      ElementSpec{left: input, right: Input::None, op: Operation::Value}
+   }
+
+   // Names of the wires this spec reads from.
+   pub fn wires(&self) -> Vec<&str> {
+      let mut wires = Vec::new();
+      for input in &[&self.left, &self.right] {
+         if let &&Input::Element(ref e) = input {
+            wires.push(&e[..]);
+         }
+      }
+      return wires;
+   }
+
+   pub fn evaluate<T: Signal>(&self, left: T, right: T) -> T {
+      match self.op {
+         Operation::Value => left,
+         Operation::Not => left.not(),
+         Operation::And => left.and(right),
+         Operation::Or => left.or(right),
+         Operation::LShift => left.lshift(right),
+         Operation::RShift => left.rshift(right),
+         Operation::Xor => left.xor(right),
+         Operation::Nand => left.and(right).not(),
+         Operation::Nor => left.or(right).not(),
+         Operation::Add => left.add(right),
+         Operation::Sub => left.sub(right),
+         Operation::Mul => left.mul(right),
+      }
+   }
+}
+
+// Works for both plain u16 wires and four-valued simulation.
+pub trait Signal: Copy {
+   fn constant(v: u16) -> Self;
+   fn not(self) -> Self;
+   fn and(self, other: Self) -> Self;
+   fn or(self, other: Self) -> Self;
+   fn xor(self, other: Self) -> Self;
+   fn lshift(self, amount: Self) -> Self;
+   fn rshift(self, amount: Self) -> Self;
+   fn add(self, other: Self) -> Self;
+   fn sub(self, other: Self) -> Self;
+   fn mul(self, other: Self) -> Self;
+}
+
+impl Signal for u16 {
+   fn constant(v: u16) -> u16 {
+      return v;
+   }
+   fn not(self) -> u16 {
+      return !self;
+   }
+   fn and(self, other: u16) -> u16 {
+      return self & other;
+   }
+   fn or(self, other: u16) -> u16 {
+      return self | other;
+   }
+   fn xor(self, other: u16) -> u16 {
+      return self ^ other;
+   }
+   // Shifting by 16 or more pushes every bit out, like Logic does, rather
+   // than panicking or wrapping the amount around.
+   fn lshift(self, amount: u16) -> u16 {
+      return self.checked_shl(amount as u32).unwrap_or(0);
+   }
+   fn rshift(self, amount: u16) -> u16 {
+      return self.checked_shr(amount as u32).unwrap_or(0);
+   }
+   fn add(self, other: u16) -> u16 {
+      return self.wrapping_add(other);
+   }
+   fn sub(self, other: u16) -> u16 {
+      return self.wrapping_sub(other);
+   }
+   fn mul(self, other: u16) -> u16 {
+      return self.wrapping_mul(other);
+   }
+}
+
+// A 16 bit wire where each bit is 0, 1, X (unknown) or Z (undriven).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Logic {
+   // Bits that are a driven 0 or 1.
+   pub known: u16,
+   // The value of known bits.  Unknown bits are Z if set here, X otherwise.
+   pub bits: u16,
+}
+
+impl Logic {
+   pub fn undriven() -> Logic {
+      Logic{known: 0, bits: 0xffff}
+   }
+   fn ones(&self) -> u16 {
+      self.known & self.bits
+   }
+   fn zeros(&self) -> u16 {
+      self.known & !self.bits
+   }
+   // Gate outputs are always driven, so anything not known is X.
+   fn from_masks(ones: u16, zeros: u16) -> Logic {
+      Logic{known: ones | zeros, bits: ones}
+   }
+   // Shifting by an amount that isn't fully known could land anywhere.
+   fn shift_amount(amount: Logic) -> Option<u32> {
+      if amount.known == 0xffff { Some(amount.bits as u32) } else { None }
+   }
+   // Carries can spread an unknown bit anywhere, so arithmetic is only
+   // known when both sides are.
+   fn arithmetic<F: Fn(u16, u16) -> u16>(self, other: Logic, f: F) -> Logic {
+      if self.known == 0xffff && other.known == 0xffff {
+         Logic::constant(f(self.bits, other.bits))
+      } else {
+         Logic::from_masks(0, 0)
+      }
+   }
+}
+
+impl Signal for Logic {
+   fn constant(v: u16) -> Logic {
+      return Logic{known: 0xffff, bits: v};
+   }
+   fn not(self) -> Logic {
+      return Logic::from_masks(self.zeros(), self.ones());
+   }
+   fn and(self, other: Logic) -> Logic {
+      return Logic::from_masks(self.ones() & other.ones(), self.zeros() | other.zeros());
+   }
+   fn or(self, other: Logic) -> Logic {
+      return Logic::from_masks(self.ones() | other.ones(), self.zeros() & other.zeros());
+   }
+   fn xor(self, other: Logic) -> Logic {
+      let known = self.known & other.known;
+      return Logic::from_masks((self.bits ^ other.bits) & known, !(self.bits ^ other.bits) & known);
+   }
+   fn lshift(self, amount: Logic) -> Logic {
+      return match Logic::shift_amount(amount) {
+         Some(n) if n < 16 => Logic::from_masks(self.ones() << n, (self.zeros() << n) | ((1 << n) - 1)),
+         Some(_) => Logic::constant(0),
+         None => Logic::from_masks(0, 0),
+      };
+   }
+   fn rshift(self, amount: Logic) -> Logic {
+      return match Logic::shift_amount(amount) {
+         Some(n) if n < 16 => Logic::from_masks(self.ones() >> n, (self.zeros() >> n) | !(0xffff >> n)),
+         Some(_) => Logic::constant(0),
+         None => Logic::from_masks(0, 0),
+      };
+   }
+   fn add(self, other: Logic) -> Logic {
+      return self.arithmetic(other, u16::wrapping_add);
+   }
+   fn sub(self, other: Logic) -> Logic {
+      return self.arithmetic(other, u16::wrapping_sub);
+   }
+   fn mul(self, other: Logic) -> Logic {
+      return self.arithmetic(other, u16::wrapping_mul);
+   }
+}
+
+impl fmt::Display for Logic {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      for i in (0..16).rev() {
+         let bit = 1 << i;
+         let c = match (self.known & bit != 0, self.bits & bit != 0) {
+            (true, false) => '0',
+            (true, true) => '1',
+            (false, false) => 'x',
+            (false, true) => 'z',
+         };
+         write!(f, "{}", c)?;
+      }
+      Ok(())
+   }
+}
@@ -0,0 +1,116 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use wires::{simulate, Names, Operand, Unsettled, Wiring};
+use {ElementSpec, Input};
+
+// A netlist just as the puzzle writes them, a `x AND y -> z` a line, and
+// none of what the circuit crate adds to that: comments, buses, modules,
+// infix expressions or caching.  It's worked out by the same simulate as
+// the circuit's.
+#[derive(Debug, Clone, Default)]
+pub struct Netlist {
+   names: Names,
+   // The gate driving each wire, by id, if anything does.
+   gates: Vec<Option<(ElementSpec, [Operand; 2])>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NetlistError {
+   // A line that isn't `spec -> wire`, numbered from 1.
+   Parse(usize),
+   Undefined(String),
+   // A wire that its own value depends on.
+   Cycle(String),
+}
+
+impl fmt::Display for NetlistError {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         NetlistError::Parse(line) => write!(f, "line {}: expected something like `x AND y -> z`", line),
+         NetlistError::Undefined(ref wire) => write!(f, "wire {} is never driven", wire),
+         NetlistError::Cycle(ref wire) => write!(f, "wire {} depends on itself", wire),
+      }
+   }
+}
+
+impl Wiring for Netlist {
+   fn gate(&self, id: usize) -> Option<(&ElementSpec, [Operand; 2])> {
+      return self.gates[id].as_ref().map(|&(ref spec, operands)| (spec, operands));
+   }
+}
+
+impl Netlist {
+   pub fn parse(text: &str) -> Result<Netlist, NetlistError> {
+      let mut netlist = Netlist::default();
+      for (i, line) in text.lines().enumerate().filter(|&(_, l)| !l.trim().is_empty()) {
+         let parts: Vec<&str> = line.split(" -> ").collect();
+         let spec = match parts[..] {
+            [spec, wire] if !wire.trim().is_empty() => spec.parse::<ElementSpec>().map(|spec| (wire.trim(), spec)),
+            _ => Err(::InvalidInput),
+         };
+         let (wire, spec) = spec.map_err(|_| NetlistError::Parse(i + 1))?;
+         netlist.set(wire, spec);
+      }
+      return Ok(netlist);
+   }
+
+   fn intern(&mut self, name: &str) -> usize {
+      let id = self.names.intern(name);
+      if id == self.gates.len() {
+         self.gates.push(None);
+      }
+      return id;
+   }
+
+   fn operand(&mut self, input: &Input) -> Operand {
+      return match *input {
+         Input::Value(v) => Operand::Value(v),
+         Input::Element(ref e) => Operand::Wire(self.intern(e)),
+         Input::None => Operand::Value(0),
+      };
+   }
+
+   // Drives wire with spec instead of whatever drove it before.
+   pub fn set(&mut self, wire: &str, spec: ElementSpec) {
+      let id = self.intern(wire);
+      let operands = [self.operand(&spec.left), self.operand(&spec.right)];
+      self.gates[id] = Some((spec, operands));
+   }
+
+   // What's on wire, working out everything it depends on as it goes.
+   pub fn value(&self, wire: &str) -> Result<u16, NetlistError> {
+      let id = match self.names.id(wire) {
+         Some(id) if self.gates[id].is_some() => id,
+         _ => return Err(NetlistError::Undefined(wire.to_string())),
+      };
+      let order = simulate(self, self.names.len(), &[id], |_| None, None::<u16>, || Ok::<(), ()>(()));
+      return match order {
+         Ok(order) => Ok(order.last().unwrap().1),
+         Err(Unsettled::Undefined(w)) => Err(NetlistError::Undefined(self.names[w].clone())),
+         Err(Unsettled::Cycle(wires)) => Err(NetlistError::Cycle(self.names[wires[0]].clone())),
+         Err(Unsettled::Stopped(())) => unreachable!(),
+      };
+   }
+}
+
+// Both parts of the puzzle: the signal on a, and then what it is with b
+// driven by that instead.
+pub fn puzzle(text: &str) -> Result<(u16, u16), NetlistError> {
+   let mut netlist = Netlist::parse(text)?;
+   let a = netlist.value("a")?;
+   netlist.set("b", ElementSpec::value(Input::Value(a)));
+   return Ok((a, netlist.value("a")?));
+}
+
+#[test]
+fn test_netlist() {
+   let netlist = Netlist::parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i\n").unwrap();
+   let values: Vec<u16> = ["d", "e", "f", "g", "h", "i", "x", "y"].iter().map(|w| netlist.value(w).unwrap()).collect();
+   assert_eq!(values, [72, 507, 492, 114, 65412, 65079, 123, 456]);
+   assert_eq!(netlist.value("z"), Err(NetlistError::Undefined("z".to_string())));
+   assert_eq!(Netlist::parse("1 -> a\nx FROB y -> b\n").unwrap_err(), NetlistError::Parse(2));
+   let cycle = Netlist::parse("b -> a\nNOT a -> c\nc AND a -> b\n").unwrap();
+   assert!(matches!(cycle.value("a"), Err(NetlistError::Cycle(_))));
+   assert_eq!(puzzle(include_str!("../../input.txt")), Ok((46065, 14134)));
+}
@@ -0,0 +1,181 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Index;
+use {ElementSpec, Signal};
+
+// Every wire name that's been mentioned, each with an id counting up from
+// 0, so that evaluation can index by id rather than look names up.  It's
+// serialized as just the names, in id order.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<String>", into = "Vec<String>"))]
+pub struct Names {
+   names: Vec<String>,
+   ids: BTreeMap<String, usize>,
+}
+
+impl Names {
+   // name's id, giving it the next one if it hasn't got one yet.
+   pub fn intern(&mut self, name: &str) -> usize {
+      if let Some(&id) = self.ids.get(name) {
+         return id;
+      }
+      let id = self.names.len();
+      self.names.push(name.to_string());
+      self.ids.insert(name.to_string(), id);
+      return id;
+   }
+
+   pub fn id(&self, name: &str) -> Option<usize> {
+      return self.ids.get(name).cloned();
+   }
+
+   pub fn len(&self) -> usize {
+      return self.names.len();
+   }
+
+   pub fn is_empty(&self) -> bool {
+      return self.names.is_empty();
+   }
+}
+
+impl Index<usize> for Names {
+   type Output = String;
+   fn index(&self, id: usize) -> &String {
+      return &self.names[id];
+   }
+}
+
+impl From<Vec<String>> for Names {
+   fn from(names: Vec<String>) -> Names {
+      let ids = names.iter().cloned().enumerate().map(|(id, name)| (name, id)).collect();
+      return Names{names: names, ids: ids};
+   }
+}
+
+impl From<Names> for Vec<String> {
+   fn from(names: Names) -> Vec<String> {
+      return names.names;
+   }
+}
+
+// Where one of a gate's inputs comes from, with wires already looked up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operand {
+   Value(u16),
+   Wire(usize),
+}
+
+impl Default for Operand {
+   fn default() -> Operand {
+      Operand::Value(0)
+   }
+}
+
+impl Operand {
+   pub fn wire(self) -> Option<usize> {
+      return match self {
+         Operand::Wire(w) => Some(w),
+         Operand::Value(_) => None,
+      };
+   }
+}
+
+// Wires by id, as simulate sees them: each is driven by a gate with its
+// operands looked up, or by nothing at all.
+pub trait Wiring {
+   fn gate(&self, id: usize) -> Option<(&ElementSpec, [Operand; 2])>;
+}
+
+// Why simulate couldn't settle everything it was asked for.
+#[derive(Debug, PartialEq)]
+pub enum Unsettled<E> {
+   // The wires that lead from one back round to itself, by id, with the
+   // first one again at the end.
+   Cycle(Vec<usize>),
+   // A wire that's read but never driven.
+   Undefined(usize),
+   // Whatever step gave up with.
+   Stopped(E),
+}
+
+// Works out the value of each of roots and everything they depend on,
+// returning the newly settled wires in the order they were computed.
+// Wires for which `cached` returns a value aren't recomputed, and wires
+// shared between roots are only computed once.  Undriven wires read as
+// `undefined`, or are an error if that's None.  step is called for every
+// wire visited, and can stop the whole thing by failing.
+//
+// Resolution is a depth-first walk driven by an explicit stack rather
+// than recursion, so arbitrarily long chains of wires can't overflow, not
+// even a board's own small stack.  stack holds the wires currently being
+// resolved, outermost first; seeing a wire that's already on it means
+// we've looped back on ourselves.
+pub fn simulate<W, T, C, S, E>(wiring: &W, wires: usize, roots: &[usize], cached: C, undefined: Option<T>, mut step: S)
+   -> Result<Vec<(usize, T)>, Unsettled<E>>
+   where W: Wiring, T: Signal, C: Fn(usize) -> Option<T>, S: FnMut() -> Result<(), E> {
+   let mut settled: Vec<Option<T>> = vec![None; wires];
+   let mut order = Vec::new();
+   let mut roots = roots.iter().rev().cloned().collect::<Vec<_>>();
+   let mut stack = Vec::new();
+   let mut on_stack = vec![false; wires];
+   // Value of an operand, if its wire (if any) has been settled.
+   let signal = |operand: Operand, settled: &[Option<T>]| match operand {
+      Operand::Value(v) => Ok(Some(T::constant(v))),
+      Operand::Wire(w) => match wiring.gate(w) {
+         Some(_) => Ok(settled[w].or_else(|| cached(w))),
+         None => undefined.map(Some).ok_or(Unsettled::Undefined(w)),
+      },
+   };
+   while let Some(top) = stack.last().cloned().or_else(|| roots.pop()) {
+      step().map_err(Unsettled::Stopped)?;
+      if stack.is_empty() {
+         stack.push(top);
+         on_stack[top] = true;
+      }
+      let (spec, [left_operand, right_operand]) = match wiring.gate(top) {
+         Some(gate) if settled[top].is_none() && cached(top).is_none() => gate,
+         _ => {
+            on_stack[top] = false;
+            stack.pop();
+            continue;
+         }
+      };
+      let left = signal(left_operand, &settled)?;
+      let right = signal(right_operand, &settled)?;
+      if let (Some(l), Some(r)) = (left, right) {
+         let v = spec.evaluate(l, r);
+         settled[top] = Some(v);
+         order.push((top, v));
+         on_stack[top] = false;
+         stack.pop();
+         continue;
+      }
+
+      let next = match (left.is_none(), left_operand, right_operand) {
+         (true, Operand::Wire(w), _) => w,
+         (false, _, Operand::Wire(w)) => w,
+         _ => unreachable!(),
+      };
+      if on_stack[next] {
+         let start = stack.iter().position(|&p| p == next).unwrap();
+         let mut cycle = stack[start..].to_vec();
+         cycle.push(next);
+         return Err(Unsettled::Cycle(cycle));
+      }
+      on_stack[next] = true;
+      stack.push(next);
+   }
+   return Ok(order);
+}
+
+#[test]
+fn test_names() {
+   let mut names = Names::default();
+   assert_eq!((names.intern("b"), names.intern("a"), names.intern("b")), (0, 1, 0));
+   assert_eq!((names.id("a"), names.id("c")), (Some(1), None));
+   assert_eq!(&names[0], "b");
+   let names = Names::from(Vec::from(names));
+   assert_eq!((names.len(), names.id("a")), (2, Some(1)));
+}
@@ -0,0 +1,65 @@
+#ifndef CIRCUIT_H
+#define CIRCUIT_H
+
+/* Generated by cbindgen from src/ffi.rs; don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct circuit circuit;
+
+/*
+ Makes an empty circuit, to be released with circuit_free.
+ */
+circuit *circuit_new(void);
+
+/*
+ Adds one netlist line, like "x AND y -> d".
+
+ # Safety
+
+ circuit must be non-null, from circuit_new, and not yet given to
+ circuit_free.  line must be non-null and NUL-terminated, and fails
+ unless it's UTF-8.  Null pointers fail with -1; any other bad pointer
+ is undefined behaviour.
+ */
+int circuit_add_line(circuit *circuit, const char *line);
+
+/*
+ Resolves wire, storing its value in *value.
+
+ # Safety
+
+ circuit must be non-null, from circuit_new, and not yet given to
+ circuit_free.  wire must be non-null and NUL-terminated, and fails
+ unless it's UTF-8.  value must be non-null and point to a uint16_t
+ that can be written.  Null pointers fail with -1; any other bad
+ pointer is undefined behaviour.
+ */
+int circuit_get_value(circuit *circuit, const char *wire, uint16_t *value);
+
+/*
+ Pins a defined wire to value.
+
+ # Safety
+
+ circuit must be non-null, from circuit_new, and not yet given to
+ circuit_free.  wire must be non-null and NUL-terminated, and fails
+ unless it's UTF-8.  Null pointers fail with -1; any other bad pointer
+ is undefined behaviour.
+ */
+int circuit_force(circuit *circuit, const char *wire, uint16_t value);
+
+/*
+ Releases a circuit from circuit_new.  Null is ignored.
+
+ # Safety
+
+ circuit must be null or from circuit_new, and not already freed.  It
+ can't be used again afterwards.
+ */
+void circuit_free(circuit *circuit);
+
+#endif  /* CIRCUIT_H */
@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+use {Circuit, CircuitError};
+
+// How many steps go by between looks at the clock, which is far slower
+// than a step.
+const STEPS_PER_CLOCK_CHECK: usize = 256;
+
+// Caps on how much work a single evaluation (a get_value, get_logic,
+// settle, par_resolve_all, resolve_by_level, precompute_constants and so
+// on) may do before giving up, for netlists that are pathologically large
+// or never settle.  A step is one visit to a wire while resolving, or one
+// gate evaluated while settling, resolving by level or precomputing.
+// Unlimited by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+   pub max_steps: Option<usize>,
+   pub timeout: Option<Duration>,
+}
+
+// Limits being spent by one evaluation.
+pub struct Budget {
+   limits: Limits,
+   steps: usize,
+   // Only read when there's a timeout, so evaluating without one never
+   // touches the clock (which isn't there at all on wasm).
+   started: Option<Instant>,
+}
+
+impl Budget {
+   pub fn start(limits: Limits) -> Budget {
+      Budget{limits: limits, steps: 0, started: limits.timeout.map(|_| Instant::now())}
+   }
+
+   pub fn step(&mut self) -> Result<(), CircuitError> {
+      self.steps += 1;
+      if let Some(max) = self.limits.max_steps {
+         if self.steps > max {
+            return Err(CircuitError::OutOfSteps(max));
+         }
+      }
+      if let (Some(timeout), Some(started)) = (self.limits.timeout, self.started) {
+         if self.steps.is_multiple_of(STEPS_PER_CLOCK_CHECK) && started.elapsed() > timeout {
+            return Err(CircuitError::TimedOut(timeout));
+         }
+      }
+      return Ok(());
+   }
+}
+
+impl Circuit {
+   pub fn limits(&self) -> Limits {
+      self.limits
+   }
+
+   // Limits every evaluation from now on; Limits::default() lifts them.
+   // A get_value that's cut short caches nothing, so it can just be asked
+   // again with more room; settle, the level-by-level resolves and
+   // precompute_constants keep whatever they had got to.
+   pub fn set_limits(&mut self, limits: Limits) {
+      self.limits = limits;
+   }
+}
+
+#[test]
+fn test_step_limit() {
+   // A chain of 100 gates, waa through wdv.
+   let name = |i: usize| format!("w{}{}", (b'a' + (i / 26) as u8) as char, (b'a' + (i % 26) as u8) as char);
+   let mut circuit = Circuit::new();
+   circuit.add_element(&name(0), "1").unwrap();
+   for i in 1..100 {
+      circuit.add_element(&name(i), &format!("{} AND 1", name(i - 1))).unwrap();
+   }
+   circuit.set_limits(Limits{max_steps: Some(50), timeout: None});
+   assert_eq!(circuit.get_value(&name(99)), Err(CircuitError::OutOfSteps(50)));
+   assert!(circuit.element(&name(0)).unwrap().value.is_none());
+
+   // Resolving a level at a time, in parallel or not, and precomputing
+   // constants count a step for each wire too, and keep what they got to.
+   assert_eq!(circuit.par_resolve_all(), Err(CircuitError::OutOfSteps(50)));
+   assert!(circuit.element(&name(49)).unwrap().value.is_some());
+   assert!(circuit.element(&name(50)).unwrap().value.is_none());
+   circuit.clear_cache();
+   assert_eq!(circuit.resolve_by_level(|_, _| ()), Err(CircuitError::OutOfSteps(50)));
+   circuit.clear_cache();
+   assert_eq!(circuit.precompute_constants(), Err(CircuitError::OutOfSteps(50)));
+   assert!(circuit.element(&name(49)).unwrap().value.is_some());
+   circuit.clear_cache();
+
+   circuit.set_limits(Limits::default());
+   assert_eq!(circuit.get_value(&name(99)), Ok(1));
+   // Cached values cost nothing to look up again.
+   circuit.set_limits(Limits{max_steps: Some(1), timeout: None});
+   assert_eq!(circuit.get_value(&name(99)), Ok(1));
+}
+
+#[test]
+fn test_timeout() {
+   let mut circuit: Circuit = "NOT x -> x\n1 -> y".parse().unwrap();
+   circuit.set_limits(Limits{max_steps: None, timeout: Some(Duration::from_millis(20))});
+   let err = circuit.settle(&[], usize::MAX).unwrap_err();
+   assert_eq!(err, CircuitError::TimedOut(Duration::from_millis(20)));
+   assert_eq!(err.to_string(), "gave up after 20ms");
+   assert_eq!(circuit.get_value("y"), Ok(1));
+}
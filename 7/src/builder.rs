@@ -0,0 +1,161 @@
+use {valid_name, Circuit, ElementSpec, Input, Operation};
+
+// Builds circuits without going through the netlist parser:
+//
+//    let mut builder = CircuitBuilder::new();
+//    builder.wire("x").value(123);
+//    builder.wire("d").and("x", "y");
+//    builder.wire("f").lshift("x", 2);
+//    let circuit = builder.build();
+//
+// Inputs are anything that converts into an Input, so a &str names a wire
+// and a u16 is a constant.  A &str that reads as a constant, like "123",
+// is no more a wire name here than in a netlist, and panics along with
+// any other name a netlist couldn't have.
+pub struct CircuitBuilder {
+   circuit: Circuit,
+}
+
+impl Default for CircuitBuilder {
+   fn default() -> CircuitBuilder {
+      return CircuitBuilder::new();
+   }
+}
+
+impl CircuitBuilder {
+   pub fn new() -> CircuitBuilder {
+      CircuitBuilder{circuit: Circuit::new()}
+   }
+
+   // Starts defining name.  Panics if the name couldn't appear in a
+   // netlist: it's empty, has a space in it or is a number.
+   pub fn wire<'a>(&'a mut self, name: &str) -> WireBuilder<'a> {
+      assert!(valid_name(name), "invalid wire name {:?}", name);
+      WireBuilder{circuit: &mut self.circuit, name: name.to_string()}
+   }
+
+   pub fn build(self) -> Circuit {
+      self.circuit
+   }
+}
+
+// The definition of one wire; consumed by picking what drives it.
+pub struct WireBuilder<'a> {
+   circuit: &'a mut Circuit,
+   name: String,
+}
+
+impl<'a> WireBuilder<'a> {
+   fn define(self, op: Operation, left: Input, right: Input) {
+      let spec = ElementSpec{left: left, right: right, op: op};
+      for wire in spec.wires() {
+         assert!(valid_name(wire), "invalid wire name {:?}", wire);
+      }
+      self.circuit.add_spec(&self.name, spec).unwrap();
+   }
+
+   pub fn value(self, v: u16) {
+      self.define(Operation::Value, Input::Value(v), Input::None)
+   }
+
+   // A passthrough from another wire (or a constant).
+   pub fn from<I: Into<Input>>(self, input: I) {
+      self.define(Operation::Value, input.into(), Input::None)
+   }
+
+   pub fn not<I: Into<Input>>(self, input: I) {
+      self.define(Operation::Not, input.into(), Input::None)
+   }
+
+   pub fn and<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::And, left.into(), right.into())
+   }
+
+   pub fn or<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::Or, left.into(), right.into())
+   }
+
+   pub fn xor<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::Xor, left.into(), right.into())
+   }
+
+   pub fn nand<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::Nand, left.into(), right.into())
+   }
+
+   pub fn nor<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::Nor, left.into(), right.into())
+   }
+
+   pub fn add<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::Add, left.into(), right.into())
+   }
+
+   pub fn sub<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::Sub, left.into(), right.into())
+   }
+
+   pub fn mul<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::Mul, left.into(), right.into())
+   }
+
+   pub fn lshift<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::LShift, left.into(), right.into())
+   }
+
+   pub fn rshift<L: Into<Input>, R: Into<Input>>(self, left: L, right: R) {
+      self.define(Operation::RShift, left.into(), right.into())
+   }
+}
+
+#[test]
+fn test_builder() {
+   let mut builder = CircuitBuilder::new();
+   builder.wire("x").value(123);
+   builder.wire("y").value(456);
+   builder.wire("d").and("x", "y");
+   builder.wire("e").or("x", "y");
+   builder.wire("f").lshift("x", 2);
+   builder.wire("g").rshift("y", 2);
+   builder.wire("h").not("x");
+   builder.wire("i").nor("y", 0);
+   builder.wire("j").from("d");
+   let mut circuit = builder.build();
+
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+   assert_eq!(circuit.get_value("e").unwrap(), 507);
+   assert_eq!(circuit.get_value("f").unwrap(), 492);
+   assert_eq!(circuit.get_value("g").unwrap(), 114);
+   assert_eq!(circuit.get_value("h").unwrap(), 65412);
+   assert_eq!(circuit.get_value("i").unwrap(), 65079);
+   assert_eq!(circuit.get_value("j").unwrap(), 72);
+   assert_eq!(circuit.element("f").unwrap().spec, "x LSHIFT 2".parse::<ElementSpec>().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "invalid wire name")]
+fn test_builder_bad_name() {
+   CircuitBuilder::new().wire("a b").value(1);
+}
+
+#[test]
+#[should_panic(expected = "invalid wire name \"123\"")]
+fn test_builder_number_operand() {
+   // Numbers are constants, so they can't be wires; 123 as a u16 is.
+   CircuitBuilder::new().wire("d").and("x", "123");
+}
+
+#[test]
+#[should_panic(expected = "invalid wire name \"0x1f\"")]
+fn test_builder_number_name() {
+   CircuitBuilder::new().wire("0x1f").value(1);
+}
+
+#[test]
+fn test_bad_operand_names() {
+   let mut circuit = Circuit::new();
+   assert!(circuit.add_spec("d", ElementSpec{left: Input::from("x"), right: Input::from("a b"), op: Operation::Or}).is_err());
+   assert!(circuit.add_spec("d", ElementSpec::value(Input::from("0b1"))).is_err());
+   assert!(circuit.add_element("12", "x").is_err());
+   assert!(circuit.is_empty());
+}
@@ -0,0 +1,134 @@
+use InvalidInput;
+
+// Wider than any bus anyone will write by hand, but small enough that a
+// typo like `x[65535:0]` can't eat all the memory.
+const MAX_BUS_WIDTH: usize = 1024;
+
+// The lanes a wire reference stands for, most significant first: `x[7:4]`
+// is x[7], x[6], x[5], x[4], and `x[2]` is just itself.  Lanes are plain
+// wires named like `x[2]`.  Concatenations like `{x[1:0], y, 5}` can mix
+// in plain wires and constants as single lanes.  None for something that
+// isn't a bus at all.
+pub fn lanes(token: &str) -> Result<Option<Vec<String>>, InvalidInput> {
+   if token.starts_with('{') {
+      if !token.ends_with('}') || token.len() < 2 {
+         return Err(InvalidInput);
+      }
+      let mut all = Vec::new();
+      for part in token[1..token.len() - 1].split(',') {
+         match lanes(part)? {
+            Some(part) => all.extend(part),
+            None if !part.is_empty() => all.push(part.to_string()),
+            None => return Err(InvalidInput),
+         }
+      }
+      if all.len() > MAX_BUS_WIDTH {
+         return Err(InvalidInput);
+      }
+      return Ok(Some(all));
+   }
+   let open = match token.find('[') {
+      Some(open) if open > 0 && token.ends_with(']') => open,
+      Some(_) => return Err(InvalidInput),
+      None if token.contains(']') => return Err(InvalidInput),
+      None => return Ok(None),
+   };
+   let (name, range) = (&token[..open], &token[open + 1..token.len() - 1]);
+   let bounds: Vec<&str> = range.split(':').collect();
+   let parse = |b: &str| b.parse::<usize>().map_err(|_| InvalidInput);
+   let (first, last) = match bounds.len() {
+      1 => (parse(bounds[0])?, parse(bounds[0])?),
+      2 => (parse(bounds[0])?, parse(bounds[1])?),
+      _ => return Err(InvalidInput),
+   };
+   if first.max(last) - first.min(last) >= MAX_BUS_WIDTH {
+      return Err(InvalidInput);
+   }
+   let lane = |i: usize| format!("{}[{}]", name, i);
+   return Ok(Some(if first >= last {
+      (last..first + 1).rev().map(lane).collect()
+   } else {
+      (first..last + 1).map(lane).collect()
+   }));
+}
+
+// Splits spec into tokens, keeping each `{...}` concatenation together.
+pub fn tokens(spec: &str) -> Vec<String> {
+   let mut out = String::new();
+   let mut braces = 0i32;
+   for c in spec.chars() {
+      match c {
+         '{' => braces += 1,
+         '}' => braces -= 1,
+         _ => (),
+      }
+      match c {
+         ' ' | '\t' if braces > 0 => (),
+         '(' | ')' if braces == 0 => {
+            out.push(' ');
+            out.push(c);
+            out.push(' ');
+         }
+         _ => out.push(c),
+      }
+   }
+   return out.split_whitespace().map(|t| t.to_string()).collect();
+}
+
+// Expands a definition involving buses into one definition per lane, so
+// `x[7:0] AND y[7:0] -> z[7:0]` becomes `x[7] AND y[7] -> z[7]` and so on.
+// Every bus in a definition must be as wide as the target; plain wires and
+// constants are used as-is in every lane.  Slicing like `x[7:4]` and
+// concatenation like `{x[3:0], y[3:0]}` give buses made of other buses'
+// lanes.  Definitions without any buses come back unchanged.
+pub fn expand(name: &str, spec: &str) -> Result<Vec<(String, String)>, InvalidInput> {
+   if !name.contains(['[', '{']) && !spec.contains(['[', '{']) {
+      return Ok(vec![(name.to_string(), spec.to_string())]);
+   }
+   // Wires can be sliced into, but not concatenated into.
+   if name.starts_with('{') {
+      return Err(InvalidInput);
+   }
+   let targets = lanes(name)?.unwrap_or(vec![name.to_string()]);
+   let tokens = tokens(spec);
+   let mut expanded = Vec::with_capacity(tokens.len());
+   for token in &tokens {
+      match lanes(token)? {
+         Some(ref l) if l.len() != targets.len() => return Err(InvalidInput),
+         l => expanded.push(l),
+      }
+   }
+   return Ok(targets.iter().enumerate().map(|(i, target)| {
+      let spec: Vec<&str> = tokens.iter().zip(&expanded).map(|(t, l)| match *l {
+         Some(ref l) => &l[i][..],
+         None => &t[..],
+      }).collect();
+      (target.clone(), spec.join(" "))
+   }).collect());
+}
+
+#[test]
+fn test_lanes() {
+   assert_eq!(lanes("x").unwrap(), None);
+   assert_eq!(lanes("x[2]").unwrap(), Some(vec!["x[2]".to_string()]));
+   assert_eq!(lanes("x[2:0]").unwrap().unwrap(), vec!["x[2]", "x[1]", "x[0]"]);
+   assert_eq!(lanes("x[0:1]").unwrap().unwrap(), vec!["x[0]", "x[1]"]);
+   assert_eq!(lanes("{x[1:0],y,z[3]}").unwrap().unwrap(), vec!["x[1]", "x[0]", "y", "z[3]"]);
+   for bad in &["x[", "x]", "[3]", "x[a]", "x[1:2:3]", "x[100000:0]", "{x", "{x,}", "{}"] {
+      assert!(lanes(bad).is_err(), "{:?}", bad);
+   }
+}
+
+#[test]
+fn test_expand() {
+   assert_eq!(expand("a", "x AND y").unwrap(), vec![("a".to_string(), "x AND y".to_string())]);
+   assert_eq!(expand("z[1:0]", "(x[3:2] AND (NOT m)) OR 1").unwrap(),
+              vec![("z[1]".to_string(), "( x[3] AND ( NOT m ) ) OR 1".to_string()),
+                   ("z[0]".to_string(), "( x[2] AND ( NOT m ) ) OR 1".to_string())]);
+   assert_eq!(expand("c[2:0]", "{a[1:0], b}").unwrap(),
+              vec![("c[2]".to_string(), "a[1]".to_string()),
+                   ("c[1]".to_string(), "a[0]".to_string()),
+                   ("c[0]".to_string(), "b".to_string())]);
+   assert!(expand("z[1:0]", "x[2:0]").is_err());
+   assert!(expand("{a, b}", "x[1:0]").is_err());
+}
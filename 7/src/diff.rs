@@ -0,0 +1,85 @@
+use std::fmt;
+use {Circuit, ElementSpec};
+
+// How one netlist's definitions differ from another's, from Circuit::diff.
+// Everything is sorted by wire name.
+#[derive(Debug, PartialEq)]
+pub struct CircuitDiff {
+   pub added: Vec<(String, ElementSpec)>,
+   pub removed: Vec<(String, ElementSpec)>,
+   // Wires defined in both, with their old and new specs.
+   pub changed: Vec<(String, ElementSpec, ElementSpec)>,
+}
+
+impl CircuitDiff {
+   pub fn is_empty(&self) -> bool {
+      self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+   }
+}
+
+impl fmt::Display for CircuitDiff {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      for &(ref name, ref spec) in &self.removed {
+         writeln!(f, "- {} -> {}", spec, name)?;
+      }
+      for &(ref name, ref spec) in &self.added {
+         writeln!(f, "+ {} -> {}", spec, name)?;
+      }
+      for &(ref name, ref old, ref new) in &self.changed {
+         writeln!(f, "~ {}: {} => {}", name, old, new)?;
+      }
+      Ok(())
+   }
+}
+
+impl Circuit {
+   // The definitions added, removed and changed going from this circuit to
+   // other.
+   pub fn diff(&self, other: &Circuit) -> CircuitDiff {
+      let mut diff = CircuitDiff{added: Vec::new(), removed: Vec::new(), changed: Vec::new()};
+      for name in self.sorted_names() {
+         let old = &self.element(name).unwrap().spec;
+         match other.element(name) {
+            None => diff.removed.push((name.to_string(), old.clone())),
+            Some(e) if e.spec != *old => diff.changed.push((name.to_string(), old.clone(), e.spec.clone())),
+            Some(_) => (),
+         }
+      }
+      for name in other.sorted_names() {
+         if self.element(name).is_none() {
+            diff.added.push((name.to_string(), other.element(name).unwrap().spec.clone()));
+         }
+      }
+      return diff;
+   }
+
+   // Wires defined in both circuits whose values come out differently,
+   // with their values here and in other.  A wire that can't be resolved,
+   // because of a loop or an undefined input, has no value.
+   pub fn value_changes(&mut self, other: &mut Circuit) -> Vec<(String, Option<u16>, Option<u16>)> {
+      let names: Vec<String> = self.sorted_names().iter().map(|n| n.to_string()).collect();
+      let mut changes = Vec::new();
+      for name in names {
+         if other.element(&name).is_none() {
+            continue;
+         }
+         let (old, new) = (self.get_value(&name).ok(), other.get_value(&name).ok());
+         if old != new {
+            changes.push((name, old, new));
+         }
+      }
+      return changes;
+   }
+}
+
+#[test]
+fn test_diff() {
+   let mut old = "123 -> x\n456 -> y\nx AND y -> d\nNOT d -> e\nx OR y -> f".parse::<Circuit>().unwrap();
+   let mut new = "123 -> x\n456 -> y\nx OR y -> d\nNOT d -> e\ny LSHIFT 2 -> g".parse::<Circuit>().unwrap();
+   let diff = old.diff(&new);
+   assert_eq!(diff.to_string(), "- x OR y -> f\n+ y LSHIFT 2 -> g\n~ d: x AND y => x OR y\n");
+   assert!(old.diff(&old.clone()).is_empty());
+
+   assert_eq!(old.value_changes(&mut new), vec![("d".to_string(), Some(72), Some(507)),
+                                                ("e".to_string(), Some(65463), Some(65028))]);
+}
@@ -0,0 +1,135 @@
+use budget::Budget;
+use {Circuit, CircuitError, Operand};
+
+impl Circuit {
+   // An event-driven alternative to get_value that copes with feedback,
+   // like an SR latch made of two NOR gates.  Rather than resolving wires
+   // in dependency order, wires are evaluated from whatever they currently
+   // hold (0 if they've never been resolved), and every wire whose value
+   // changes schedules the gates it feeds for the next round, until a round
+   // changes nothing.  Within a round each gate sees the latest values, so
+   // cross-coupled gates settle rather than chasing each other.
+   //
+   // inputs are forced first, like force_value, but without forgetting the
+   // values downstream of them, so a latch keeps its state between calls
+   // and only what the new inputs disturb gets re-evaluated.  Returns how
+   // many rounds it took, or Unsettled if still going after max_rounds,
+   // which is what an oscillator like `NOT x -> x` does.
+   pub fn settle(&mut self, inputs: &[(&str, u16)], max_rounds: usize) -> Result<usize, CircuitError> {
+      let mut values: Vec<u16> = self.elements.iter()
+         .map(|e| e.as_ref().and_then(|e| e.value).unwrap_or(0)).collect();
+      let mut scheduled = vec![false; self.elements.len()];
+      let mut round: Vec<usize> = self.defined().into_iter()
+         .filter(|id| self.elements[*id].as_ref().unwrap().value.is_none()).collect();
+      for &id in &round {
+         scheduled[id] = true;
+      }
+      for &(name, v) in inputs {
+         let id = match self.id(name) {
+            Some(id) if self.elements[id].is_some() => id,
+            _ => return Err(CircuitError::Undefined(name.to_string())),
+         };
+         if values[id] != v {
+            for &d in &self.dependents[id] {
+               if !scheduled[d] {
+                  scheduled[d] = true;
+                  round.push(d);
+               }
+            }
+         }
+         values[id] = v;
+         self.elements[id].as_mut().unwrap().set_value(v);
+         self.remember_force(id);
+         self.overrides.insert(id, v);
+      }
+
+      let mut rounds = 0;
+      let mut budget = Budget::start(self.limits);
+      while !round.is_empty() {
+         if rounds == max_rounds {
+            let mut wires: Vec<String> = round.iter().map(|&id| self.names[id].clone()).collect();
+            wires.sort();
+            return Err(CircuitError::Unsettled(wires));
+         }
+         rounds += 1;
+         let mut next = Vec::new();
+         for &id in &round {
+            scheduled[id] = false;
+         }
+         for id in round {
+            if self.overrides.contains_key(&id) {
+               continue;
+            }
+            budget.step()?;
+            let mut inputs = [0; 2];
+            for (input, operand) in inputs.iter_mut().zip(self.elements[id].as_ref().unwrap().operands.iter()) {
+               *input = match *operand {
+                  Operand::Value(v) => v,
+                  Operand::Wire(w) if self.elements[w].is_some() => values[w],
+                  Operand::Wire(w) => return Err(CircuitError::Undefined(self.names[w].clone())),
+               };
+            }
+            let element = self.elements[id].as_mut().unwrap();
+            let v = element.spec.evaluate(inputs[0], inputs[1]);
+            let changed = v != values[id];
+            if changed || element.value.is_none() {
+               values[id] = v;
+               element.set_value(v);
+            }
+            if changed {
+               for &d in &self.dependents[id] {
+                  if !scheduled[d] {
+                     scheduled[d] = true;
+                     next.push(d);
+                  }
+               }
+            }
+         }
+         round = next;
+      }
+      return Ok(rounds);
+   }
+}
+
+#[test]
+fn test_settle_sr_latch() {
+   let mut latch: Circuit = "0 -> s\n0 -> r\nr NOR qb -> q\ns NOR q -> qb".parse().unwrap();
+   let q = |c: &mut Circuit| (c.get_value("q").unwrap(), c.get_value("qb").unwrap());
+   assert!(latch.get_value("q").is_err());
+
+   latch.settle(&[("s", 0xffff), ("r", 0)], 100).unwrap();
+   assert_eq!(q(&mut latch), (0xffff, 0));
+   // Letting go of set holds the state...
+   assert_eq!(latch.settle(&[("s", 0)], 100).unwrap(), 1);
+   assert_eq!(q(&mut latch), (0xffff, 0));
+   // ...until reset.
+   latch.settle(&[("r", 0xffff)], 100).unwrap();
+   latch.settle(&[("r", 0)], 100).unwrap();
+   assert_eq!(q(&mut latch), (0, 0xffff));
+   // Nothing changed, so nothing to do.
+   assert_eq!(latch.settle(&[("r", 0)], 100).unwrap(), 0);
+}
+
+#[test]
+fn test_settle_undo() {
+   let mut circuit: Circuit = "0 -> s\nNOT s -> q".parse().unwrap();
+   circuit.settle(&[("s", 1)], 100).unwrap();
+   circuit.settle(&[("s", 2)], 100).unwrap();
+   // Settling the same input again is no more to undo than once.
+   assert!(circuit.undo_force("s"));
+   assert!(!circuit.undo_force("s"));
+   assert_eq!(circuit.get_value("s").unwrap(), 0);
+   circuit.force_value("s", 5);
+   assert!(circuit.undo_force("s"));
+   assert!(!circuit.undo_force("s"));
+   assert_eq!(circuit.get_value("s").unwrap(), 0);
+   assert_eq!(circuit.get_value("q").unwrap(), 0xffff);
+}
+
+#[test]
+fn test_settle_oscillator() {
+   let mut circuit: Circuit = "NOT x -> x\n1 -> y\ny AND 3 -> z".parse().unwrap();
+   assert_eq!(circuit.settle(&[], 50), Err(CircuitError::Unsettled(vec!["x".to_string()])));
+   assert_eq!(circuit.settle(&[("w", 1)], 50), Err(CircuitError::Undefined("w".to_string())));
+   assert_eq!(circuit.get_value("z").unwrap(), 1);
+}
@@ -0,0 +1,162 @@
+use std::str::FromStr;
+use {ElementSpec, Input, InvalidInput, Operation};
+
+// How deeply parentheses and NOTs may nest before we give up on a line.
+const MAX_DEPTH: usize = 256;
+
+// A wire expression that may nest, like `(x AND y) OR (NOT z)`.  Binary
+// operators chain left to right with no precedence, so `x AND y OR z`
+// means `(x AND y) OR z`.
+#[derive(Debug, PartialEq)]
+pub enum Expr {
+   Operand(Input),
+   Not(Box<Expr>),
+   Binary(Box<Expr>, Operation, Box<Expr>),
+}
+
+impl FromStr for Expr {
+   type Err = InvalidInput;
+   fn from_str(s: &str) -> Result<Expr, InvalidInput> {
+      let spaced = s.replace("(", " ( ").replace(")", " ) ");
+      let tokens: Vec<&str> = spaced.split_whitespace().collect();
+      let mut pos = 0;
+      let expr = parse_chain(&tokens, &mut pos, 0)?;
+      if pos != tokens.len() {
+         return Err(InvalidInput);
+      }
+      return Ok(expr);
+   }
+}
+
+// The C-style operators of the infix syntax, like `a = x & (y << 2)`, and
+// the keywords they stand for.  Two-character operators come first so `<<`
+// isn't taken for something else.
+const INFIX_OPERATORS: [(&str, &str); 10] = [
+   ("<<", "LSHIFT"), (">>", "RSHIFT"), ("&", "AND"), ("|", "OR"), ("^", "XOR"),
+   ("!", "NOT"), ("~", "NOT"), ("+", "ADD"), ("-", "SUB"), ("*", "MUL"),
+];
+
+// Rewrites an infix expression into the keyword form Expr parses, so both
+// syntaxes end up as the same specs.  Operators still chain left to right
+// with no precedence, so `x | y & z` means `(x | y) & z`.
+pub fn from_infix(s: &str) -> String {
+   let mut out = String::with_capacity(s.len() * 2);
+   let mut rest = s;
+   'outer: while let Some(c) = rest.chars().next() {
+      for &(op, keyword) in INFIX_OPERATORS.iter() {
+         if rest.starts_with(op) {
+            out.push_str(&format!(" {} ", keyword));
+            rest = &rest[op.len()..];
+            continue 'outer;
+         }
+      }
+      out.push(c);
+      rest = &rest[c.len_utf8()..];
+   }
+   return out;
+}
+
+fn is_keyword(token: &str) -> bool {
+   token == "NOT" || token == "(" || token == ")" || token.parse::<Operation>().is_ok()
+}
+
+fn parse_chain(tokens: &[&str], pos: &mut usize, depth: usize) -> Result<Expr, InvalidInput> {
+   let mut expr = parse_unary(tokens, pos, depth)?;
+   while let Some(op) = tokens.get(*pos).and_then(|t| t.parse::<Operation>().ok()) {
+      *pos += 1;
+      let right = parse_unary(tokens, pos, depth)?;
+      expr = Expr::Binary(Box::new(expr), op, Box::new(right));
+   }
+   return Ok(expr);
+}
+
+fn parse_unary(tokens: &[&str], pos: &mut usize, depth: usize) -> Result<Expr, InvalidInput> {
+   if depth > MAX_DEPTH {
+      return Err(InvalidInput);
+   }
+   let token = *tokens.get(*pos).ok_or(InvalidInput)?;
+   *pos += 1;
+   return match token {
+      "NOT" => Ok(Expr::Not(Box::new(parse_unary(tokens, pos, depth + 1)?))),
+      "(" => {
+         let inner = parse_chain(tokens, pos, depth + 1)?;
+         if tokens.get(*pos) != Some(&")") {
+            return Err(InvalidInput);
+         }
+         *pos += 1;
+         Ok(inner)
+      }
+      t if is_keyword(t) => Err(InvalidInput),
+      t => Ok(Expr::Operand(t.parse::<Input>()?)),
+   };
+}
+
+impl Expr {
+   // Flattens into single gates, ending with name's own spec.  Each nested
+   // gate gets an anonymous wire named after name, like `a$1`; `$` never
+   // shows up in puzzle input, so these can't collide with real wires.
+   pub fn lower(self, name: &str) -> Vec<(String, ElementSpec)> {
+      let mut specs = Vec::new();
+      let spec = self.lower_spec(name, &mut specs);
+      specs.push((name.to_string(), spec));
+      return specs;
+   }
+
+   fn lower_spec(self, name: &str, specs: &mut Vec<(String, ElementSpec)>) -> ElementSpec {
+      match self {
+         Expr::Operand(input) => ElementSpec::value(input),
+         Expr::Not(inner) => ElementSpec{left: inner.lower_input(name, specs), right: Input::None, op: Operation::Not},
+         Expr::Binary(left, op, right) => {
+            let left = left.lower_input(name, specs);
+            let right = right.lower_input(name, specs);
+            ElementSpec{left: left, right: right, op: op}
+         }
+      }
+   }
+
+   fn lower_input(self, name: &str, specs: &mut Vec<(String, ElementSpec)>) -> Input {
+      if let Expr::Operand(input) = self {
+         return input;
+      }
+      let spec = self.lower_spec(name, specs);
+      let wire = format!("{}${}", name, specs.len() + 1);
+      specs.push((wire.clone(), spec));
+      return Input::Element(wire);
+   }
+}
+
+#[test]
+fn test_parse_expr() {
+   let x = || Box::new(Expr::Operand(Input::Element("x".to_string())));
+   assert_eq!("x".parse::<Expr>().unwrap(), *x());
+   assert_eq!("NOT NOT x".parse::<Expr>().unwrap(), Expr::Not(Box::new(Expr::Not(x()))));
+   assert_eq!("(x AND 1) OR x".parse::<Expr>().unwrap(),
+              Expr::Binary(Box::new(Expr::Binary(x(), Operation::And, Box::new(Expr::Operand(Input::Value(1))))),
+                           Operation::Or, x()));
+   assert_eq!("x AND 1 OR x".parse::<Expr>().unwrap(), "((x) AND 1) OR x".parse::<Expr>().unwrap());
+
+   for bad in &["", "(x AND y", "x AND y)", "()", "x AND", "NOT", "x y", "AND x", "x AND OR"] {
+      assert_eq!(bad.parse::<Expr>(), Err(InvalidInput), "{:?}", bad);
+   }
+   let deep = format!("{}x{}", "(".repeat(1000), ")".repeat(1000));
+   assert_eq!(deep.parse::<Expr>(), Err(InvalidInput));
+}
+
+#[test]
+fn test_from_infix() {
+   assert_eq!(from_infix("x&y").split_whitespace().collect::<Vec<_>>(), vec!["x", "AND", "y"]);
+   assert_eq!(from_infix("!(x << 2) | ~y >> z[1]"), " NOT (x  LSHIFT  2)  OR   NOT y  RSHIFT  z[1]");
+   assert_eq!(from_infix("a+b-c*d").parse::<Expr>().unwrap(), "a ADD b SUB c MUL d".parse::<Expr>().unwrap());
+}
+
+#[test]
+fn test_lower() {
+   let specs = "(x AND y) OR (NOT z)".parse::<Expr>().unwrap().lower("a");
+   let names: Vec<&str> = specs.iter().map(|&(ref n, _)| &n[..]).collect();
+   assert_eq!(names, vec!["a$1", "a$2", "a"]);
+   assert_eq!(specs[0].1, "x AND y".parse().unwrap());
+   assert_eq!(specs[1].1, "NOT z".parse().unwrap());
+   assert_eq!(specs[2].1, "a$1 OR a$2".parse().unwrap());
+
+   assert_eq!("x LSHIFT 2".parse::<Expr>().unwrap().lower("f"), vec![("f".to_string(), "x LSHIFT 2".parse().unwrap())]);
+}
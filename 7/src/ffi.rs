@@ -0,0 +1,127 @@
+// A C interface to the circuit engine, for embedding it in C or C++ test
+// harnesses.  include/circuit.h is generated from this with cbindgen.
+//
+// Every function returning int gives 0 on success and -1 on failure.
+// Strings are NUL-terminated UTF-8 and are only borrowed for the call.
+// The functions' own comments are doc comments, so that cbindgen copies
+// them, Safety sections and all, into the header.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use Circuit;
+
+unsafe fn borrow_str<'a>(s: *const c_char) -> Option<&'a str> {
+   if s.is_null() {
+      return None;
+   }
+   return CStr::from_ptr(s).to_str().ok();
+}
+
+/// Makes an empty circuit, to be released with circuit_free.
+#[no_mangle]
+pub extern "C" fn circuit_new() -> *mut Circuit {
+   return Box::into_raw(Box::new(Circuit::new()));
+}
+
+/// Adds one netlist line, like "x AND y -> d".
+///
+/// # Safety
+///
+/// circuit must be non-null, from circuit_new, and not yet given to
+/// circuit_free.  line must be non-null and NUL-terminated, and fails
+/// unless it's UTF-8.  Null pointers fail with -1; any other bad pointer
+/// is undefined behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn circuit_add_line(circuit: *mut Circuit, line: *const c_char) -> c_int {
+   let (circuit, line) = match (circuit.as_mut(), borrow_str(line)) {
+      (Some(circuit), Some(line)) => (circuit, line),
+      _ => return -1,
+   };
+   return match circuit.add_line(0, line) {
+      Ok(()) => 0,
+      Err(_) => -1,
+   };
+}
+
+/// Resolves wire, storing its value in *value.
+///
+/// # Safety
+///
+/// circuit must be non-null, from circuit_new, and not yet given to
+/// circuit_free.  wire must be non-null and NUL-terminated, and fails
+/// unless it's UTF-8.  value must be non-null and point to a uint16_t
+/// that can be written.  Null pointers fail with -1; any other bad
+/// pointer is undefined behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn circuit_get_value(circuit: *mut Circuit, wire: *const c_char, value: *mut u16) -> c_int {
+   let (circuit, wire) = match (circuit.as_mut(), borrow_str(wire)) {
+      (Some(circuit), Some(wire)) if !value.is_null() => (circuit, wire),
+      _ => return -1,
+   };
+   return match circuit.get_value(wire) {
+      Ok(v) => {
+         ptr::write(value, v);
+         0
+      }
+      Err(_) => -1,
+   };
+}
+
+/// Pins a defined wire to value.
+///
+/// # Safety
+///
+/// circuit must be non-null, from circuit_new, and not yet given to
+/// circuit_free.  wire must be non-null and NUL-terminated, and fails
+/// unless it's UTF-8.  Null pointers fail with -1; any other bad pointer
+/// is undefined behaviour.
+#[no_mangle]
+pub unsafe extern "C" fn circuit_force(circuit: *mut Circuit, wire: *const c_char, value: u16) -> c_int {
+   return match (circuit.as_mut(), borrow_str(wire)) {
+      (Some(circuit), Some(wire)) if circuit.element(wire).is_some() => {
+         circuit.force_value(wire, value);
+         0
+      }
+      _ => -1,
+   };
+}
+
+/// Releases a circuit from circuit_new.  Null is ignored.
+///
+/// # Safety
+///
+/// circuit must be null or from circuit_new, and not already freed.  It
+/// can't be used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn circuit_free(circuit: *mut Circuit) {
+   if !circuit.is_null() {
+      drop(Box::from_raw(circuit));
+   }
+}
+
+#[test]
+fn test_ffi() {
+   use std::ffi::CString;
+
+   let c = |s: &str| CString::new(s).unwrap();
+   unsafe {
+      let circuit = circuit_new();
+      assert_eq!(circuit_add_line(circuit, c("123 -> x").as_ptr()), 0);
+      assert_eq!(circuit_add_line(circuit, c("NOT x -> a").as_ptr()), 0);
+      assert_eq!(circuit_add_line(circuit, c("NOPE").as_ptr()), -1);
+      assert_eq!(circuit_add_line(circuit, ptr::null()), -1);
+
+      let mut v = 0u16;
+      assert_eq!(circuit_get_value(circuit, c("a").as_ptr(), &mut v), 0);
+      assert_eq!(v, 65412);
+      assert_eq!(circuit_force(circuit, c("x").as_ptr(), 0), 0);
+      assert_eq!(circuit_get_value(circuit, c("a").as_ptr(), &mut v), 0);
+      assert_eq!(v, 65535);
+      assert_eq!(circuit_force(circuit, c("q").as_ptr(), 0), -1);
+      assert_eq!(circuit_get_value(circuit, c("q").as_ptr(), &mut v), -1);
+      assert_eq!(circuit_get_value(ptr::null_mut(), c("a").as_ptr(), &mut v), -1);
+      circuit_free(circuit);
+      circuit_free(ptr::null_mut());
+   }
+}
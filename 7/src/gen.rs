@@ -0,0 +1,79 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Puzzle-style wire names: a, b, ..., z, aa, ab, ...
+fn wire_name(mut n: usize) -> String {
+   let mut name = Vec::new();
+   loop {
+      name.push(b'a' + (n % 26) as u8);
+      if n < 26 {
+         break;
+      }
+      n = n / 26 - 1;
+   }
+   name.reverse();
+   return String::from_utf8(name).unwrap();
+}
+
+// A random, well-formed netlist in the puzzle's syntax with the given
+// number of wires, arranged so the longest chain through it is depth
+// levels long.  The first level is constants, and every wire after that
+// reads at least one wire from the level just before it.  Lines come out
+// shuffled, so wires are used before they're defined just like in the
+// puzzle input.  The same seed always gives the same netlist.
+pub fn random_netlist(wires: usize, depth: usize, seed: u64) -> String {
+   assert!(depth >= 1 && wires >= depth, "need at least one wire per level");
+   let mut rng = StdRng::seed_from_u64(seed);
+
+   // Spread the wires over the levels, at least one apiece.
+   let mut level_of: Vec<usize> = (0..depth).collect();
+   level_of.extend((depth..wires).map(|_| rng.gen_range(0..depth)));
+   level_of.sort();
+   let starts: Vec<usize> = (0..depth).map(|d| level_of.iter().position(|&l| l == d).unwrap()).collect();
+
+   let mut lines = Vec::with_capacity(wires);
+   for (i, &level) in level_of.iter().enumerate() {
+      let name = wire_name(i);
+      if level == 0 {
+         lines.push(format!("{} -> {}", rng.gen::<u16>(), name));
+         continue;
+      }
+      let previous = wire_name(rng.gen_range(starts[level - 1]..starts[level]));
+      let any = wire_name(rng.gen_range(0..starts[level]));
+      let spec = match rng.gen_range(0..7) {
+         0 => format!("NOT {}", previous),
+         1 => format!("{} LSHIFT {}", previous, rng.gen_range(0..16)),
+         2 => format!("{} RSHIFT {}", previous, rng.gen_range(0..16)),
+         3 => format!("{} AND {}", previous, any),
+         4 => format!("{} OR {}", any, previous),
+         5 => format!("{} AND {}", rng.gen::<u16>(), previous),
+         _ => previous,
+      };
+      lines.push(format!("{} -> {}", spec, name));
+   }
+
+   for i in (1..lines.len()).rev() {
+      lines.swap(i, rng.gen_range(0..i + 1));
+   }
+   let mut out = lines.join("\n");
+   out.push('\n');
+   return out;
+}
+
+#[test]
+fn test_random_netlist() {
+   use Circuit;
+
+   assert_eq!(wire_name(0), "a");
+   assert_eq!(wire_name(25), "z");
+   assert_eq!(wire_name(26), "aa");
+   assert_eq!(wire_name(27 * 26), "aaa");
+
+   let netlist = random_netlist(500, 20, 7);
+   assert_eq!(netlist, random_netlist(500, 20, 7));
+   let mut circuit = netlist.parse::<Circuit>().unwrap();
+   assert_eq!(circuit.len(), 500);
+   assert!(circuit.undefined().is_empty());
+   assert_eq!(circuit.levels().unwrap().len(), 20);
+   assert_eq!(circuit.resolve_all().unwrap().len(), 500);
+}
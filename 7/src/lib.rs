@@ -0,0 +1,2588 @@
+extern crate aoc2015_lib;
+extern crate gates;
+extern crate rand;
+extern crate rayon;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+#[macro_use]
+extern crate tracing;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+// pyo3's macros expand to `::core` paths, which need this in a 2015 crate.
+#[cfg(feature = "python")]
+extern crate core;
+#[cfg(feature = "python")]
+extern crate pyo3;
+#[cfg(test)]
+#[macro_use]
+extern crate proptest;
+
+mod budget;
+mod builder;
+mod bus;
+mod diff;
+mod event;
+mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod gen;
+mod minimize;
+mod module;
+mod snapshot;
+mod solver;
+mod svg;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use budget::Limits;
+pub use builder::{CircuitBuilder, WireBuilder};
+pub use diff::CircuitDiff;
+pub use expr::Expr;
+pub use gates::{ElementSpec, Input, InvalidInput, Logic, Operation, Signal};
+pub use gen::random_netlist;
+pub use minimize::MAX_MINIMIZE_INPUTS;
+pub use module::Module;
+pub use snapshot::StateId;
+pub use solver::Day07;
+use aoc2015_lib::parse::split_once_trim;
+use budget::Budget;
+use gates::{parse_literal, simulate, Names, Operand, Unsettled, Wiring};
+use rayon::prelude::*;
+use snapshot::Snapshot;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::str;
+use std::str::FromStr;
+use std::time::Duration;
+
+// What circuit's events are traced under: the day rather than the crate,
+// so that RUST_LOG=day07=debug shows them along with the binary's own.
+const LOG_TARGET: &str = "day07";
+
+// A netlist line that couldn't be parsed, numbered from 1.
+#[derive(Debug)]
+pub struct ParseError {
+   pub line: usize,
+   pub text: String,
+}
+
+impl fmt::Display for ParseError {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      write!(f, "line {}: can't parse {:?}", self.line, self.text)
+   }
+}
+
+// Loading a netlist can fail either reading it or parsing it, or when
+// merging it into a circuit, defining a wire that's already defined.
+#[derive(Debug)]
+pub enum LoadError {
+   Io(io::Error),
+   Parse(ParseError),
+   Duplicate(String),
+}
+
+impl fmt::Display for LoadError {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         LoadError::Io(ref e) => write!(f, "{}", e),
+         LoadError::Parse(ref e) => write!(f, "{}", e),
+         LoadError::Duplicate(ref wire) => write!(f, "wire {} is already defined", wire),
+      }
+   }
+}
+
+impl From<io::Error> for LoadError {
+   fn from(e: io::Error) -> LoadError {
+      LoadError::Io(e)
+   }
+}
+
+impl From<ParseError> for LoadError {
+   fn from(e: ParseError) -> LoadError {
+      LoadError::Parse(e)
+   }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Element {
+   pub spec: ElementSpec,
+   pub name: String,
+   pub value: Option<u16>,
+   // spec's left and right inputs, resolved against the circuit's wire ids.
+   #[serde(skip)]
+   operands: [Operand; 2],
+   // Whether value has ever been computed (rather than forced), so
+   // recomputations after invalidation can be counted.
+   #[serde(skip)]
+   computed: bool,
+}
+
+impl Element {
+   fn set_value(&mut self, val: u16) {
+      self.value = Some(val);
+      debug!(target: LOG_TARGET, "setting {} as {}", self.name, val);
+   }
+
+   fn clear_value(&mut self) {
+      self.value = None;
+      debug!(target: LOG_TARGET, "Clearing {}", self.name);
+   }
+
+   fn wire_operands<'a>(&'a self) -> impl Iterator<Item=usize> + 'a {
+      self.operands.iter().filter_map(|o| o.wire())
+   }
+}
+
+// Leaves out the interned operands, whose ids depend on the order wires
+// were first mentioned in.
+impl fmt::Debug for Element {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      f.debug_struct("Element")
+         .field("spec", &self.spec)
+         .field("name", &self.name)
+         .field("value", &self.value)
+         .finish()
+   }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CircuitError {
+   Cycle(Vec<String>),
+   // A wire that's read but never driven by anything.
+   Undefined(String),
+   // More free inputs than truth_table or equivalent are willing to
+   // enumerate.
+   TooManyInputs(usize),
+   // Wires still changing when Circuit::settle gave up.
+   Unsettled(Vec<String>),
+   // A snapshot taken before the circuit was last changed, or of another
+   // circuit altogether.
+   StaleSnapshot,
+   // An evaluation that ran past the circuit's Limits.
+   OutOfSteps(usize),
+   TimedOut(Duration),
+}
+
+impl fmt::Display for CircuitError {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         CircuitError::Cycle(ref wires) => write!(f, "circular reference: {}", wires.join(" -> ")),
+         CircuitError::Undefined(ref wire) => write!(f, "wire {} is never defined", wire),
+         CircuitError::TooManyInputs(n) => write!(f, "{} free inputs is too many to enumerate", n),
+         CircuitError::Unsettled(ref wires) => write!(f, "never settled, still changing: {}", wires.join(" ")),
+         CircuitError::StaleSnapshot => write!(f, "the circuit has changed since that snapshot"),
+         CircuitError::OutOfSteps(n) => write!(f, "gave up after {} steps", n),
+         CircuitError::TimedOut(t) => write!(f, "gave up after {}ms", t.as_millis()),
+      }
+   }
+}
+
+// An overview of a circuit, from Circuit::stats.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+   pub wires: usize,
+   // How many elements use each operation, by name.
+   pub operations: BTreeMap<String, usize>,
+   // Operands that are a literal value rather than a wire.
+   pub constant_inputs: usize,
+   // The longest critical path to any wire.
+   pub depth: usize,
+   pub undefined: Vec<String>,
+}
+
+impl fmt::Display for Stats {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      writeln!(f, "{} wires", self.wires)?;
+      for (op, count) in &self.operations {
+         writeln!(f, "   {} {}", count, op)?;
+      }
+      writeln!(f, "{} constant inputs", self.constant_inputs)?;
+      writeln!(f, "{} gates deep", self.depth)?;
+      write!(f, "{} undefined wires", self.undefined.len())?;
+      if !self.undefined.is_empty() {
+         write!(f, ": {}", self.undefined.join(" "))?;
+      }
+      writeln!(f)
+   }
+}
+
+// How well get_value's memoization is doing, from Circuit::cache_stats.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+   // Wires that were needed, as a query or as an input to a gate being
+   // computed, and already had a value.
+   pub hits: usize,
+   // Wires that had to be computed.
+   pub misses: usize,
+   // Misses for wires that had been computed before, and were forgotten
+   // because something upstream of them changed.
+   pub recomputed: usize,
+}
+
+impl fmt::Display for CacheStats {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      writeln!(f, "{} cache hits, {} misses ({} recomputed after invalidation)", self.hits, self.misses, self.recomputed)
+   }
+}
+
+// 2^16 rows is already more than anyone wants to read.
+pub const MAX_TRUTH_TABLE_INPUTS: usize = 16;
+
+// How many input assignments equivalent will try before giving up.
+const MAX_EQUIVALENCE_ASSIGNMENTS: u64 = 1 << 20;
+
+// A short description of a gate for diagrams: its operation, if it's not
+// just a wire, and any constant operands, like `LSHIFT 2`.
+fn gate_detail(spec: &ElementSpec) -> Vec<String> {
+   let mut detail = Vec::new();
+   match spec.op {
+      Operation::Value => (),
+      ref op => detail.push(op.to_string()),
+   }
+   for input in &[&spec.left, &spec.right] {
+      if let &&Input::Value(v) = input {
+         detail.push(v.to_string());
+      }
+   }
+   return detail;
+}
+
+// How deeply module instances may nest inside each other, and how many
+// there can be in all, since each level of nesting can multiply them.
+const MAX_MODULE_DEPTH: usize = 32;
+const MAX_MODULE_INSTANCES: usize = 1 << 16;
+
+// A netlist line without its comment, if any, and surrounding whitespace.
+fn strip_comment(text: &str) -> &str {
+   let end = match (text.find('#'), text.find("//")) {
+      (Some(a), Some(b)) => a.min(b),
+      (a, b) => a.or(b).unwrap_or(text.len()),
+   };
+   return text[..end].trim();
+}
+
+// A line in the infix syntax, like "name = x & y", rewritten as the
+// equivalent "x AND y -> name"; see expr::from_infix.  Anything else is
+// left alone.
+fn arrow_form(text: &str) -> String {
+   if text.contains("->") || !text.contains('=') {
+      return text.to_string();
+   }
+   let parts = text.splitn(2, '=').map(|p| p.trim()).collect::<Vec<_>>();
+   return format!("{} -> {}", expr::from_infix(parts[1]), parts[0]);
+}
+
+// Whether name could be a wire in a netlist: not empty, without a space,
+// and not something that would be read back as a constant, like 123 or
+// 0x1f.
+fn valid_name(name: &str) -> bool {
+   !name.is_empty() && !name.contains(' ') && parse_literal(name).is_none()
+}
+
+impl FromStr for Circuit {
+   type Err = ParseError;
+   fn from_str(s: &str) -> Result<Circuit, ParseError> {
+      let mut circuit = Circuit::new();
+      for (i, line) in s.lines().enumerate() {
+         if !circuit.add_plain_line(line.as_bytes()) {
+            circuit.add_line(i + 1, line)?;
+         }
+      }
+      circuit.finish()?;
+      return Ok(circuit);
+   }
+}
+
+// Wires are interned: every name that's mentioned, defined or not, gets an
+// id indexing names, elements and dependents, so evaluation never has to
+// look a string up.  The public API still speaks in names.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Circuit {
+   names: Names,
+   // None for wires that are read somewhere but never defined.
+   elements: Vec<Option<Element>>,
+   // Wires pinned by force_value, which invalidation leaves alone.
+   #[serde(default)]
+   overrides: HashMap<usize, u16>,
+   // Each wire force_value has forced, with the override it had before
+   // the first time, if any, for undo_force.  Forcing it again changes
+   // nothing here, so forcing the same wire over and over can't pile up
+   // entries.
+   #[serde(skip)]
+   forced: Vec<(usize, Option<u16>)>,
+   // For each wire, the elements that read from it.
+   #[serde(skip)]
+   dependents: Vec<Vec<usize>>,
+   // Modules defined so far while loading, by name.
+   #[serde(skip)]
+   modules: HashMap<String, Module>,
+   // The module whose body add_line is in the middle of, along with the
+   // line number and text of its header.
+   #[serde(skip)]
+   open_module: Option<(usize, String, String, Module)>,
+   // How many module instances have been flattened, to keep their
+   // internal wire names unique.
+   #[serde(skip)]
+   instances: usize,
+   // Bumped whenever a wire's definition changes, so restore can tell
+   // which snapshots still apply.
+   #[serde(skip)]
+   revision: usize,
+   #[serde(skip)]
+   snapshots: Vec<Snapshot>,
+   #[serde(skip)]
+   cache_stats: CacheStats,
+   #[serde(skip)]
+   limits: Limits,
+}
+
+// Wires, forced values and modules listed by name, so the output is the
+// same from one run to the next and can be diffed.
+impl fmt::Debug for Circuit {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      let overrides: BTreeMap<&str, u16> = self.overrides.iter()
+         .map(|(&id, &v)| (&self.names[id][..], v)).collect();
+      let modules: BTreeMap<&str, &Module> = self.modules.iter()
+         .map(|(name, module)| (&name[..], module)).collect();
+      f.debug_struct("Circuit")
+         .field("elements", &self.sorted_elements())
+         .field("undefined", &self.undefined())
+         .field("overrides", &overrides)
+         .field("modules", &modules)
+         .finish()
+   }
+}
+
+impl Wiring for Circuit {
+   fn gate(&self, id: usize) -> Option<(&ElementSpec, [Operand; 2])> {
+      return self.elements[id].as_ref().map(|e| (&e.spec, e.operands));
+   }
+}
+
+impl Default for Circuit {
+   fn default() -> Circuit {
+      return Circuit::new();
+   }
+}
+
+impl Circuit {
+   pub fn new() -> Circuit {
+      Circuit{
+         names: Names::default(),
+         elements: Vec::new(),
+         overrides: HashMap::new(),
+         forced: Vec::new(),
+         dependents: Vec::new(),
+         modules: HashMap::new(),
+         open_module: None,
+         instances: 0,
+         revision: 0,
+         snapshots: Vec::new(),
+         cache_stats: CacheStats::default(),
+         limits: Limits::default(),
+      }
+   }
+
+   fn intern(&mut self, name: &str) -> usize {
+      let id = self.names.intern(name);
+      if id == self.elements.len() {
+         self.elements.push(None);
+         self.dependents.push(Vec::new());
+      }
+      return id;
+   }
+
+   fn operand(&mut self, input: &Input) -> Operand {
+      match input {
+         &Input::Value(v) => Operand::Value(v),
+         &Input::Element(ref e) => Operand::Wire(self.intern(e)),
+         &Input::None => Operand::Value(0),
+      }
+   }
+
+   // Replaces id's spec, keeping its operands and the dependents index in
+   // step.  Any resolved value is kept.
+   fn set_spec(&mut self, id: usize, spec: ElementSpec) {
+      self.unlink(id);
+      self.revision += 1;
+      let operands = [self.operand(&spec.left), self.operand(&spec.right)];
+      let e = self.elements[id].as_mut().unwrap();
+      e.spec = spec;
+      e.operands = operands;
+      self.link(id);
+   }
+
+   fn link(&mut self, id: usize) {
+      let wires: Vec<usize> = self.elements[id].as_ref().unwrap().wire_operands().collect();
+      for w in wires {
+         self.dependents[w].push(id);
+      }
+   }
+
+   fn unlink(&mut self, id: usize) {
+      let wires: Vec<usize> = self.elements[id].as_ref().unwrap().wire_operands().collect();
+      for w in wires {
+         if let Some(i) = self.dependents[w].iter().position(|&d| d == id) {
+            self.dependents[w].swap_remove(i);
+         }
+      }
+   }
+
+   // Recomputes everything that isn't serialized.
+   fn reindex(&mut self) {
+      self.dependents = vec![Vec::new(); self.names.len()];
+      for id in 0..self.elements.len() {
+         if let Some(spec) = self.elements[id].as_ref().map(|e| e.spec.clone()) {
+            let operands = [self.operand(&spec.left), self.operand(&spec.right)];
+            self.elements[id].as_mut().unwrap().operands = operands;
+            self.link(id);
+         }
+      }
+   }
+
+   fn id(&self, name: &str) -> Option<usize> {
+      self.names.id(name)
+   }
+
+   // Ids of every defined wire.
+   fn defined(&self) -> Vec<usize> {
+      (0..self.elements.len()).filter(|&id| self.elements[id].is_some()).collect()
+   }
+
+   // Every defined wire, sorted.
+   pub fn sorted_names(&self) -> Vec<&str> {
+      let mut names: Vec<&str> = self.defined().into_iter().map(|id| &self.names[id][..]).collect();
+      names.sort();
+      return names;
+   }
+
+   // Every defined element, sorted by wire name rather than in the order
+   // the wires happened to be mentioned, so two netlists with the same
+   // lines in a different order look the same.
+   pub fn sorted_elements(&self) -> Vec<&Element> {
+      let mut elements: Vec<&Element> = self.elements.iter().filter_map(|e| e.as_ref()).collect();
+      elements.sort_by(|a, b| a.name.cmp(&b.name));
+      return elements;
+   }
+
+   pub fn element(&self, name: &str) -> Option<&Element> {
+      self.id(name).and_then(|id| self.elements[id].as_ref())
+   }
+
+   pub fn len(&self) -> usize {
+      self.elements.iter().filter(|e| e.is_some()).count()
+   }
+
+   pub fn is_empty(&self) -> bool {
+      self.len() == 0
+   }
+
+   // Defines name from spec, which may nest like `(x AND y) OR (NOT z)`.
+   // Nested gates get their own anonymous wires; see Expr::lower.  Either
+   // side can also use buses like `x[7:0]`, which define a wire per lane;
+   // see bus::expand.  Nothing is added unless the whole thing parses.
+   pub fn add_element(&mut self, name: &str, spec: &str) -> Result<(), InvalidInput> {
+      let mut specs = Vec::new();
+      for (lane, spec) in bus::expand(name, spec)? {
+         if !valid_name(&lane) {
+            return Err(InvalidInput);
+         }
+         specs.extend(spec.parse::<Expr>()?.lower(&lane));
+      }
+      for (wire, spec) in specs {
+         self.add_spec(&wire, spec)?;
+      }
+      return Ok(());
+   }
+
+   // Defines (or redefines) name as spec.  Redefining a wire forgets its
+   // value, and those of everything downstream of it, along with any
+   // value it was forced to.
+   pub fn add_spec(&mut self, name: &str, spec: ElementSpec) -> Result<(), InvalidInput> {
+      if !valid_name(name) || !spec.wires().into_iter().all(valid_name) {
+         return Err(InvalidInput);
+      }
+      let id = self.intern(name);
+      self.revision += 1;
+      let redefined = self.elements[id].is_some();
+      if redefined {
+         self.unlink(id);
+         self.overrides.remove(&id);
+         self.forced.retain(|&(w, _)| w != id);
+      }
+      let operands = [self.operand(&spec.left), self.operand(&spec.right)];
+      self.elements[id] = Some(Element {
+         spec: spec,
+         name: name.to_string(),
+         value: None,
+         operands: operands,
+         computed: false,
+      });
+      self.link(id);
+      if redefined {
+         self.invalidate_dependents(id);
+      }
+      if let Some(warning) = self.shift_warning(id) {
+         warn!(target: LOG_TARGET, "{}", warning);
+      }
+      return Ok(());
+   }
+
+   // Swaps the definition of an existing wire for spec, like `x OR y`, so
+   // the next get_value only recomputes what it affects.
+   pub fn redefine(&mut self, name: &str, spec: &str) -> Result<(), InvalidInput> {
+      if self.element(name).is_none() {
+         return Err(InvalidInput);
+      }
+      return self.add_element(name, spec);
+   }
+
+   // Adds one "spec -> name" line of a netlist.  Blank lines and comments
+   // starting with `#` or `//` are skipped.
+   pub fn add_line(&mut self, line: usize, text: &str) -> Result<(), ParseError> {
+      let error = |_| ParseError { line: line, text: text.to_string() };
+      let full_text = text;
+      let text = strip_comment(text);
+      if text.is_empty() {
+         return Ok(());
+      }
+      if let Some((start, header, name, mut module)) = self.open_module.take() {
+         if text == "end" {
+            self.modules.insert(name, module);
+         } else if text.starts_with("module ") {
+            return Err(error(InvalidInput));
+         } else {
+            module.body.push(arrow_form(text));
+            self.open_module = Some((start, header, name, module));
+         }
+         return Ok(());
+      }
+      if let Some(call) = text.strip_prefix("module ") {
+         let (name, inputs, outputs) = module::parse_call(call).map_err(error)?;
+         let module = Module{inputs: inputs, outputs: outputs, body: Vec::new()};
+         self.open_module = Some((line, full_text.to_string(), name, module));
+         return Ok(());
+      }
+
+      let parsed = if module::instantiates(text, &self.modules).is_some() {
+         self.instantiate(text, 0)
+      } else {
+         self.add_definition(text)
+      };
+      return parsed.map_err(error);
+   }
+
+   // Adds "spec -> name", however it's spaced, or the same thing in the
+   // infix syntax.
+   fn add_definition(&mut self, text: &str) -> Result<(), InvalidInput> {
+      let text = arrow_form(text);
+      return match split_once_trim(&text, "->") {
+         Some((spec, name)) if !name.contains("->") => self.add_element(name, spec),
+         _ => Err(InvalidInput),
+      };
+   }
+
+   // Checks a netlist fed through add_line didn't stop partway through a
+   // module definition.
+   pub fn finish(&self) -> Result<(), ParseError> {
+      return match self.open_module {
+         Some((line, ref header, _, _)) => Err(ParseError { line: line, text: header.clone() }),
+         None => Ok(()),
+      };
+   }
+
+   // Flattens a module instantiation like `half_adder(x, y) -> (s, c)`
+   // into the circuit.  depth guards against modules that instantiate
+   // themselves.
+   fn instantiate(&mut self, text: &str, depth: usize) -> Result<(), InvalidInput> {
+      if depth > MAX_MODULE_DEPTH || self.instances >= MAX_MODULE_INSTANCES {
+         return Err(InvalidInput);
+      }
+      let (name, args, outputs) = module::parse_call(text)?;
+      self.instances += 1;
+      let prefix = format!("{}{}", name, self.instances);
+      for line in self.modules[&name].flatten(&args, &outputs, &prefix, &self.modules)? {
+         if module::instantiates(&line, &self.modules).is_some() {
+            self.instantiate(&line, depth + 1)?;
+         } else {
+            self.add_definition(&line)?;
+         }
+      }
+      return Ok(());
+   }
+
+   // Fast path for the common "x AND y -> z" line, parsed straight from
+   // its bytes without building the intermediate strings add_line does.
+   // Returns false, having added nothing, for anything fancier (comments,
+   // buses, nesting, modules) or anything invalid, which add_line then
+   // handles or reports.
+   fn add_plain_line(&mut self, line: &[u8]) -> bool {
+      if self.open_module.is_some() || line.iter().any(|b| b"#/[]{}()".contains(b)) {
+         return false;
+      }
+      let mut tokens: [&str; 5] = [""; 5];
+      let mut count = 0;
+      for token in line.split(|b| b.is_ascii_whitespace()).filter(|t| !t.is_empty()) {
+         if count == tokens.len() {
+            return false;
+         }
+         tokens[count] = match str::from_utf8(token) {
+            Ok(t) => t,
+            Err(_) => return false,
+         };
+         count += 1;
+      }
+      if count < 3 || tokens[count - 2] != "->" {
+         return false;
+      }
+      let operand = |t: &str| match parse_literal(t) {
+         Some(v) => v.ok().map(Input::Value),
+         None if t != "NOT" && t != "->" && t.parse::<Operation>().is_err() => Some(Input::from(t)),
+         None => None,
+      };
+      let spec = match (count, tokens[0].parse::<Operation>()) {
+         (3, _) => operand(tokens[0]).map(ElementSpec::value),
+         (4, _) if tokens[0] == "NOT" => operand(tokens[1]).map(|i| ElementSpec{left: i, right: Input::None, op: Operation::Not}),
+         (5, Err(_)) => match (operand(tokens[0]), tokens[1].parse::<Operation>(), operand(tokens[2])) {
+            (Some(l), Ok(op), Some(r)) => Some(ElementSpec{left: l, right: r, op: op}),
+            _ => None,
+         },
+         _ => None,
+      };
+      return match spec {
+         Some(spec) => self.add_spec(tokens[count - 1], spec).is_ok(),
+         None => false,
+      };
+   }
+
+   // Reads a whole netlist, one "spec -> name" line at a time.  Lines are
+   // streamed through a single reused buffer rather than collected, so
+   // memory goes on the circuit itself however long the netlist is.
+   pub fn from_reader<R: BufRead>(mut reader: R) -> Result<Circuit, LoadError> {
+      let mut circuit = Circuit::new();
+      let mut buf = Vec::new();
+      let mut line = 0;
+      loop {
+         buf.clear();
+         if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+         }
+         line += 1;
+         if circuit.add_plain_line(&buf) {
+            continue;
+         }
+         let text = str::from_utf8(&buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+         circuit.add_line(line, text.trim_end_matches(['\n', '\r']))?;
+      }
+      circuit.finish()?;
+      return Ok(circuit);
+   }
+
+   // Reads the netlist at path into this circuit alongside what's already
+   // here; see merge.
+   pub fn load<P: AsRef<Path>>(&mut self, path: P, prefix: Option<&str>) -> Result<(), LoadError> {
+      let other = Circuit::from_reader(BufReader::new(File::open(path)?))?;
+      return self.merge(&other, prefix, &[]);
+   }
+
+   // Copies other's wires into this circuit.  With a prefix, each of other's
+   // wires is renamed `prefix.wire`, the way module instances are, so
+   // independently written netlists can't collide.  ports then stitches
+   // other's wires onto this one's by name instead: `("x", "a")` makes
+   // other's x read from (or, if other defines x, drive) this circuit's a.
+   // Nothing is merged if any wire would end up defined twice.
+   pub fn merge(&mut self, other: &Circuit, prefix: Option<&str>, ports: &[(&str, &str)]) -> Result<(), LoadError> {
+      let ports: HashMap<&str, &str> = ports.iter().cloned().collect();
+      let rename = |wire: &str| match (ports.get(wire), prefix) {
+         (Some(port), _) => port.to_string(),
+         (None, Some(prefix)) => format!("{}.{}", prefix, wire),
+         (None, None) => wire.to_string(),
+      };
+      let names = other.sorted_names();
+      for name in &names {
+         let renamed = rename(name);
+         if self.element(&renamed).is_some() {
+            return Err(LoadError::Duplicate(renamed));
+         }
+      }
+      for name in names {
+         let e = other.element(name).unwrap();
+         let input = |input: &Input| match *input {
+            Input::Element(ref wire) => Input::Element(rename(wire)),
+            ref other => other.clone(),
+         };
+         let spec = ElementSpec{left: input(&e.spec.left), right: input(&e.spec.right), op: e.spec.op.clone()};
+         self.add_spec(&rename(name), spec).unwrap();
+      }
+      return Ok(());
+   }
+
+   // gates::simulate on this circuit, within its limits.  Wires for which
+   // `cached` returns a value aren't recomputed, and undefined wires read
+   // as `undefined`, or are an error if that's None.
+   fn simulate<T, F>(&self, roots: &[usize], cached: F, undefined: Option<T>) -> Result<Vec<(usize, T)>, CircuitError>
+      where T: Signal, F: Fn(&Element) -> Option<T> {
+      let mut budget = Budget::start(self.limits);
+      let cached = |id: usize| self.elements[id].as_ref().and_then(&cached);
+      return simulate(self, self.names.len(), roots, cached, undefined, || budget.step()).map_err(|e| match e {
+         Unsettled::Cycle(wires) => CircuitError::Cycle(wires.into_iter().map(|w| self.names[w].clone()).collect()),
+         Unsettled::Undefined(w) => CircuitError::Undefined(self.names[w].clone()),
+         Unsettled::Stopped(e) => e,
+      });
+   }
+
+   fn resolve(&mut self, id: usize) -> Result<u16, CircuitError> {
+      let settled = self.simulate(&[id], |e| e.value, None)?;
+      self.store(&[id], settled);
+      return Ok(self.elements[id].as_ref().and_then(|e| e.value).unwrap_or(0));
+   }
+
+   // Caches freshly simulated values, keeping count of what was and wasn't
+   // already cached for the queries.
+   fn store(&mut self, queries: &[usize], settled: Vec<(usize, u16)>) {
+      let fresh: HashSet<usize> = settled.iter().map(|&(wire, _)| wire).collect();
+      self.cache_stats.hits += queries.iter().filter(|id| !fresh.contains(id)).count();
+      for (wire, v) in settled {
+         let cached_inputs = self.elements[wire].as_ref().unwrap().wire_operands().filter(|w| !fresh.contains(w)).count();
+         self.cache_stats.hits += cached_inputs;
+         self.cache(wire, v);
+         if let Some(warning) = self.shift_warning(wire) {
+            warn!(target: LOG_TARGET, "{}", warning);
+         }
+      }
+   }
+
+   // Caches a value just computed for id.
+   fn cache(&mut self, id: usize, v: u16) {
+      let e = self.elements[id].as_mut().unwrap();
+      self.cache_stats.misses += 1;
+      if e.computed {
+         self.cache_stats.recomputed += 1;
+      }
+      e.computed = true;
+      e.set_value(v);
+   }
+
+   // Counts of cache hits and misses since the circuit was made or
+   // reset_cache_stats was last called.
+   pub fn cache_stats(&self) -> CacheStats {
+      self.cache_stats.clone()
+   }
+
+   pub fn reset_cache_stats(&mut self) {
+      self.cache_stats = CacheStats::default();
+   }
+
+   // A complaint about id if it's a shift by 16 or more, which always
+   // gives 0 and is probably a mistake.  Shift amounts that come from a
+   // wire are only checked once that wire has been resolved.
+   fn shift_warning(&self, id: usize) -> Option<String> {
+      let e = self.elements[id].as_ref()?;
+      if e.spec.op != Operation::LShift && e.spec.op != Operation::RShift {
+         return None;
+      }
+      let amount = match e.operands[1] {
+         Operand::Value(v) => v,
+         Operand::Wire(w) => self.elements[w].as_ref().and_then(|w| w.value)?,
+      };
+      if amount < 16 {
+         return None;
+      }
+      return Some(format!("{} = {} shifts by {}, which always gives 0", self.names[id], e.spec, amount));
+   }
+
+   // The values of several wires at once, in the order given, resolved in
+   // a single pass so anything they share is only visited once.
+   pub fn get_values(&mut self, names: &[&str]) -> Result<Vec<(String, u16)>, CircuitError> {
+      let mut ids = Vec::new();
+      for name in names {
+         match self.id(name) {
+            Some(id) if self.elements[id].is_some() => ids.push(id),
+            _ => return Err(CircuitError::Undefined(name.to_string())),
+         }
+      }
+      let settled = self.simulate(&ids, |e| e.value, None)?;
+      self.store(&ids, settled);
+      return Ok(names.iter().zip(ids).map(|(name, id)| {
+         (name.to_string(), self.elements[id].as_ref().unwrap().value.unwrap())
+      }).collect());
+   }
+
+   // The value of name, or an error if it (or anything it depends on) is
+   // undefined or circular.
+   pub fn get_value(&mut self, name: &str) -> Result<u16, CircuitError> {
+      match self.id(name) {
+         Some(id) if self.elements[id].is_some() => self.resolve(id),
+         _ => Err(CircuitError::Undefined(name.to_string())),
+      }
+   }
+
+   // Every wire target depends on, in an order they could be computed in:
+   // each wire comes after all of its inputs, and target comes last.
+   pub fn evaluation_order(&self, target: &str) -> Result<Vec<String>, CircuitError> {
+      let id = match self.id(target) {
+         Some(id) if self.elements[id].is_some() => id,
+         _ => return Err(CircuitError::Undefined(target.to_string())),
+      };
+      let order = self.simulate(&[id], |_| None, None::<u16>)?;
+      return Ok(order.into_iter().map(|(wire, _)| self.names[wire].clone()).collect());
+   }
+
+   // The value of each lane of a bus like `x[7:0]` (or a concatenation
+   // like `{x[3:0], y}`), most significant first.
+   pub fn get_bus(&mut self, bus: &str) -> Result<Vec<u16>, CircuitError> {
+      let lanes = bus::lanes(&bus.replace(" ", "")).map_err(|_| CircuitError::Undefined(bus.to_string()))?;
+      return match lanes {
+         Some(lanes) => lanes.iter().map(|lane| self.get_value(lane)).collect(),
+         None => Ok(vec![self.get_value(bus)?]),
+      };
+   }
+
+   // Every wire that's read somewhere but never defined, sorted.  These are
+   // usually typos in the netlist.
+   pub fn undefined(&self) -> Vec<&str> {
+      let mut wires: Vec<&str> = (0..self.names.len())
+         .filter(|&id| self.elements[id].is_none() && !self.dependents[id].is_empty())
+         .map(|id| &self.names[id][..])
+         .collect();
+      wires.sort();
+      return wires;
+   }
+
+   // Four-valued simulation, where undefined wires float at Z and unknowns
+   // propagate through the gates they feed.  Nothing is cached.
+   pub fn get_logic(&self, name: &str) -> Result<Logic, CircuitError> {
+      let id = match self.id(name) {
+         Some(id) if self.elements[id].is_some() => id,
+         _ => return Ok(Logic::undriven()),
+      };
+      let order = self.simulate(&[id], |_| None, Some(Logic::undriven()))?;
+      return Ok(order.last().unwrap().1);
+   }
+
+   // Forgets every resolved value, including forced ones.
+   pub fn clear_cache(&mut self) {
+      let mut ids = self.defined();
+      ids.sort_by(|&a, &b| self.names[a].cmp(&self.names[b]));
+      for id in ids {
+         self.elements[id].as_mut().unwrap().clear_value();
+      }
+      self.overrides.clear();
+      self.forced.clear();
+   }
+
+   // Pins name to val.  Only the wires downstream of it are forgotten, so
+   // the rest of the circuit doesn't need resolving again.
+   pub fn force_value(&mut self, name: &str, val: u16) {
+      if let Some(id) = self.id(name).filter(|&id| self.elements[id].is_some()) {
+         self.remember_force(id);
+      }
+      self.pin(name, val);
+   }
+
+   // Keeps what id was pinned to, for undo_force, unless it's been forced
+   // already and that's kept from the first time.
+   fn remember_force(&mut self, id: usize) {
+      if !self.forced.iter().any(|&(w, _)| w == id) {
+         let previous = self.overrides.get(&id).cloned();
+         self.forced.push((id, previous));
+      }
+   }
+
+   // force_value without an undo step, for searches that try lots of values.
+   fn pin(&mut self, name: &str, val: u16) {
+      let id = match self.id(name) {
+         Some(id) if self.elements[id].is_some() => id,
+         _ => return,
+      };
+      self.elements[id].as_mut().unwrap().set_value(val);
+      self.overrides.insert(id, val);
+      self.invalidate_dependents(id);
+   }
+
+   // Backs out force_value of name, however many times it's been forced,
+   // putting back what it was pinned to before the first, if anything, and
+   // forgetting whatever depended on it.  Returns false if name wasn't
+   // forced.
+   pub fn undo_force(&mut self, name: &str) -> bool {
+      let id = match self.id(name) {
+         Some(id) if self.overrides.contains_key(&id) => id,
+         _ => return false,
+      };
+      let previous = match self.forced.iter().position(|&(w, _)| w == id) {
+         Some(i) => self.forced.remove(i).1,
+         None => None,
+      };
+      match previous {
+         Some(v) => self.pin(name, v),
+         None => {
+            self.overrides.remove(&id);
+            self.elements[id].as_mut().unwrap().clear_value();
+            self.invalidate_dependents(id);
+         }
+      }
+      return true;
+   }
+
+   // Backs out every forced wire, keeping everything they didn't affect.
+   pub fn undo_all(&mut self) {
+      let mut ids: Vec<usize> = self.overrides.keys().cloned().collect();
+      ids.sort();
+      self.overrides.clear();
+      self.forced.clear();
+      for id in ids {
+         self.elements[id].as_mut().unwrap().clear_value();
+         self.invalidate_dependents(id);
+      }
+   }
+
+   // Part two's trick: resolves from, forces that value onto to, and
+   // resolves from again with everything downstream of to recomputed.
+   pub fn feedback(&mut self, from: &str, to: &str) -> Result<u16, CircuitError> {
+      if self.element(to).is_none() {
+         return Err(CircuitError::Undefined(to.to_string()));
+      }
+      let v = self.get_value(from)?;
+      self.force_value(to, v);
+      return self.get_value(from);
+   }
+
+   // Looks for a value to force onto vary that makes target come out as
+   // want.  If target turns out to rise or fall steadily with vary a binary
+   // search finds it quickly; failing that every value is tried, which is
+   // still cheap since only vary's dependents are recomputed each time.
+   // vary is left forced to the answer, or to the last value tried.
+   pub fn solve(&mut self, target: &str, want: u16, vary: &str) -> Result<Option<u16>, CircuitError> {
+      if self.element(vary).is_none() {
+         return Err(CircuitError::Undefined(vary.to_string()));
+      }
+      // One undo step for the whole search.
+      self.force_value(vary, 0);
+      let try_value = |circuit: &mut Circuit, v: u16| {
+         circuit.pin(vary, v);
+         circuit.get_value(target)
+      };
+
+      let (low, high) = (try_value(self, 0)?, try_value(self, 0xffff)?);
+      let rising = low <= high;
+      let (mut lo, mut hi) = (0u32, 0x10000u32);
+      while lo < hi {
+         let mid = (lo + hi) / 2;
+         let v = try_value(self, mid as u16)?;
+         if (rising && v < want) || (!rising && v > want) {
+            lo = mid + 1;
+         } else {
+            hi = mid;
+         }
+      }
+      if lo <= 0xffff && try_value(self, lo as u16)? == want {
+         return Ok(Some(lo as u16));
+      }
+
+      for v in 0..=0xffff {
+         if try_value(self, v)? == want {
+            return Ok(Some(v));
+         }
+      }
+      return Ok(None);
+   }
+
+   // Forgets the value of everything downstream of id, stopping at forced
+   // wires.  A wire without a value can't have resolved dependents (short
+   // of them being forced), so there's no need to look past one.  What was
+   // cleared is logged afterwards by name, since the walk's own order
+   // depends on how the netlist was laid out.
+   fn invalidate_dependents(&mut self, id: usize) {
+      let mut cleared = Vec::new();
+      let mut todo = vec![id];
+      while let Some(wire) = todo.pop() {
+         for i in 0..self.dependents[wire].len() {
+            let d = self.dependents[wire][i];
+            if self.overrides.contains_key(&d) {
+               continue;
+            }
+            let e = self.elements[d].as_mut().unwrap();
+            if e.value.is_some() {
+               e.value = None;
+               cleared.push(d);
+               todo.push(d);
+            }
+         }
+      }
+      if enabled!(target: LOG_TARGET, tracing::Level::DEBUG) {
+         let mut names: Vec<&str> = cleared.iter().map(|&d| &self.names[d][..]).collect();
+         names.sort();
+         for name in names {
+            debug!(target: LOG_TARGET, "Clearing {}", name);
+         }
+      }
+   }
+
+   // Resolves, up front, every wire that only depends on literal values
+   // (forced wires count as literals too), and returns how many there were.
+   // Those values never need recomputing, since force_value only
+   // invalidates what's downstream of the wire being forced, so loops of
+   // forcing an input and querying again only redo the part of the circuit
+   // that input actually feeds.  Running past the circuit's Limits keeps
+   // whatever had been worked out by then.
+   pub fn precompute_constants(&mut self) -> Result<usize, CircuitError> {
+      let mut waiting: Vec<usize> = self.elements.iter().map(|e| match *e {
+         Some(ref e) if e.value.is_none() => e.wire_operands().count(),
+         Some(_) => 0,
+         None => usize::MAX,
+      }).collect();
+      let mut ready: Vec<usize> = self.defined().into_iter().filter(|&id| waiting[id] == 0).collect();
+      let mut computed = 0;
+      let mut budget = Budget::start(self.limits);
+      while let Some(id) = ready.pop() {
+         if self.elements[id].as_ref().unwrap().value.is_none() {
+            budget.step()?;
+            let e = self.elements[id].as_ref().unwrap();
+            let operand = |o: Operand| match o {
+               Operand::Value(v) => v,
+               Operand::Wire(w) => self.elements[w].as_ref().unwrap().value.unwrap(),
+            };
+            let v = e.spec.evaluate(operand(e.operands[0]), operand(e.operands[1]));
+            self.cache(id, v);
+            computed += 1;
+         }
+         for &d in &self.dependents[id] {
+            if waiting[d] > 0 && waiting[d] != usize::MAX {
+               waiting[d] -= 1;
+               if waiting[d] == 0 {
+                  ready.push(d);
+               }
+            }
+         }
+      }
+      return Ok(computed);
+   }
+
+   // Resolves every wire, returning them all sorted by name.
+   pub fn resolve_all(&mut self) -> Result<Vec<(String, u16)>, CircuitError> {
+      let mut ids = self.defined();
+      ids.sort_by(|&a, &b| self.names[a].cmp(&self.names[b]));
+      let mut values = Vec::with_capacity(ids.len());
+      for id in ids {
+         let v = self.resolve(id)?;
+         values.push((self.names[id].clone(), v));
+      }
+      return Ok(values);
+   }
+
+   fn id_levels(&self) -> Result<Vec<Vec<usize>>, CircuitError> {
+      if let Some(wire) = self.undefined().first() {
+         return Err(CircuitError::Undefined(wire.to_string()));
+      }
+      let unresolved = |w: usize| self.elements[w].as_ref().is_some_and(|e| e.value.is_none());
+      let mut waiting_on = HashMap::new();
+      let mut level = Vec::new();
+      for id in self.defined().into_iter().filter(|&id| unresolved(id)) {
+         let pending = self.elements[id].as_ref().unwrap().wire_operands().filter(|&w| unresolved(w)).count();
+         if pending == 0 {
+            level.push(id);
+         }
+         waiting_on.insert(id, pending);
+      }
+
+      let mut levels = Vec::new();
+      let mut placed = 0;
+      while !level.is_empty() {
+         let mut next = Vec::new();
+         for &id in &level {
+            for &d in &self.dependents[id] {
+               if let Some(count) = waiting_on.get_mut(&d) {
+                  *count -= 1;
+                  if *count == 0 {
+                     next.push(d);
+                  }
+               }
+            }
+         }
+         placed += level.len();
+         levels.push(level);
+         level = next;
+      }
+
+      // Anything left over is stuck behind a loop; walk from one of them to
+      // find out where it is.
+      if placed < waiting_on.len() {
+         let mut stuck: Vec<usize> = waiting_on.iter().filter(|&(_, &c)| c > 0).map(|(&id, _)| id).collect();
+         stuck.sort_by(|&a, &b| self.names[a].cmp(&self.names[b]));
+         return Err(self.simulate(&stuck[..1], |e| e.value, None).err().unwrap());
+      }
+      return Ok(levels);
+   }
+
+   // Groups the unresolved wires into levels, where each wire's inputs are
+   // all either in an earlier level or already resolved.  Everything within
+   // a level can then be evaluated independently.
+   pub fn levels(&self) -> Result<Vec<Vec<String>>, CircuitError> {
+      let levels = self.id_levels()?;
+      return Ok(levels.into_iter().map(|level| {
+         let mut names: Vec<String> = level.into_iter().map(|id| self.names[id].clone()).collect();
+         names.sort();
+         names
+      }).collect());
+   }
+
+   // Like resolve_all, but evaluates each of levels() in parallel.
+   pub fn par_resolve_all(&mut self) -> Result<Vec<(String, u16)>, CircuitError> {
+      let fresh = self.resolve_levels(true, |_, _| ())?;
+      let mut ids = self.defined();
+      ids.sort_by(|&a, &b| self.names[a].cmp(&self.names[b]));
+      // Whatever was already cached is a hit, as it is for resolve_all.
+      self.cache_stats.hits += ids.iter().filter(|id| !fresh.contains(id)).count();
+      return Ok(ids.into_iter().map(|id| (self.names[id].clone(), self.elements[id].as_ref().unwrap().value.unwrap())).collect());
+   }
+
+   // Like resolve_all, but a level at a time, calling on_level with the
+   // circuit and the wires that just got their values after each one.
+   pub fn resolve_by_level<F>(&mut self, mut on_level: F) -> Result<(), CircuitError>
+      where F: FnMut(&Circuit, &[String]) {
+      self.resolve_levels(false, |circuit, level| {
+         let mut names: Vec<String> = level.iter().map(|&id| circuit.names[id].clone()).collect();
+         names.sort();
+         on_level(circuit, &names);
+      })?;
+      return Ok(());
+   }
+
+   // Evaluates every level from id_levels in turn, calling on_level after
+   // each, and returns the wires that got their values.  Each wire is a
+   // step of the circuit's Limits, taken before its level is evaluated,
+   // since there's no stopping a level halfway when it's in parallel; the
+   // levels before one that would run past them are kept.
+   fn resolve_levels<F>(&mut self, parallel: bool, mut on_level: F) -> Result<HashSet<usize>, CircuitError>
+      where F: FnMut(&Circuit, &[usize]) {
+      let levels = self.id_levels()?;
+      let fresh: HashSet<usize> = levels.iter().flatten().cloned().collect();
+      let mut budget = Budget::start(self.limits);
+      for level in levels {
+         for _ in &level {
+            budget.step()?;
+         }
+         self.resolve_level(&level, parallel, &fresh);
+         on_level(self, &level);
+      }
+      return Ok(fresh);
+   }
+
+   // Evaluates a level from id_levels, whose inputs must all be resolved,
+   // and caches what it gets like resolve does: inputs from outside fresh
+   // were already cached, so are hits.
+   fn resolve_level(&mut self, level: &[usize], parallel: bool, fresh: &HashSet<usize>) {
+      let values: Vec<u16> = {
+         let elements = &self.elements;
+         let settled = |operand: Operand| match operand {
+            Operand::Value(v) => v,
+            Operand::Wire(w) => elements[w].as_ref().and_then(|e| e.value).unwrap(),
+         };
+         let evaluate = |&id: &usize| {
+            let e = elements[id].as_ref().unwrap();
+            e.spec.evaluate(settled(e.operands[0]), settled(e.operands[1]))
+         };
+         if parallel {
+            level.par_iter().map(evaluate).collect()
+         } else {
+            level.iter().map(evaluate).collect()
+         }
+      };
+      for (&id, v) in level.iter().zip(values) {
+         self.cache_stats.hits += self.elements[id].as_ref().unwrap().wire_operands().filter(|w| !fresh.contains(w)).count();
+         self.cache(id, v);
+      }
+   }
+
+   fn input_value(&mut self, input: &Input) -> Result<u16, CircuitError> {
+      match input {
+         &Input::None => Ok(0),
+         &Input::Value(v) => Ok(v),
+         &Input::Element(ref e) => self.get_value(e),
+      }
+   }
+
+   // Evaluates a spec that isn't part of the circuit against its wires.
+   pub fn evaluate_spec(&mut self, spec: &ElementSpec) -> Result<u16, CircuitError> {
+      let left = self.input_value(&spec.left)?;
+      let right = self.input_value(&spec.right)?;
+      return Ok(spec.evaluate(left, right));
+   }
+
+   // The dependency tree of name, one wire per line with its spec and
+   // value, indented under the gate it feeds.  Each wire is only expanded
+   // the first time it shows up so shared subtrees don't repeat.
+   pub fn explain(&mut self, name: &str) -> Result<String, CircuitError> {
+      self.get_value(name)?;
+      let mut out = String::new();
+      let mut seen = HashSet::new();
+      let mut stack = vec![(name.to_string(), 0)];
+      while let Some((wire, depth)) = stack.pop() {
+         let indent = "   ".repeat(depth);
+         let element = self.element(&wire).unwrap();
+         let value = element.value.unwrap();
+         if !seen.insert(wire.clone()) {
+            out.push_str(&format!("{}{} ({}, see above)\n", indent, wire, value));
+            continue;
+         }
+         out.push_str(&format!("{}{} = {} ({})\n", indent, wire, element.spec, value));
+         for input in element.spec.wires().into_iter().rev() {
+            stack.push((input.to_string(), depth + 1));
+         }
+      }
+      return Ok(out);
+   }
+
+   pub fn gate_count(&self) -> usize {
+      self.elements.iter().filter_map(|e| e.as_ref()).filter(|e| e.spec.op != Operation::Value).count()
+   }
+
+   // Rewrites gates into simpler equivalents:
+   //  * wires driven by a constant are substituted into the gates they feed,
+   //  * gates with only constant inputs become constants,
+   //  * identities like `x AND 0`, `x OR 0` and `NOT (NOT x)` collapse,
+   //  * gates reading an alias read the wire it's an alias of instead.
+   // This repeats until nothing changes, and returns how many gates were
+   // eliminated.  Since constants get baked into their consumers and alias
+   // chains are skipped over, forcing a folded wire or an alias afterwards
+   // no longer affects anything downstream of it.
+   pub fn optimize(&mut self) -> usize {
+      let before = self.gate_count();
+      loop {
+         let mut constants = HashMap::new();
+         for e in self.elements.iter().filter_map(|e| e.as_ref()) {
+            if let ElementSpec { op: Operation::Value, left: Input::Value(v), .. } = e.spec {
+               constants.insert(e.name.clone(), v);
+            }
+         }
+
+         let mut changed = false;
+         for id in self.defined() {
+            if let Some(spec) = self.simplify(&self.elements[id].as_ref().unwrap().spec, &constants) {
+               self.set_spec(id, spec);
+               changed = true;
+            }
+         }
+         if !changed {
+            break;
+         }
+      }
+      return before - self.gate_count();
+   }
+
+   // One round of optimize() for a single spec, or None if it can't be
+   // simplified any further.
+   fn simplify(&self, spec: &ElementSpec, constants: &HashMap<String, u16>) -> Option<ElementSpec> {
+      let substitute = |input: &Input| match input {
+         &Input::Element(ref e) if constants.contains_key(e) => Input::Value(constants[e]),
+         &Input::Element(ref e) if self.alias_of(e).is_some() => Input::from(self.alias_of(e).unwrap()),
+         other => other.clone(),
+      };
+      let mut new = ElementSpec{left: substitute(&spec.left), right: substitute(&spec.right), op: spec.op.clone()};
+
+      let constant = |input: &Input| match input {
+         &Input::Value(v) => Some(v),
+         &Input::None => Some(0),
+         &Input::Element(_) => None,
+      };
+      match (&new.op, constant(&new.left), constant(&new.right)) {
+         (&Operation::Value, _, _) => (),
+         (_, Some(l), Some(r)) => new = ElementSpec::value(Input::Value(new.evaluate(l, r))),
+         (&Operation::And, Some(0), _) | (&Operation::And, _, Some(0)) |
+         (&Operation::Mul, Some(0), _) | (&Operation::Mul, _, Some(0)) =>
+            new = ElementSpec::value(Input::Value(0)),
+         (&Operation::Or, _, Some(0)) | (&Operation::Xor, _, Some(0)) |
+         (&Operation::LShift, _, Some(0)) | (&Operation::RShift, _, Some(0)) |
+         (&Operation::Add, _, Some(0)) | (&Operation::Sub, _, Some(0)) | (&Operation::Mul, _, Some(1)) =>
+            new = ElementSpec::value(new.left.clone()),
+         (&Operation::Or, Some(0), _) | (&Operation::Xor, Some(0), _) |
+         (&Operation::Add, Some(0), _) | (&Operation::Mul, Some(1), _) =>
+            new = ElementSpec::value(new.right.clone()),
+         (&Operation::Not, _, _) => {
+            if let Input::Element(ref e) = new.left {
+               if let Some(&Element { spec: ElementSpec { op: Operation::Not, left: ref inner, .. }, .. }) = self.element(e) {
+                  new = ElementSpec::value(inner.clone());
+               }
+            }
+         }
+         _ => (),
+      }
+      return if new != *spec { Some(new) } else { None };
+   }
+
+   // How many gates read each wire, busiest first, then by name.  A gate
+   // reading the same wire twice, like `x AND x`, only counts once.  Wires
+   // that are read but never defined are included, since they're the
+   // inputs everything else hangs off.
+   pub fn fan_out(&self) -> Vec<(String, usize)> {
+      let mut counts: Vec<(String, usize)> = (0..self.names.len()).map(|id| {
+         let mut readers = self.dependents[id].clone();
+         readers.sort();
+         readers.dedup();
+         (self.names[id].clone(), readers.len())
+      }).collect();
+      counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+      return counts;
+   }
+
+   // If name is a plain passthrough like `x -> y`, or a chain of them, the
+   // wire at the far end that actually computes something (or is never
+   // defined).  None for anything else, including passthroughs that loop.
+   pub fn alias_of<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+      let mut seen = HashSet::new();
+      let mut current = name;
+      while let Some(&Element { spec: ElementSpec { op: Operation::Value, left: Input::Element(ref e), .. }, .. }) = self.element(current) {
+         if !seen.insert(current) {
+            return None;
+         }
+         current = e;
+      }
+      return if current != name { Some(current) } else { None };
+   }
+
+   // Every alias in the circuit with the wire it stands for, sorted by alias.
+   pub fn aliases(&self) -> Vec<(&str, &str)> {
+      self.sorted_names().into_iter().filter_map(|name| self.alias_of(name).map(|c| (name, c))).collect()
+   }
+
+   fn reachable_ids(&self, target: &str) -> HashSet<usize> {
+      let mut seen = HashSet::new();
+      let mut todo: Vec<usize> = self.id(target).into_iter().collect();
+      while let Some(id) = todo.pop() {
+         if let Some(ref e) = self.elements[id] {
+            if seen.insert(id) {
+               todo.extend(e.wire_operands());
+            }
+         }
+      }
+      return seen;
+   }
+
+   // The longest chain of gates feeding target, counting a unit delay per
+   // gate (plain wire-to-wire assignments are free).  Returns the delay and
+   // the wires along the chain, starting from where it begins.
+   pub fn critical_path(&self, target: &str) -> Result<(usize, Vec<String>), CircuitError> {
+      let id = match self.id(target) {
+         Some(id) if self.elements[id].is_some() => id,
+         _ => return Err(CircuitError::Undefined(target.to_string())),
+      };
+      // simulate settles wires inputs first, which is just the order we need.
+      let order = self.simulate(&[id], |_| None, Some(0u16))?;
+      let mut delay: HashMap<usize, (usize, Option<usize>)> = HashMap::new();
+      for (wire, _) in order {
+         let e = self.elements[wire].as_ref().unwrap();
+         let mut longest = (0, None);
+         for w in e.wire_operands().filter(|w| delay.contains_key(w)) {
+            if longest.1.is_none() || delay[&w].0 > longest.0 {
+               longest = (delay[&w].0, Some(w));
+            }
+         }
+         let own = if e.spec.op == Operation::Value { 0 } else { 1 };
+         delay.insert(wire, (longest.0 + own, longest.1));
+      }
+
+      let mut path = vec![self.names[id].clone()];
+      let mut at = id;
+      while let Some(prev) = delay[&at].1 {
+         path.push(self.names[prev].clone());
+         at = prev;
+      }
+      path.reverse();
+      return Ok((delay[&id].0, path));
+   }
+
+   pub fn stats(&self) -> Result<Stats, CircuitError> {
+      let mut operations = BTreeMap::new();
+      let mut constant_inputs = 0;
+      let mut depth = 0;
+      for id in self.defined() {
+         let spec = &self.elements[id].as_ref().unwrap().spec;
+         *operations.entry(spec.op.to_string()).or_insert(0) += 1;
+         for input in &[&spec.left, &spec.right] {
+            if let &&Input::Value(_) = input {
+               constant_inputs += 1;
+            }
+         }
+         // Only wires nothing reads can be the end of the longest path.
+         if self.dependents[id].is_empty() {
+            depth = depth.max(self.critical_path(&self.names[id])?.0);
+         }
+      }
+      return Ok(Stats{
+         wires: self.len(),
+         operations: operations,
+         constant_inputs: constant_inputs,
+         depth: depth,
+         undefined: self.undefined().iter().map(|w| w.to_string()).collect(),
+      });
+   }
+
+   // Treats each of inputs as a free one-bit variable, whatever drives it,
+   // and evaluates wire under every assignment of 0s and 1s.  Rows come
+   // back in counting order, with the first input as the most significant
+   // bit.  The circuit itself is left alone.
+   pub fn truth_table(&self, wire: &str, inputs: &[&str]) -> Result<Vec<(Vec<u16>, u16)>, CircuitError> {
+      if inputs.len() > MAX_TRUTH_TABLE_INPUTS {
+         return Err(CircuitError::TooManyInputs(inputs.len()));
+      }
+      let mut scratch = self.clone();
+      scratch.clear_cache();
+      for input in inputs {
+         if scratch.element(input).is_none() {
+            scratch.add_element(input, "0").map_err(|_| CircuitError::Undefined(input.to_string()))?;
+         }
+      }
+      let mut rows = Vec::new();
+      for row in 0..1usize << inputs.len() {
+         let values: Vec<u16> = (0..inputs.len()).map(|i| (row >> (inputs.len() - 1 - i)) as u16 & 1).collect();
+         for (input, &v) in inputs.iter().zip(&values) {
+            scratch.pin(input, v);
+         }
+         let out = scratch.get_value(wire)?;
+         rows.push((values, out));
+      }
+      return Ok(rows);
+   }
+
+   // The undefined wires target reads, sorted, and whether every gate
+   // between them and target works on each bit independently.
+   fn free_inputs(&self, target: &str) -> (Vec<String>, bool) {
+      let mut inputs = HashSet::new();
+      let mut bitwise = true;
+      for id in self.reachable_ids(target) {
+         let e = self.elements[id].as_ref().unwrap();
+         inputs.extend(e.wire_operands().filter(|&w| self.elements[w].is_none()).map(|w| self.names[w].clone()));
+         bitwise &= !matches!(e.spec.op, Operation::LShift | Operation::RShift | Operation::Add | Operation::Sub | Operation::Mul);
+      }
+      let mut inputs: Vec<String> = inputs.into_iter().collect();
+      inputs.sort();
+      return (inputs, bitwise);
+   }
+
+   // Checks whether wire computes the same function of its free inputs
+   // (the undefined wires feeding it) here and in other, returning a
+   // counterexample if not.  Every assignment is tried, but when both sides
+   // are purely bitwise each bit can be checked at once, so inputs only
+   // need to be all zeros or all ones.  Otherwise every 16 bit value is
+   // tried, which is only feasible for a single input.
+   pub fn equivalent(&self, other: &Circuit, wire: &str) -> Result<Option<Vec<(String, u16)>>, CircuitError> {
+      let (mut inputs, mut bitwise) = self.free_inputs(wire);
+      let (other_inputs, other_bitwise) = other.free_inputs(wire);
+      inputs.extend(other_inputs);
+      inputs.sort();
+      inputs.dedup();
+      bitwise &= other_bitwise;
+
+      let choices: u64 = if bitwise { 2 } else { 1 << 16 };
+      let total = choices.checked_pow(inputs.len() as u32).unwrap_or(u64::MAX);
+      if total > MAX_EQUIVALENCE_ASSIGNMENTS {
+         return Err(CircuitError::TooManyInputs(inputs.len()));
+      }
+
+      let mut left = self.clone();
+      let mut right = other.clone();
+      for scratch in &mut [&mut left, &mut right] {
+         scratch.clear_cache();
+         for input in &inputs {
+            if scratch.element(input).is_none() {
+               scratch.add_element(input, "0").unwrap();
+            }
+         }
+      }
+      for n in 0..total {
+         let mut assignment = Vec::with_capacity(inputs.len());
+         let mut rest = n;
+         for input in &inputs {
+            let v = if bitwise { (rest % 2) as u16 * 0xffff } else { (rest % choices) as u16 };
+            rest /= choices;
+            left.pin(input, v);
+            right.pin(input, v);
+            assignment.push((input.clone(), v));
+         }
+         if left.get_value(wire)? != right.get_value(wire)? {
+            return Ok(Some(assignment));
+         }
+      }
+      return Ok(None);
+   }
+
+   // Every feedback loop in the circuit, as the sorted names of the wires
+   // caught up in it.  These are the strongly connected components of the
+   // wire graph with more than one wire, or a wire that reads itself, found
+   // with Tarjan's algorithm.  It's run with an explicit stack so long
+   // chains of wires can't overflow.
+   pub fn feedback_loops(&self) -> Vec<Vec<String>> {
+      let inputs = |id: usize| -> Vec<usize> {
+         self.elements[id].as_ref().unwrap().wire_operands().filter(|&w| self.elements[w].is_some()).collect()
+      };
+      let n = self.elements.len();
+      let mut index = vec![None; n];
+      let mut low = vec![0; n];
+      let mut on_stack = vec![false; n];
+      let mut stack = Vec::new();
+      let mut next_index = 0;
+      let mut loops = Vec::new();
+
+      for root in self.defined() {
+         if index[root].is_some() {
+            continue;
+         }
+         // Each frame is a wire and how many of its inputs we've looked at.
+         let mut calls = vec![(root, 0)];
+         index[root] = Some(next_index);
+         low[root] = next_index;
+         next_index += 1;
+         stack.push(root);
+         on_stack[root] = true;
+
+         while let Some(&mut (v, ref mut i)) = calls.last_mut() {
+            let edges = inputs(v);
+            if *i < edges.len() {
+               let w = edges[*i];
+               *i += 1;
+               match index[w] {
+                  None => {
+                     index[w] = Some(next_index);
+                     low[w] = next_index;
+                     next_index += 1;
+                     stack.push(w);
+                     on_stack[w] = true;
+                     calls.push((w, 0));
+                  }
+                  Some(iw) if on_stack[w] => low[v] = low[v].min(iw),
+                  Some(_) => (),
+               }
+               continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+               low[parent] = low[parent].min(low[v]);
+            }
+            if Some(low[v]) == index[v] {
+               let mut component = Vec::new();
+               loop {
+                  let w = stack.pop().unwrap();
+                  on_stack[w] = false;
+                  component.push(self.names[w].clone());
+                  if w == v {
+                     break;
+                  }
+               }
+               if component.len() > 1 || edges.contains(&v) {
+                  component.sort();
+                  loops.push(component);
+               }
+            }
+         }
+      }
+      loops.sort();
+      return loops;
+   }
+
+   // Every defined wire that target depends on, including itself.
+   pub fn reachable(&self, target: &str) -> HashSet<String> {
+      self.reachable_ids(target).into_iter().map(|id| self.names[id].clone()).collect()
+   }
+
+   // Drops every element that doesn't feed target, returning how many went.
+   pub fn prune(&mut self, target: &str) -> usize {
+      let keep = self.reachable_ids(target);
+      let mut pruned = 0;
+      for id in self.defined() {
+         if !keep.contains(&id) {
+            self.unlink(id);
+            self.elements[id] = None;
+            self.overrides.remove(&id);
+            self.forced.retain(|&(w, _)| w != id);
+            pruned += 1;
+         }
+      }
+      return pruned;
+   }
+
+   // A Verilog module with a 16 bit net per wire and an assign per gate.
+   // Wires that are read but never defined become inputs, and wires nothing
+   // reads become outputs.  Nets are prefixed so puzzle wires like `if` or
+   // `or` don't collide with keywords.
+   pub fn to_verilog(&self) -> String {
+      let names = self.sorted_names();
+      let mut used = HashSet::new();
+      for e in self.elements.iter().filter_map(|e| e.as_ref()) {
+         used.extend(e.spec.wires());
+      }
+      let mut inputs: Vec<&str> = used.iter().cloned().filter(|w| self.element(w).is_none()).collect();
+      inputs.sort();
+
+      let net = |input: &Input| match input {
+         &Input::Value(v) => format!("16'd{}", v),
+         &Input::Element(ref e) => format!("w_{}", e),
+         &Input::None => "16'd0".to_string(),
+      };
+      let mut ports = Vec::new();
+      for name in &inputs {
+         ports.push(format!("   input wire [15:0] w_{}", name));
+      }
+      for name in names.iter().filter(|n| !used.contains(*n)) {
+         ports.push(format!("   output wire [15:0] w_{}", name));
+      }
+
+      let mut out = format!("module circuit(\n{}\n);\n", ports.join(",\n"));
+      for name in names.iter().filter(|n| used.contains(*n)) {
+         out.push_str(&format!("   wire [15:0] w_{};\n", name));
+      }
+      for name in &names {
+         let spec = &self.element(name).unwrap().spec;
+         let (l, r) = (net(&spec.left), net(&spec.right));
+         let expr = match spec.op {
+            Operation::Value => l,
+            Operation::Not => format!("~{}", l),
+            Operation::And => format!("{} & {}", l, r),
+            Operation::Or => format!("{} | {}", l, r),
+            Operation::LShift => format!("{} << {}", l, r),
+            Operation::RShift => format!("{} >> {}", l, r),
+            Operation::Xor => format!("{} ^ {}", l, r),
+            Operation::Nand => format!("~({} & {})", l, r),
+            Operation::Nor => format!("~({} | {})", l, r),
+            Operation::Add => format!("{} + {}", l, r),
+            Operation::Sub => format!("{} - {}", l, r),
+            Operation::Mul => format!("{} * {}", l, r),
+         };
+         out.push_str(&format!("   assign w_{} = {};\n", name, expr));
+      }
+      out.push_str("endmodule\n");
+      return out;
+   }
+
+   // The whole circuit as JSON, including any values resolved so far, so
+   // work can be picked up again later with from_json.
+   pub fn to_json(&self) -> String {
+      serde_json::to_string(self).unwrap()
+   }
+
+   // Every wire's value as a JSON object, like `{"a":46065,"b":1234}`, with
+   // the wires sorted by name.
+   pub fn values_json(&mut self) -> Result<String, CircuitError> {
+      let values: BTreeMap<String, u16> = self.resolve_all()?.into_iter().collect();
+      return Ok(serde_json::to_string(&values).unwrap());
+   }
+
+   pub fn from_json(s: &str) -> Result<Circuit, serde_json::Error> {
+      let mut circuit: Circuit = serde_json::from_str(s)?;
+      circuit.reindex();
+      return Ok(circuit);
+   }
+
+   // Graphviz rendering: one node per gate, labeled with its operation and
+   // any constant operands, and an edge from each wire to the gates it feeds.
+   // Aliases don't get nodes of their own; they're listed on the node of
+   // the wire they stand for.
+   pub fn to_dot(&self) -> String {
+      let mut aliases: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+      for (alias, wire) in self.aliases() {
+         aliases.entry(wire).or_default().push(alias);
+      }
+      let names: Vec<&str> = self.sorted_names().into_iter().filter(|n| self.alias_of(n).is_none()).collect();
+      let mut nodes = names.clone();
+      nodes.extend(aliases.keys().filter(|w| self.element(w).is_none()));
+      nodes.sort();
+
+      let mut out = String::from("digraph circuit {\n");
+      for name in &nodes {
+         let mut label = name.to_string();
+         if let Some(e) = self.element(name) {
+            let detail = gate_detail(&e.spec);
+            if !detail.is_empty() {
+               label.push_str(&format!("\\n{}", detail.join(" ")));
+            }
+         }
+         if let Some(names) = aliases.get(name) {
+            label.push_str(&format!("\\n= {}", names.join(" = ")));
+         }
+         out.push_str(&format!("   \"{}\" [label=\"{}\"];\n", name, label));
+      }
+      for name in &names {
+         for e in self.element(name).unwrap().spec.wires() {
+            out.push_str(&format!("   \"{}\" -> \"{}\";\n", self.alias_of(e).unwrap_or(e), name));
+         }
+      }
+      out.push_str("}\n");
+      return out;
+   }
+}
+
+#[test]
+fn test_number() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x AND y").unwrap();
+   circuit.add_element("e", "x OR y").unwrap();
+   circuit.add_element("f", "x LSHIFT 2").unwrap();
+   circuit.add_element("g", "y RSHIFT 2").unwrap();
+   circuit.add_element("h", "NOT x").unwrap();
+   circuit.add_element("i", "NOT y").unwrap();
+
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+   assert_eq!(circuit.get_value("e").unwrap(), 507);
+   assert_eq!(circuit.get_value("f").unwrap(), 492);
+   assert_eq!(circuit.get_value("g").unwrap(), 114);
+   assert_eq!(circuit.get_value("h").unwrap(), 65412);
+   assert_eq!(circuit.get_value("i").unwrap(), 65079);
+   assert_eq!(circuit.get_value("x").unwrap(), 123);
+   assert_eq!(circuit.get_value("y").unwrap(), 456);
+}
+
+#[test]
+fn test_undo_force() {
+   let mut circuit: Circuit = "123 -> x\n456 -> y\nx AND y -> d\nNOT y -> e".parse().unwrap();
+   assert_eq!(circuit.get_value("e").unwrap(), 65079);
+   circuit.force_value("x", 1);
+   circuit.force_value("x", 0xffff);
+   assert_eq!(circuit.get_value("d").unwrap(), 456);
+   for _ in 0..1000 {
+      circuit.force_value("x", 0xffff);
+   }
+   assert_eq!(circuit.forced.len(), 1);
+
+   // Back to before the first force, not the one before last.
+   assert!(circuit.undo_force("x"));
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+   assert!(!circuit.undo_force("x"));
+   assert!(!circuit.undo_force("nope"));
+
+   circuit.force_value("x", 0);
+   circuit.force_value("y", 0);
+   assert_eq!(circuit.get_value("e").unwrap(), 65535);
+   circuit.undo_all();
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+   assert_eq!(circuit.get_value("e").unwrap(), 65079);
+   // Values the overrides never touched are kept.
+   circuit.force_value("x", 0);
+   circuit.undo_all();
+   assert_eq!(circuit.element("e").unwrap().value, Some(65079));
+}
+
+#[test]
+fn test_get_values() {
+   let mut circuit: Circuit = "123 -> x\n456 -> y\nx AND y -> d\nNOT d -> e\nNOT x -> f\ng -> g".parse().unwrap();
+   assert_eq!(circuit.get_values(&["e", "x", "d"]).unwrap(),
+              vec![("e".to_string(), 65463), ("x".to_string(), 123), ("d".to_string(), 72)]);
+   assert_eq!(circuit.element("f").unwrap().value, None);
+   circuit.force_value("d", 1);
+   assert_eq!(circuit.get_values(&["d", "e"]).unwrap(), vec![("d".to_string(), 1), ("e".to_string(), 65534)]);
+   assert!(circuit.get_values(&[]).unwrap().is_empty());
+   assert_eq!(circuit.get_values(&["f", "q"]), Err(CircuitError::Undefined("q".to_string())));
+   assert_eq!(circuit.get_values(&["f", "g"]), Err(CircuitError::Cycle(vec!["g".to_string(), "g".to_string()])));
+}
+
+#[test]
+fn test_shifts() {
+   let mut circuit: Circuit = "65535 -> x\n20 -> n\nx LSHIFT 16 -> a\nx RSHIFT n -> b\nx LSHIFT 15 -> c".parse().unwrap();
+   let ids: Vec<usize> = ["a", "b", "c"].iter().map(|w| circuit.id(w).unwrap()).collect();
+   assert_eq!(circuit.shift_warning(ids[0]), Some("a = x LSHIFT 16 shifts by 16, which always gives 0".to_string()));
+   assert_eq!(circuit.shift_warning(ids[1]), None);
+   assert_eq!(circuit.get_values(&["a", "b", "c"]).unwrap(),
+              vec![("a".to_string(), 0), ("b".to_string(), 0), ("c".to_string(), 32768)]);
+   assert_eq!(circuit.shift_warning(ids[1]), Some("b = x RSHIFT n shifts by 20, which always gives 0".to_string()));
+   assert_eq!(circuit.shift_warning(ids[2]), None);
+   assert_eq!(Signal::lshift(1u16, 65535), 0);
+   assert_eq!(Signal::rshift(0x8000u16, 15), 1);
+}
+
+#[test]
+fn test_extra_gates() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x XOR y").unwrap();
+   circuit.add_element("e", "x NAND y").unwrap();
+   circuit.add_element("f", "x NOR y").unwrap();
+   circuit.add_element("g", "x XOR x").unwrap();
+   circuit.add_element("h", "y NAND 65535").unwrap();
+   circuit.add_element("i", "x NOR 0").unwrap();
+
+   assert_eq!(circuit.get_value("d").unwrap(), 435);
+   assert_eq!(circuit.get_value("e").unwrap(), 65463);
+   assert_eq!(circuit.get_value("f").unwrap(), 65028);
+   assert_eq!(circuit.get_value("g").unwrap(), 0);
+   assert_eq!(circuit.get_value("h").unwrap(), 65079);
+   assert_eq!(circuit.get_value("i").unwrap(), 65412);
+}
+
+#[test]
+fn test_arithmetic() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "65535").unwrap();
+   circuit.add_element("y", "300").unwrap();
+   circuit.add_element("a", "x ADD 2").unwrap();
+   circuit.add_element("s", "1 SUB 3").unwrap();
+   circuit.add_element("m", "y MUL y").unwrap();
+   circuit.add_element("d", "(x ADD 1) SUB 1").unwrap();
+   assert_eq!(circuit.get_value("a").unwrap(), 1);
+   assert_eq!(circuit.get_value("s").unwrap(), 65534);
+   assert_eq!(circuit.get_value("m").unwrap(), (90000 % 65536) as u16);
+   assert_eq!(circuit.get_value("d").unwrap(), 65535);
+   assert_eq!(circuit.element("m").unwrap().spec.to_string(), "y MUL y");
+
+   circuit.add_element("u", "q ADD 1").unwrap();
+   assert_eq!(circuit.get_logic("u").unwrap().to_string(), "xxxxxxxxxxxxxxxx");
+   assert_eq!(circuit.get_logic("s").unwrap(), Logic::constant(65534));
+}
+
+#[test]
+fn test_four_state() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "65280").unwrap();
+   circuit.add_element("d", "x AND u").unwrap();
+   circuit.add_element("e", "x OR u").unwrap();
+   circuit.add_element("f", "NOT d").unwrap();
+   circuit.add_element("g", "u LSHIFT 4").unwrap();
+   circuit.add_element("h", "x RSHIFT u").unwrap();
+   circuit.add_element("i", "u").unwrap();
+   circuit.add_element("j", "x XOR d").unwrap();
+
+   assert_eq!(circuit.get_logic("u").unwrap().to_string(), "zzzzzzzzzzzzzzzz");
+   assert_eq!(circuit.get_logic("x").unwrap().to_string(), "1111111100000000");
+   assert_eq!(circuit.get_logic("d").unwrap().to_string(), "xxxxxxxx00000000");
+   assert_eq!(circuit.get_logic("e").unwrap().to_string(), "11111111xxxxxxxx");
+   assert_eq!(circuit.get_logic("f").unwrap().to_string(), "xxxxxxxx11111111");
+   assert_eq!(circuit.get_logic("g").unwrap().to_string(), "xxxxxxxxxxxx0000");
+   assert_eq!(circuit.get_logic("h").unwrap().to_string(), "xxxxxxxxxxxxxxxx");
+   assert_eq!(circuit.get_logic("i").unwrap().to_string(), "zzzzzzzzzzzzzzzz");
+   assert_eq!(circuit.get_logic("j").unwrap().to_string(), "xxxxxxxx00000000");
+
+   // Fully driven circuits agree with the plain simulation.
+   assert_eq!(circuit.get_logic("x").unwrap(), Logic::constant(circuit.get_value("x").unwrap()));
+}
+
+#[test]
+fn test_optimize() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "x LSHIFT 2").unwrap();
+   circuit.add_element("d", "u AND 0").unwrap();
+   circuit.add_element("e", "u OR 0").unwrap();
+   circuit.add_element("f", "NOT u").unwrap();
+   circuit.add_element("g", "NOT f").unwrap();
+   circuit.add_element("h", "y OR u").unwrap();
+   circuit.add_element("u", "v AND w").unwrap();
+
+   assert_eq!(circuit.optimize(), 4);
+   assert_eq!(circuit.element("y").unwrap().spec, ElementSpec::value(Input::Value(492)));
+   assert_eq!(circuit.element("d").unwrap().spec, ElementSpec::value(Input::Value(0)));
+   assert_eq!(circuit.element("e").unwrap().spec, ElementSpec::value(Input::Element("u".to_string())));
+   assert_eq!(circuit.element("g").unwrap().spec, ElementSpec::value(Input::Element("u".to_string())));
+   assert_eq!(circuit.element("h").unwrap().spec, "492 OR u".parse::<ElementSpec>().unwrap());
+   assert_eq!(circuit.gate_count(), 3);
+   assert_eq!(circuit.optimize(), 0);
+}
+
+#[test]
+fn test_critical_path() {
+   let circuit = "123 -> x\nq -> y\nx AND y -> d\nNOT d -> e\nx OR y -> f\ne -> g\nf XOR g -> a".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.critical_path("a").unwrap(), (3, ["x", "d", "e", "g", "a"].iter().map(|w| w.to_string()).collect()));
+   assert_eq!(circuit.critical_path("x").unwrap(), (0, vec!["x".to_string()]));
+   assert!(circuit.critical_path("q").is_err());
+}
+
+#[test]
+fn test_stats() {
+   let circuit = "123 -> x\nx AND y -> d\nNOT d -> e\nx LSHIFT 2 -> f\nf OR e -> a".parse::<Circuit>().unwrap();
+   let stats = circuit.stats().unwrap();
+   assert_eq!(stats.to_string(), "5 wires
+   1 AND
+   1 LSHIFT
+   1 NOT
+   1 OR
+   1 VALUE
+2 constant inputs
+3 gates deep
+1 undefined wires: y
+");
+}
+
+#[test]
+fn test_truth_table() {
+   let circuit = "p XOR q -> sum\np AND q -> carry\n1 -> q".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.truth_table("sum", &["p", "q"]).unwrap(),
+              vec![(vec![0, 0], 0), (vec![0, 1], 1), (vec![1, 0], 1), (vec![1, 1], 0)]);
+   assert_eq!(circuit.truth_table("carry", &["p"]).unwrap(), vec![(vec![0], 0), (vec![1], 1)]);
+   assert!(circuit.element("p").is_none());
+
+   let many: Vec<&str> = (0..17).map(|_| "p").collect();
+   assert_eq!(circuit.truth_table("sum", &many), Err(CircuitError::TooManyInputs(17)));
+   assert_eq!(circuit.truth_table("sum", &["q"]), Err(CircuitError::Undefined("p".to_string())));
+}
+
+#[test]
+fn test_equivalent() {
+   let original = "x AND y -> d\nx OR y -> e\nNOT d -> f\nf AND e -> a".parse::<Circuit>().unwrap();
+   let simplified = "x XOR y -> a".parse::<Circuit>().unwrap();
+   assert_eq!(original.equivalent(&simplified, "a").unwrap(), None);
+
+   let wrong = "x OR y -> a".parse::<Circuit>().unwrap();
+   assert_eq!(original.equivalent(&wrong, "a").unwrap(), Some(vec![("x".to_string(), 65535), ("y".to_string(), 65535)]));
+
+   let doubled = "x ADD x -> a".parse::<Circuit>().unwrap();
+   let shifted = "x LSHIFT 1 -> a".parse::<Circuit>().unwrap();
+   assert_eq!(doubled.equivalent(&shifted, "a").unwrap(), None);
+   assert!(doubled.equivalent(&"x MUL 3 -> a".parse::<Circuit>().unwrap(), "a").unwrap().is_some());
+   assert_eq!(doubled.equivalent(&"x ADD y -> a".parse::<Circuit>().unwrap(), "a"), Err(CircuitError::TooManyInputs(2)));
+}
+
+#[test]
+fn test_prune() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x AND 7").unwrap();
+   circuit.add_element("a", "d").unwrap();
+   circuit.add_element("e", "x OR y").unwrap();
+   circuit.add_element("f", "NOT e").unwrap();
+
+   let mut reachable: Vec<String> = circuit.reachable("a").into_iter().collect();
+   reachable.sort();
+   assert_eq!(reachable, vec!["a", "d", "x"]);
+   assert_eq!(circuit.reachable("nope").len(), 0);
+
+   assert_eq!(circuit.prune("a"), 3);
+   assert_eq!(circuit.len(), 3);
+   assert_eq!(circuit.get_value("a").unwrap(), 3);
+}
+
+#[test]
+fn test_verilog() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("f", "x LSHIFT 2").unwrap();
+   circuit.add_element("or", "f NOR u").unwrap();
+   circuit.add_element("a", "NOT or").unwrap();
+
+   assert_eq!(circuit.to_verilog(), "module circuit(
+   input wire [15:0] w_u,
+   output wire [15:0] w_a
+);
+   wire [15:0] w_f;
+   wire [15:0] w_or;
+   wire [15:0] w_x;
+   assign w_a = ~w_or;
+   assign w_f = w_x << 16'd2;
+   assign w_or = ~(w_f | w_u);
+   assign w_x = 16'd123;
+endmodule
+");
+}
+
+#[test]
+fn test_explain() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x AND y").unwrap();
+   circuit.add_element("e", "x OR d").unwrap();
+   circuit.add_element("a", "e LSHIFT q").unwrap();
+   assert_eq!(circuit.explain("a"), Err(CircuitError::Undefined("q".to_string())));
+
+   circuit.add_element("q", "0").unwrap();
+   assert_eq!(circuit.explain("a").unwrap(), "a = e LSHIFT q (123)
+   e = x OR d (123)
+      x = 123 (123)
+      d = x AND y (72)
+         x (123, see above)
+         y = 456 (456)
+   q = 0 (0)
+");
+}
+
+#[test]
+fn test_resolve_all() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("e", "x OR 7").unwrap();
+   circuit.add_element("d", "x AND y").unwrap();
+
+   assert_eq!(circuit.resolve_all().unwrap(),
+              vec![("d".to_string(), 72), ("e".to_string(), 127), ("x".to_string(), 123), ("y".to_string(), 456)]);
+
+   circuit.add_element("e", "x OR q").unwrap();
+   circuit.add_element("q", "NOT e").unwrap();
+   circuit.clear_cache();
+   assert!(circuit.resolve_all().is_err());
+}
+
+#[test]
+fn test_json() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x AND y").unwrap();
+   circuit.add_element("e", "NOT d").unwrap();
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+
+   let mut resumed = Circuit::from_json(&circuit.to_json()).unwrap();
+   assert_eq!(resumed.len(), 4);
+   assert_eq!(resumed.element("d").unwrap().spec, circuit.element("d").unwrap().spec);
+   assert_eq!(resumed.element("d").unwrap().value, Some(72));
+   assert_eq!(resumed.element("e").unwrap().value, None);
+   assert_eq!(resumed.get_value("e").unwrap(), 65463);
+
+   assert!(Circuit::from_json("{\"parts\": 5}").is_err());
+   assert_eq!(resumed.values_json().unwrap(), r#"{"d":72,"e":65463,"x":123,"y":456}"#);
+}
+
+#[test]
+fn test_levels() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x AND y").unwrap();
+   circuit.add_element("e", "x OR d").unwrap();
+   circuit.add_element("f", "NOT 5").unwrap();
+
+   assert_eq!(circuit.levels().unwrap(), vec![vec!["f", "x", "y"], vec!["d"], vec!["e"]]);
+   circuit.force_value("d", 1);
+   assert_eq!(circuit.levels().unwrap(), vec![vec!["f", "x", "y"], vec!["e"]]);
+
+   let mut serial = Circuit::new();
+   let mut parallel = Circuit::new();
+   for &(name, spec) in &[("x", "123"), ("y", "456"), ("d", "x AND y"), ("e", "x OR d"), ("f", "NOT 5")] {
+      serial.add_element(name, spec).unwrap();
+      parallel.add_element(name, spec).unwrap();
+   }
+   assert_eq!(serial.resolve_all().unwrap(), parallel.par_resolve_all().unwrap());
+   assert_eq!(parallel.cache_stats(), CacheStats{hits: 0, misses: 5, recomputed: 0});
+   // Only what forcing d threw away is worked out again, reading the rest
+   // from the cache.
+   parallel.force_value("d", 1);
+   parallel.reset_cache_stats();
+   assert_eq!(parallel.par_resolve_all().unwrap()[1], ("e".to_string(), 123));
+   assert_eq!(parallel.cache_stats(), CacheStats{hits: 6, misses: 1, recomputed: 1});
+
+   parallel.add_element("f", "NOT q").unwrap();
+   parallel.add_element("q", "f").unwrap();
+   parallel.clear_cache();
+   match parallel.par_resolve_all() {
+      Err(CircuitError::Cycle(wires)) => assert_eq!(wires, vec!["f", "q", "f"]),
+      other => panic!("expected a cycle, got {:?}", other),
+   }
+}
+
+#[test]
+fn test_solve() {
+   let mut circuit = "b ADD 100 -> c\nc LSHIFT 1 -> a\n7 -> b\nb XOR 21845 -> x".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.solve("a", 1000, "b").unwrap(), Some(400));
+   assert_eq!(circuit.get_value("a").unwrap(), 1000);
+   assert_eq!(circuit.solve("x", 0, "b").unwrap(), Some(21845));
+   assert_eq!(circuit.solve("a", 1, "b").unwrap(), None);
+   assert_eq!(circuit.solve("a", 1, "q"), Err(CircuitError::Undefined("q".to_string())));
+}
+
+#[test]
+fn test_resolve_by_level() {
+   let mut circuit = "x AND y -> d\nNOT d -> a\n123 -> x\n456 -> y\nx OR 1 -> e".parse::<Circuit>().unwrap();
+   let mut seen = Vec::new();
+   circuit.resolve_by_level(|c, wires| {
+      let values: Vec<String> = wires.iter().map(|w| format!("{}={}", w, c.element(w).unwrap().value.unwrap())).collect();
+      seen.push(values.join(" "));
+   }).unwrap();
+   assert_eq!(seen, vec!["x=123 y=456", "d=72 e=123", "a=65463"]);
+}
+
+#[test]
+fn test_redefine() {
+   let mut circuit = "123 -> x\n456 -> y\nx AND y -> d\nNOT d -> e\nNOT x -> f".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.get_value("e").unwrap(), 65463);
+   assert_eq!(circuit.get_value("f").unwrap(), 65412);
+   circuit.force_value("d", 0);
+
+   circuit.redefine("d", "x OR y").unwrap();
+   assert_eq!(circuit.element("e").unwrap().value, None);
+   assert_eq!(circuit.element("f").unwrap().value, Some(65412));
+   assert_eq!(circuit.get_value("e").unwrap(), !(123 | 456));
+
+   assert!(circuit.redefine("q", "1").is_err());
+   assert!(circuit.redefine("d", "x FOO y").is_err());
+   assert_eq!(circuit.get_value("d").unwrap(), 123 | 456);
+}
+
+#[test]
+fn test_precompute_constants() {
+   let mut circuit: Circuit = "123 -> x\n456 -> y\nx AND y -> d\nd OR u -> e\nx AND x -> f\nNOT f -> g\ng -> g2\nk OR 1 -> k".parse().unwrap();
+   assert_eq!(circuit.precompute_constants().unwrap(), 6);
+   let cached: Vec<&str> = circuit.sorted_names().into_iter().filter(|w| circuit.element(w).unwrap().value.is_some()).collect();
+   assert_eq!(cached, vec!["d", "f", "g", "g2", "x", "y"]);
+   assert_eq!(circuit.element("g2").unwrap().value, Some(65412));
+   assert_eq!(circuit.precompute_constants().unwrap(), 0);
+
+   // Forcing an input only throws away what depends on it.
+   circuit.force_value("y", 1);
+   assert_eq!(circuit.element("g").unwrap().value, Some(65412));
+   assert_eq!(circuit.element("d").unwrap().value, None);
+   assert_eq!(circuit.precompute_constants().unwrap(), 1);
+   assert_eq!(circuit.element("d").unwrap().value, Some(1));
+}
+
+#[test]
+fn test_cache_stats() {
+   let mut circuit: Circuit = "123 -> x\n456 -> y\nx AND y -> d\nd OR y -> e\nNOT x -> f".parse().unwrap();
+   assert_eq!(circuit.get_value("e").unwrap(), 456);
+   assert_eq!(circuit.cache_stats(), CacheStats{hits: 0, misses: 4, recomputed: 0});
+   circuit.get_value("e").unwrap();
+   circuit.get_value("f").unwrap();
+   assert_eq!(circuit.cache_stats(), CacheStats{hits: 2, misses: 5, recomputed: 0});
+
+   // Forcing y only recomputes what y feeds, reading x from the cache.
+   circuit.reset_cache_stats();
+   circuit.force_value("y", 0);
+   assert_eq!(circuit.get_value("e").unwrap(), 0);
+   assert_eq!(circuit.cache_stats(), CacheStats{hits: 3, misses: 2, recomputed: 2});
+   assert_eq!(circuit.cache_stats().to_string(), "3 cache hits, 2 misses (2 recomputed after invalidation)\n");
+}
+
+#[test]
+fn test_incremental_invalidation() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x AND y").unwrap();
+   circuit.add_element("e", "d OR 1").unwrap();
+   circuit.add_element("f", "y RSHIFT 2").unwrap();
+   circuit.add_element("g", "e AND f").unwrap();
+   circuit.add_element("h", "NOT g").unwrap();
+   assert_eq!(circuit.resolve_all().unwrap().len(), 7);
+
+   circuit.force_value("x", 1);
+   let cached: Vec<&str> = ["x", "y", "d", "e", "f", "g", "h"].iter().cloned()
+      .filter(|w| circuit.element(w).unwrap().value.is_some()).collect();
+   assert_eq!(cached, vec!["x", "y", "f"]);
+   assert_eq!(circuit.get_value("h").unwrap(), !(1 & (456 >> 2)));
+
+   // Forced wires stay put when something upstream of them changes.
+   circuit.force_value("e", 3);
+   circuit.force_value("d", 0);
+   assert_eq!(circuit.get_value("e").unwrap(), 3);
+   assert_eq!(circuit.get_value("h").unwrap(), !(3 & (456 >> 2)));
+
+   let mut resumed = Circuit::from_json(&circuit.to_json()).unwrap();
+   resumed.force_value("y", 0);
+   assert_eq!(resumed.get_value("e").unwrap(), 3);
+   assert_eq!(resumed.get_value("h").unwrap(), 65535);
+}
+
+#[test]
+fn test_forward_references() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("d", "x AND y").unwrap();
+   assert_eq!(circuit.len(), 1);
+   assert!(circuit.element("x").is_none());
+
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x OR y").unwrap();
+   assert_eq!(circuit.len(), 3);
+   assert_eq!(circuit.get_value("d").unwrap(), 507);
+   circuit.force_value("y", 0);
+   assert_eq!(circuit.get_value("d").unwrap(), 123);
+}
+
+#[test]
+fn test_from_str() {
+   let mut circuit = "123 -> x\n456 -> y\nx AND y -> d\nNOT x -> h".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.len(), 4);
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+   assert_eq!(circuit.get_value("h").unwrap(), 65412);
+
+   let err = "123 -> x\nx AND -> d".parse::<Circuit>().unwrap_err();
+   assert_eq!((err.line, &err.text[..]), (2, "x AND -> d"));
+
+   let mut circuit = Circuit::from_reader(io::Cursor::new("1 -> x\nx LSHIFT 3 -> a\n")).unwrap();
+   assert_eq!(circuit.get_value("a").unwrap(), 8);
+   match Circuit::from_reader(io::Cursor::new("1 -> x\nbogus\n")) {
+      Err(LoadError::Parse(e)) => assert_eq!(e.line, 2),
+      other => panic!("expected a parse error, got {:?}", other),
+   }
+}
+
+#[test]
+fn test_nested_expressions() {
+   let mut circuit = "123 -> x\n456 -> y\n0 -> z\n(x AND y) OR (NOT z) -> a\nNOT (x LSHIFT (y RSHIFT 7)) -> b".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.get_value("a").unwrap(), 65535);
+   assert_eq!(circuit.get_value("a$1").unwrap(), 72);
+   assert_eq!(circuit.get_value("b").unwrap(), !(123 << 3));
+   assert_eq!(circuit.len(), 9);
+}
+
+#[test]
+fn test_undefined() {
+   let mut circuit = "123 -> x\nx AND y -> d\nd OR z -> e\nNOT x -> f".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.undefined(), vec!["y", "z"]);
+   assert_eq!(circuit.get_value("f").unwrap(), 65412);
+   assert_eq!(circuit.get_value("e"), Err(CircuitError::Undefined("z".to_string())));
+   assert_eq!(circuit.get_value("nope"), Err(CircuitError::Undefined("nope".to_string())));
+   assert_eq!(circuit.par_resolve_all(), Err(CircuitError::Undefined("y".to_string())));
+   assert_eq!(circuit.get_value("f").unwrap(), 65412);
+
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("z", "1").unwrap();
+   assert!(circuit.undefined().is_empty());
+   assert_eq!(circuit.get_value("e").unwrap(), 73);
+}
+
+#[test]
+fn test_buses() {
+   let mut circuit = "{1, 2, 3, 4} -> x[3:0]\nx[3:0] MUL 10 -> t[3:0]\n{x[1:0], t[3:2]} -> s[0:3]\nNOT s[3] -> n".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.get_bus("x[3:0]").unwrap(), vec![1, 2, 3, 4]);
+   assert_eq!(circuit.get_bus("t[0:3]").unwrap(), vec![40, 30, 20, 10]);
+   assert_eq!(circuit.get_bus("s[0:3]").unwrap(), vec![3, 4, 10, 20]);
+   assert_eq!(circuit.get_bus("{n, x[0]}").unwrap(), vec![!20, 4]);
+   assert_eq!(circuit.get_value("t[1]").unwrap(), 30);
+   assert_eq!(circuit.len(), 13);
+
+   assert!(circuit.add_element("y[1:0]", "x[2:0]").is_err());
+   assert!(circuit.get_bus("q[1:0]").is_err());
+}
+
+#[test]
+fn test_modules() {
+   let netlist = "module half_adder(a, b) -> (sum, carry)
+a XOR b -> sum
+a AND b -> carry
+end
+module full_adder(a, b, cin) -> (sum, cout)
+half_adder(a, b) -> (s1, c1)
+half_adder(s1, cin) -> (sum, c2)
+c1 OR c2 -> cout
+end
+65535 -> x
+0 -> y
+1 -> z
+full_adder(x, y, z) -> (s, c)
+half_adder(y, 7) -> t, u";
+   let mut circuit = netlist.parse::<Circuit>().unwrap();
+   assert_eq!(circuit.get_value("s").unwrap(), 65534);
+   assert_eq!(circuit.get_value("c").unwrap(), 1);
+   assert_eq!(circuit.get_value("t").unwrap(), 7);
+   assert_eq!(circuit.get_value("full_adder1.s1").unwrap(), 65535);
+   assert!(circuit.get_value("half_adder3.sum").is_err());
+   assert!(circuit.element("u").is_some());
+
+   assert_eq!("module m(a) -> b\nNOT a -> b".parse::<Circuit>().err().unwrap().line, 1);
+   assert_eq!("module m(a) -> b\nNOT a -> b\nend\nm(x, y) -> z".parse::<Circuit>().err().unwrap().line, 4);
+   assert!("module m(a) -> b\nm(a) -> b\nend\nm(x) -> z".parse::<Circuit>().is_err());
+}
+
+#[test]
+fn test_evaluation_order() {
+   let circuit = "x AND y -> d\nNOT d -> a\n123 -> x\nx OR 1 -> y\n5 -> unused".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.evaluation_order("a").unwrap(), vec!["x", "y", "d", "a"]);
+   assert_eq!(circuit.evaluation_order("x").unwrap(), vec!["x"]);
+   assert!("NOT q -> a".parse::<Circuit>().unwrap().evaluation_order("a").is_err());
+}
+
+#[test]
+fn test_comments_and_spacing() {
+   let netlist = "# The example from the puzzle
+123 -> x   // the first input
+
+456   ->   y
+   x  AND\ty -> d  # tabs too
+NOT   x -> h//no space needed
+module m(a) -> b   # modules can have comments
+   # even inside
+   NOT a -> b
+end
+m(d) -> e";
+   let mut circuit = netlist.parse::<Circuit>().unwrap();
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+   assert_eq!(circuit.get_value("h").unwrap(), 65412);
+   assert_eq!(circuit.get_value("e").unwrap(), 65463);
+   assert_eq!(circuit.len(), 5);
+   assert_eq!("x  OR   y".parse::<ElementSpec>().unwrap(), "x OR y".parse::<ElementSpec>().unwrap());
+   assert_eq!("1 -> x\nx -> # y".parse::<Circuit>().err().unwrap().line, 2);
+}
+
+#[test]
+fn test_from_reader() {
+   let netlist = random_netlist(2000, 40, 3) + "# fancier lines take the slow path\n(a AND 1) OR b -> wide\nNOT -> x\n";
+   let streamed = Circuit::from_reader(netlist.as_bytes());
+   let error = format!("line {}: can't parse \"NOT -> x\"", netlist.lines().count());
+   assert_eq!(streamed.err().map(|e| e.to_string()), Some(error));
+
+   let netlist = &netlist[..netlist.len() - "NOT -> x\n".len()];
+   let mut streamed = Circuit::from_reader(netlist.as_bytes()).unwrap();
+   let mut slow = Circuit::new();
+   for (i, line) in netlist.lines().enumerate() {
+      slow.add_line(i + 1, line).unwrap();
+   }
+   assert_eq!(streamed.diff(&slow).to_string(), "");
+   assert_eq!(streamed.resolve_all().unwrap(), slow.resolve_all().unwrap());
+   assert!(Circuit::from_reader(&b"1 -> x\r\n\xff -> y\n"[..]).is_err());
+}
+
+#[test]
+fn test_merge() {
+   use std::env;
+   use std::fs;
+   use std::io::Write;
+   let mut circuit: Circuit = "123 -> x\n456 -> y\nx AND y -> d".parse().unwrap();
+   let harness: Circuit = "input AND 255 -> low\nNOT low -> out\nout -> d".parse().unwrap();
+   assert_eq!(circuit.merge(&harness, None, &[]).err().map(|e| e.to_string()), Some("wire d is already defined".to_string()));
+   assert_eq!(circuit.len(), 3);
+
+   circuit.merge(&harness, Some("h"), &[("input", "d")]).unwrap();
+   assert_eq!(circuit.get_value("h.out").unwrap(), !72);
+   assert_eq!(circuit.get_value("h.d").unwrap(), !72);
+   circuit.merge(&harness, Some("h2"), &[("input", "h.out"), ("out", "result")]).unwrap();
+   assert_eq!(circuit.get_value("result").unwrap(), !(!72 & 255));
+   assert!(circuit.merge(&harness, Some("h"), &[]).is_err());
+
+   // Named for this run, so that another running the tests at the same
+   // time doesn't share it.
+   let path = env::temp_dir().join(format!("test_merge-{}.txt", std::process::id()));
+   File::create(&path).unwrap().write_all(b"x OR 1 -> x1\n").unwrap();
+   circuit.load(&path, Some("file")).unwrap();
+   assert_eq!(circuit.undefined(), vec!["file.x"]);
+   circuit.load(&path, None).unwrap();
+   assert_eq!(circuit.get_value("x1").unwrap(), 123);
+   fs::remove_file(&path).unwrap();
+   assert!(circuit.load(&path, None).is_err());
+}
+
+#[test]
+fn test_infix_syntax() {
+   let infix: Circuit = "x = 123\ny = 456\nd = x & y\ne = x|y\nf = x << 2\ng = y >> 2\nh = !x\ni = ~y\n\
+                         j = (x ^ 7) + 1 # comments too\nk = j - 1\nl[1:0] = {x, y} * 2".parse().unwrap();
+   let keywords: Circuit = "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\n\
+                            NOT x -> h\nNOT y -> i\n(x XOR 7) ADD 1 -> j\nj SUB 1 -> k\n{x, y} MUL 2 -> l[1:0]".parse().unwrap();
+   assert_eq!(infix.diff(&keywords).to_string(), "");
+   assert_eq!(infix.element("h").unwrap().spec, "NOT x".parse::<ElementSpec>().unwrap());
+   // Either syntax can be used line by line.
+   let mut mixed: Circuit = "x = 3\nx AND 1 -> y\nz = y << 1\nmodule inv(a) -> b\n   b = !a\nend\ninv(z) -> w".parse().unwrap();
+   assert_eq!(mixed.get_value("w").unwrap(), !2);
+   assert!("d = x &".parse::<Circuit>().is_err());
+   assert!(" = 1".parse::<Circuit>().is_err());
+}
+
+#[test]
+fn test_cycle() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("a", "b").unwrap();
+   circuit.add_element("b", "c AND x").unwrap();
+   circuit.add_element("c", "NOT b").unwrap();
+   circuit.add_element("x", "1").unwrap();
+
+   match circuit.get_value("a") {
+      Err(CircuitError::Cycle(wires)) => assert_eq!(wires, vec!["b", "c", "b"]),
+      other => panic!("expected a cycle, got {:?}", other),
+   }
+   // The rest of the circuit is still usable afterwards.
+   assert_eq!(circuit.get_value("x").unwrap(), 1);
+}
+
+#[test]
+fn test_feedback_loops() {
+   let circuit = "b -> a\nc AND x -> b\nNOT a -> c\nq OR 1 -> d\nd -> q\ns XOR 1 -> s\n1 -> x\na -> y".parse::<Circuit>().unwrap();
+   let strings = |wires: &[&str]| wires.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+   assert_eq!(circuit.feedback_loops(), vec![strings(&["a", "b", "c"]), strings(&["d", "q"]), strings(&["s"])]);
+   assert!("1 -> x\nx -> y".parse::<Circuit>().unwrap().feedback_loops().is_empty());
+
+   let mut chain = Circuit::new();
+   chain.add_element("w0", "w49999").unwrap();
+   for i in 1..50000 {
+      chain.add_element(&format!("w{}", i), &format!("w{}", i - 1)).unwrap();
+   }
+   assert_eq!(chain.feedback_loops()[0].len(), 50000);
+}
+
+#[test]
+fn test_deep_chain() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("w0", "1").unwrap();
+   for i in 1..50000 {
+      circuit.add_element(&format!("w{}", i), &format!("w{} LSHIFT 1", i - 1)).unwrap();
+   }
+   assert_eq!(circuit.get_value("w15").unwrap(), 1 << 15);
+   assert_eq!(circuit.get_value("w49999").unwrap(), 0);
+}
+
+#[test]
+fn test_parse_errors() {
+   let mut circuit = Circuit::new();
+   assert!(circuit.add_line(1, "123 -> x").is_ok());
+   assert!(circuit.add_line(2, "x AND y -> d").is_ok());
+
+   for &bad in &["x FOO y -> e", "x AND -> e", "NOPE x -> e", "123", "1 2 3 4 -> e", "x -> "] {
+      let err = circuit.add_line(7, bad).unwrap_err();
+      assert_eq!(err.line, 7);
+      assert_eq!(err.text, bad);
+   }
+   assert_eq!(format!("{}", circuit.add_line(3, "x ->").unwrap_err()), r#"line 3: can't parse "x ->""#);
+}
+
+#[test]
+fn test_feedback() {
+   let mut circuit: Circuit = "b -> a\nx OR 1 -> b\n6 -> x\nb AND 3 -> c".parse().unwrap();
+   assert_eq!(circuit.feedback("a", "b"), Ok(7));
+   // b is still forced to 7, so forcing x doesn't get past it.
+   assert_eq!(circuit.feedback("c", "x"), Ok(3));
+   circuit.clear_cache();
+   assert_eq!(circuit.feedback("c", "x"), Ok(3));
+   assert_eq!(circuit.get_value("b"), Ok(3));
+   assert_eq!(circuit.feedback("a", "q"), Err(CircuitError::Undefined("q".to_string())));
+}
+
+#[test]
+fn test_literals() {
+   assert_eq!("0xFF".parse::<Input>(), Ok(Input::Value(255)));
+   assert_eq!("0b1010".parse::<Input>(), Ok(Input::Value(10)));
+   assert_eq!("0xffff".parse::<Input>(), Ok(Input::Value(0xffff)));
+   for &bad in &["0x", "0x1g", "0b102", "0x10000"] {
+      assert_eq!(bad.parse::<Input>(), Err(InvalidInput));
+   }
+
+   // They print back as decimal, which parses to the same spec.
+   for &(text, decimal) in &[("0xFF AND x", "255 AND x"), ("NOT 0b1", "NOT 1"), ("x LSHIFT 0x4", "x LSHIFT 4")] {
+      let spec = text.parse::<ElementSpec>().unwrap();
+      assert_eq!(spec.to_string(), decimal);
+      assert_eq!(decimal.parse::<ElementSpec>(), Ok(spec));
+   }
+
+   assert!("0xF0F0 -> x\nx OR 0x1g -> z".parse::<Circuit>().is_err());
+   let mut circuit: Circuit = "0xF0F0 -> x\nx AND 0b11110000 -> y".parse().unwrap();
+   assert_eq!(circuit.get_value("y"), Ok(0xf0));
+}
+
+#[test]
+fn test_dot() {
+   let mut circuit = Circuit::new();
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("f", "x LSHIFT 2").unwrap();
+   circuit.add_element("d", "x AND f").unwrap();
+
+   assert_eq!(circuit.to_dot(), r#"digraph circuit {
+   "d" [label="d\nAND"];
+   "f" [label="f\nLSHIFT 2"];
+   "x" [label="x\n123"];
+   "x" -> "d";
+   "f" -> "d";
+   "x" -> "f";
+}
+"#);
+}
+
+#[test]
+fn test_fan_out() {
+   let circuit: Circuit = "123 -> x\nx AND x -> d\nx OR u -> e\nd XOR e -> f\nNOT u -> g".parse().unwrap();
+   let fan_out = circuit.fan_out();
+   let fan_out: Vec<(&str, usize)> = fan_out.iter().map(|&(ref w, n)| (&w[..], n)).collect();
+   assert_eq!(fan_out, vec![("u", 2), ("x", 2), ("d", 1), ("e", 1), ("f", 0), ("g", 0)]);
+}
+
+#[test]
+fn test_aliases() {
+   let mut circuit: Circuit = "123 -> x\nx -> y\ny -> z\nz AND 1 -> d\nu -> v\nv OR z -> e\nl -> m\nm -> l".parse().unwrap();
+   assert_eq!(circuit.alias_of("z"), Some("x"));
+   assert_eq!(circuit.alias_of("x"), None);
+   assert_eq!(circuit.alias_of("l"), None);
+   assert_eq!(circuit.aliases(), vec![("v", "u"), ("y", "x"), ("z", "x")]);
+   assert_eq!(circuit.to_dot(), r#"digraph circuit {
+   "d" [label="d\nAND 1"];
+   "e" [label="e\nOR"];
+   "l" [label="l"];
+   "m" [label="m"];
+   "u" [label="u\n= v"];
+   "x" [label="x\n123\n= y = z"];
+   "x" -> "d";
+   "u" -> "e";
+   "x" -> "e";
+   "m" -> "l";
+   "l" -> "m";
+}
+"#);
+
+   // Constants fold through alias chains too.
+   circuit.optimize();
+   assert_eq!(circuit.element("e").unwrap().spec, "u OR 123".parse::<ElementSpec>().unwrap());
+   assert_eq!(circuit.element("d").unwrap().spec, ElementSpec::value(Input::Value(1)));
+}
+
+#[cfg(test)]
+fn arbitrary_input() -> proptest::strategy::BoxedStrategy<Input> {
+   use proptest::prelude::*;
+   prop_oneof![any::<u16>().prop_map(Input::Value), "[a-z]{1,3}".prop_map(Input::Element)].boxed()
+}
+
+#[cfg(test)]
+fn arbitrary_spec() -> proptest::strategy::BoxedStrategy<ElementSpec> {
+   use proptest::prelude::*;
+   let ops = vec![Operation::Value, Operation::Not, Operation::And, Operation::Or, Operation::LShift,
+                  Operation::RShift, Operation::Xor, Operation::Nand, Operation::Nor,
+                  Operation::Add, Operation::Sub, Operation::Mul];
+   (proptest::sample::select(ops), arbitrary_input(), arbitrary_input()).prop_map(|(op, left, right)| {
+      let right = match op {
+         Operation::Value | Operation::Not => Input::None,
+         _ => right,
+      };
+      ElementSpec{left: left, right: right, op: op}
+   }).boxed()
+}
+
+#[cfg(test)]
+proptest! {
+   #[test]
+   fn prop_spec_round_trips(spec in arbitrary_spec()) {
+      prop_assert_eq!(spec.to_string().parse::<ElementSpec>().unwrap(), spec.clone());
+      prop_assert_eq!(spec.to_string().parse::<Expr>().unwrap().lower("w"), vec![("w".to_string(), spec)]);
+   }
+
+   #[test]
+   fn prop_identities(x in proptest::num::u16::ANY, y in proptest::num::u16::ANY) {
+      let netlist = format!("{} -> x\n{} -> y\nNOT (NOT x) -> nn\nx AND x -> aa\nx OR x -> oo\nx XOR x -> xx\n\
+                             (x AND y) OR (x AND (NOT y)) -> split\nx NAND y -> nand\nx ADD y -> sum\nsum SUB y -> diff",
+                            x, y);
+      let mut circuit = netlist.parse::<Circuit>().unwrap();
+      prop_assert_eq!(circuit.get_value("nn").unwrap(), x);
+      prop_assert_eq!(circuit.get_value("aa").unwrap(), x);
+      prop_assert_eq!(circuit.get_value("oo").unwrap(), x);
+      prop_assert_eq!(circuit.get_value("xx").unwrap(), 0);
+      prop_assert_eq!(circuit.get_value("split").unwrap(), x);
+      prop_assert_eq!(circuit.get_value("nand").unwrap(), !(x & y));
+      prop_assert_eq!(circuit.get_value("diff").unwrap(), x);
+   }
+
+   #[test]
+   fn prop_parser_never_panics(text in "[ -~\n]{0,200}") {
+      let _ = text.parse::<Circuit>();
+      let _ = text.parse::<ElementSpec>();
+      let _ = text.parse::<Expr>();
+   }
+}
+
+#[test]
+fn test_debug_order() {
+   let mut forwards: Circuit = "123 -> x\n456 -> y\nx AND y -> d\nNOT d -> h\nh OR z -> q".parse().unwrap();
+   let mut backwards: Circuit = "h OR z -> q\nNOT d -> h\nx AND y -> d\n456 -> y\n123 -> x".parse().unwrap();
+   assert_eq!(format!("{:?}", forwards), format!("{:?}", backwards));
+   let names: Vec<&str> = backwards.sorted_elements().iter().map(|e| &e.name[..]).collect();
+   assert_eq!(names, ["d", "h", "q", "x", "y"]);
+
+   for circuit in [&mut forwards, &mut backwards].iter_mut() {
+      circuit.get_value("h").unwrap();
+      circuit.force_value("y", 1);
+   }
+   assert_eq!(format!("{:?}", forwards), format!("{:?}", backwards));
+   assert!(format!("{:?}", forwards).contains("overrides: {\"y\": 1}"));
+}
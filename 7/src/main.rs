@@ -1,215 +1,613 @@
-use std::collections::HashMap;
-use std::fs::File;
+extern crate aoc2015_lib;
+extern crate circuit;
+extern crate getopts;
+#[macro_use]
+extern crate tracing;
+
+use aoc2015_lib::AocError;
+use circuit::{random_netlist, Circuit, CircuitError, ElementSpec, Limits, LoadError};
+use getopts::Options;
+use std::env;
+use std::fs;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+// How many rounds of events --settle waits for the circuit to settle.
+const MAX_SETTLE_ROUNDS: usize = 10000;
 
-#[derive(Debug)]
-enum Input {
-   Value(u16),
-   Element(String),
-   None,
+const REPL_HELP: &str = "Commands:
+   get WIRE         resolve a wire
+   set WIRE VALUE   force a wire to a value
+   undo WIRE        put a wire back as it was before it was set
+   clear            forget every resolved value
+   show SPEC        evaluate a spec like `x AND y` without adding it
+   define WIRE SPEC redefine a wire, like `define d x OR y`
+   deps WIRE        show what a wire is built from
+   quit";
+
+// Evaluates a spec like `lx AND 255` against circuit without adding a wire.
+fn show(circuit: &mut Circuit, text: &str) -> Result<String, CircuitError> {
+   return match text.parse::<ElementSpec>() {
+      Ok(spec) => circuit.evaluate_spec(&spec).map(|v| format!("{} = {}", text, v)),
+      Err(_) => Ok(format!("Can't parse {}", text)),
+   };
 }
 
-#[derive(Debug)]
-struct InvalidInput;
+// Warnings by default, each -v shows a level more (up to every wire being
+// set with -vv), and -q hides everything.  $RUST_LOG overrides it.
+fn log_filter(verbose: usize, quiet: bool) -> &'static str {
+   if quiet {
+      return "off";
+   }
+   return match verbose {
+      0 => "warn",
+      1 => "info",
+      2 => "debug",
+      _ => "trace",
+   };
+}
 
-impl FromStr for Input {
-   type Err = InvalidInput;
-   fn from_str(s: &str) -> Result<Input, InvalidInput> {
-      return match s.parse::<u16>() {
-         Ok(v) => Ok(Input::Value(v)),
-         Err(_) => Ok(Input::Element(s.to_string())),
-      };
+// Runs one REPL command line against circuit, returning what to print.
+fn repl_command(circuit: &mut Circuit, line: &str) -> String {
+   let words: Vec<&str> = line.split_whitespace().collect();
+   let result = match (words.first().map(|w| &w[..]), words.len()) {
+      (Some("get"), 2) => circuit.get_value(words[1]).map(|v| format!("{} = {}", words[1], v)),
+      (Some("set"), 3) => match words[2].parse::<u16>() {
+         Ok(v) if circuit.element(words[1]).is_some() => {
+            circuit.force_value(words[1], v);
+            Ok(format!("{} = {}", words[1], v))
+         }
+         Ok(_) => Ok(format!("No wire named {}", words[1])),
+         Err(_) => Ok(format!("Invalid value {}", words[2])),
+      },
+      (Some("undo"), 2) if circuit.undo_force(words[1]) => Ok(format!("Unset {}", words[1])),
+      (Some("undo"), 2) => Ok(format!("{} isn't set", words[1])),
+      (Some("clear"), 1) => {
+         circuit.clear_cache();
+         Ok("Cleared".to_string())
+      }
+      (Some("show"), n) if n > 1 => show(circuit, &words[1..].join(" ")),
+      (Some("define"), n) if n > 2 => {
+         let spec = words[2..].join(" ");
+         match circuit.redefine(words[1], &spec) {
+            Ok(()) => Ok(format!("{} = {}", words[1], spec)),
+            Err(_) if circuit.element(words[1]).is_none() => Ok(format!("No wire named {}", words[1])),
+            Err(_) => Ok(format!("Can't parse {}", spec)),
+         }
+      }
+      (Some("deps"), 2) => match circuit.element(words[1]) {
+         Some(e) => {
+            let mut deps: Vec<String> = circuit.reachable(words[1]).into_iter()
+               .filter(|w| w != words[1]).collect();
+            deps.sort();
+            Ok(format!("{} = {}\ndepends on {} wires: {}", words[1], e.spec, deps.len(), deps.join(" ")))
+         }
+         None => Ok(format!("No wire named {}", words[1])),
+      },
+      (None, _) => Ok(String::new()),
+      _ => Ok(REPL_HELP.to_string()),
+   };
+   return match result {
+      Ok(out) => out,
+      Err(e) => format!("Error: {}", e),
+   };
+}
+
+fn repl(circuit: &mut Circuit) {
+   let stdin = std::io::stdin();
+   loop {
+      print!("> ");
+      let _ = std::io::stdout().flush();
+      let mut line = String::new();
+      match stdin.lock().read_line(&mut line) {
+         Ok(0) | Err(_) => break,
+         Ok(_) => {}
+      }
+      match line.trim() {
+         "quit" | "exit" => break,
+         cmd => println!("{}", repl_command(circuit, cmd)),
+      }
    }
 }
 
-#[derive(Debug)]
-enum Operation {
-   Value,
-   Not,
-   And,
-   Or,
-   LShift,
-   RShift,
-}
-
-impl FromStr for Operation {
-   type Err = InvalidInput;
-   fn from_str(s: &str) -> Result<Operation, InvalidInput> {
-      return match s {
-         "OR" => Ok(Operation::Or),
-         "AND" => Ok(Operation::And),
-         "LSHIFT" => Ok(Operation::LShift),
-         "RSHIFT" => Ok(Operation::RShift),
-         _ => Err(InvalidInput)
-      }
-   }
-}
-
-
-#[derive(Debug)]
-struct ElementSpec {
-   left: Input,
-   right: Input,
-   op: Operation,
-}
-
-impl FromStr for ElementSpec {
-   type Err = InvalidInput;
-   fn from_str(s: &str) -> Result<ElementSpec, InvalidInput> {
-      let parts = s.split(" ").collect::<Vec<_>>();
-
-      // Either passthru or Value
-      if parts.len() == 1 {
-         return Ok(ElementSpec{
-            left: parts[0].parse::<Input>().unwrap(),
-            right: Input::None,
-            op: Operation::Value,
-         });
-      } else if parts.len() == 2 {
-         return Ok(ElementSpec {
-            left: parts[1].parse::<Input>().unwrap(),
-            right: Input::None,
-            op: Operation::Not,
-         });
-      } else if parts.len() == 3 {
-         return Ok(ElementSpec {
-            left:  parts[0].parse::<Input>().unwrap(),
-            right:  parts[2].parse::<Input>().unwrap(),
-            op: parts[1].parse::<Operation>().unwrap(),
-         });
+// One frame of --animate: every wire, with the ones that just resolved
+// highlighted and the ones still to go shown as `?`.
+fn animation_frame(circuit: &Circuit, level: usize, fresh: &[String]) -> String {
+   let mut cells = Vec::new();
+   for name in circuit.sorted_names() {
+      let cell = match circuit.element(name).unwrap().value {
+         Some(v) => format!("{}={}", name, v),
+         None => format!("{}=?", name),
+      };
+      let padded = format!("{:<12}", cell);
+      if fresh.iter().any(|f| f == name) {
+         cells.push(format!("\x1b[1;32m{}\x1b[0m", padded));
       } else {
-         return Err(InvalidInput)
+         cells.push(padded);
       }
    }
+   let mut out = format!("\x1b[2J\x1b[HLevel {}\n", level);
+   for row in cells.chunks(6) {
+      out.push_str(&row.join(" "));
+      out.push('\n');
+   }
+   return out;
 }
 
-impl ElementSpec {
-   fn evaluate(&self, left: u16, right: u16) -> u16 {
-      match self.op {
-         Operation::Value => left,
-         Operation::Not => !left,
-         Operation::And => left & right,
-         Operation::Or => left | right,
-         Operation::LShift => left << right,
-         Operation::RShift => left >> right,
+// A --set argument, like `b=1234`.
+fn parse_override(s: &str) -> Result<(String, u16), AocError> {
+   let parts: Vec<&str> = s.splitn(2, '=').collect();
+   return match (parts.first(), parts.get(1).map(|v| v.parse::<u16>())) {
+      (Some(name), Some(Ok(v))) if !name.is_empty() => Ok((name.to_string(), v)),
+      _ => Err(AocError::Invalid(format!("Invalid override {:?}, expected WIRE=VALUE", s))),
+   };
+}
+
+// A --feedback argument, like `a=b`.
+fn parse_feedback(s: &str) -> Result<(String, String), AocError> {
+   let parts: Vec<&str> = s.splitn(2, '=').collect();
+   return match (parts.first(), parts.get(1)) {
+      (Some(from), Some(to)) if !from.is_empty() && !to.is_empty() => Ok((from.to_string(), to.to_string())),
+      _ => Err(AocError::Invalid(format!("Invalid feedback {:?}, expected FROM=TO", s))),
+   };
+}
+
+// The number given for an option like --timeout, if it was given.
+fn parse_count(matches: &getopts::Matches, name: &str) -> Result<Option<u64>, AocError> {
+   return match matches.opt_str(name) {
+      Some(n) => n.parse::<u64>().map(Some).map_err(|_| AocError::Invalid(format!("Invalid --{} {:?}, expected a number", name, n))),
+      None => Ok(None),
+   };
+}
+
+// The netlist at path, or on standard input for -.
+fn load_netlist(path: &str) -> Result<Circuit, AocError> {
+   let f = aoc2015_lib::open(path).map_err(|e| AocError::io(path, e))?;
+   return match Circuit::from_reader(f) {
+      Ok(circuit) => Ok(circuit),
+      Err(LoadError::Io(e)) => Err(AocError::io(path, e)),
+      Err(LoadError::Parse(e)) => Err(e.into()),
+      Err(e) => Err(AocError::Invalid(format!("Invalid {}: {}", path, e))),
+   };
+}
+
+// Writes contents to path and says it did, with verb.
+fn write_file(path: &str, contents: &str, verb: &str) -> Result<(), AocError> {
+   fs::write(path, contents).map_err(|e| AocError::io(path, e))?;
+   println!("{} {}", verb, path);
+   return Ok(());
+}
+
+// Polls path for changes, reloading it and printing wire each time, for
+// as long as it's left running.
+fn watch(path: &str, wire: &str) -> ! {
+   let mut last_modified = None;
+   loop {
+      let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+      if modified != last_modified {
+         last_modified = modified;
+         match load_netlist(path) {
+            Ok(mut circuit) => match circuit.get_value(wire) {
+               Ok(v) => println!("{} is {}", wire, v),
+               Err(e) => eprintln!("Can't resolve {}: {}", wire, e),
+            },
+            Err(e) => eprintln!("{}", e),
+         }
       }
+      thread::sleep(Duration::from_millis(500));
    }
 }
 
-#[derive(Debug)]
-struct Element {
-   spec: ElementSpec,
-   name: String,
-   value: Option<u16>,
-}
+fn main() -> Result<(), AocError> {
+   let args: Vec<String> = env::args().collect();
+   let mut opts = Options::new();
+   opts.optopt("", "input", "the netlist to load, or - for standard input; input.txt by default", "PATH");
+   opts.optopt("", "dot", "write the circuit as a Graphviz graph to FILE", "FILE");
+   opts.optmulti("", "set", "force WIRE to VALUE before anything else is done with the circuit; may be repeated", "WIRE=VALUE");
+   opts.optopt("", "query", "the wire or wires to print, instead of running the puzzle for a", "WIRE[,WIRE...]");
+   opts.optopt("", "part", "print a as is for part 1, or after feeding it back into b for part 2; both by default", "1|2");
+   opts.optopt("", "feedback", "for part 2, resolve FROM, force it onto TO and print FROM again; a=b by default", "FROM=TO");
+   opts.optflag("", "dump", "resolve and print every wire");
+   opts.optopt("", "output", "resolve every wire and print them all in FORMAT, which can only be json", "FORMAT");
+   opts.optopt("", "output-file", "write --output to FILE instead of stdout", "FILE");
+   opts.optflag("", "parallel", "resolve independent wires in parallel for --dump");
+   opts.optopt("", "explain", "print the dependency tree of WIRE with its values", "WIRE");
+   opts.optopt("", "watch", "reload the netlist whenever it changes, printing WIRE", "WIRE");
+   opts.optflag("", "animate", "show the circuit resolving one level at a time");
+   opts.optopt("", "delay", "milliseconds between --animate frames, 300 by default", "MS");
+   opts.optflag("", "repl", "interactively query and poke at the circuit");
+   opts.optopt("", "svg", "draw the query wire and everything feeding it as an SVG schematic in FILE", "FILE");
+   opts.optopt("", "verilog", "write the circuit as a Verilog module to FILE", "FILE");
+   opts.optmulti("", "merge", "also load FILE, with its wires renamed PREFIX.WIRE if given; may be repeated", "[PREFIX=]FILE");
+   opts.optmulti("", "port", "connect --merge files' wire FROM to WIRE instead of prefixing it; may be repeated", "FROM=WIRE");
+   opts.optopt("", "resume", "load a circuit saved with --save instead of INPUT", "FILE");
+   opts.optopt("", "save", "save the circuit and its resolved values to FILE when done", "FILE");
+   opts.optopt("", "prune", "drop wires that don't feed WIRE", "WIRE");
+   opts.optopt("", "critical-path", "print the longest chain of gates feeding WIRE", "WIRE");
+   opts.optopt("", "order", "print the order WIRE's dependencies get evaluated in", "WIRE");
+   opts.optflag("", "loops", "list every feedback loop in the circuit");
+   opts.optflag("", "fan-out", "list how many gates read each wire, most first");
+   opts.optflag("", "aliases", "list the wires that just pass another wire through");
+   opts.optflag("", "stats", "print a summary of the circuit's gates, and how the cache does on the query");
+   opts.optopt("", "truth-table", "print WIRE's value for every combination of --inputs", "WIRE");
+   opts.optopt("", "minimize", "print the simplest sum of products for WIRE's low bit over --inputs", "WIRE");
+   opts.optopt("", "inputs", "the wires to vary for --truth-table or --minimize, taking 0 or 1 each", "WIRE,WIRE,...");
+   opts.optopt("", "equivalent", "check the query wire computes the same thing in the netlist FILE", "FILE");
+   opts.optopt("", "solve", "find a value for the --vary wire that makes WIRE come out as VALUE", "WIRE=VALUE");
+   opts.optopt("", "vary", "the wire to search over for --solve", "WIRE");
+   opts.optflag("", "settle", "simulate event by event so feedback loops like latches can settle");
+   opts.optopt("", "max-steps", "give up on any one evaluation after N steps", "N");
+   opts.optopt("", "timeout", "give up on any one evaluation after MS milliseconds", "MS");
+   opts.optflag("", "optimize", "fold constants and simplify gates before evaluating");
+   opts.optflag("", "four-state", "simulate the query wire with 0/1/X/Z bits, leaving undefined wires floating");
+   opts.optflag("", "values", "with diff, also list the wires whose values change");
+   opts.optflagmulti("v", "verbose", "log more about what's going on; repeat for more detail");
+   opts.optflag("q", "quiet", "don't log anything, not even warnings");
+   opts.optflag("h", "help", "print this help");
+   let matches = opts.parse(&args[1..]).map_err(|e| AocError::Invalid(e.to_string()))?;
+   aoc2015_lib::init_tracing(log_filter(matches.opt_count("v"), matches.opt_present("q")));
+   if matches.opt_present("h") {
+      print!("{}", opts.usage(&format!("Usage: {0} [options] [--input PATH | INPUT]\n       {0} gen WIRES DEPTH [SEED]\n       \
+                                        {0} diff [--values] OLD NEW\n       {0} eval INPUT SPEC", args[0])));
+      return Ok(());
+   }
 
-impl Element {
-   fn set_value(&mut self, val: u16) {
-      self.value = Some(val);
-      println!("setting {} as {}", self.name, val);
+   if matches.free.first().map(|s| &s[..]) == Some("diff") {
+      if matches.free.len() != 3 {
+         return Err(AocError::Invalid(format!("Usage: {} diff [--values] OLD NEW", args[0])));
+      }
+      let (mut old, mut new) = (load_netlist(&matches.free[1])?, load_netlist(&matches.free[2])?);
+      print!("{}", old.diff(&new));
+      if matches.opt_present("values") {
+         let show = |v: Option<u16>| v.map_or("unresolved".to_string(), |v| v.to_string());
+         for (name, before, after) in old.value_changes(&mut new) {
+            println!("{}: {} => {}", name, show(before), show(after));
+         }
+      }
+      return Ok(());
    }
 
-   fn clear_value(&mut self) {
-      self.value = None;
-      println!("Clearing {}", self.name);
+   if matches.free.first().map(|s| &s[..]) == Some("gen") {
+      let numbers: Result<Vec<usize>, _> = matches.free[1..].iter().map(|n| n.parse::<usize>()).collect();
+      match numbers.as_ref().map(|n| &n[..]) {
+         Ok(&[wires, depth]) if wires >= depth && depth > 0 => print!("{}", random_netlist(wires, depth, 0)),
+         Ok(&[wires, depth, seed]) if wires >= depth && depth > 0 => print!("{}", random_netlist(wires, depth, seed as u64)),
+         _ => return Err(AocError::Invalid(format!("Usage: {} gen WIRES DEPTH [SEED], with at least one wire per level", args[0]))),
+      }
+      return Ok(());
    }
-}
 
-#[derive(Debug)]
-struct Circuit {
-   parts: HashMap<String, Element>,
-}
+   if matches.free.first().map(|s| &s[..]) == Some("eval") {
+      if matches.free.len() < 3 {
+         return Err(AocError::Invalid(format!("Usage: {} eval INPUT SPEC, like `eval input.txt lx AND 255`", args[0])));
+      }
+      let mut circuit = load_netlist(&matches.free[1])?;
+      println!("{}", show(&mut circuit, &matches.free[2..].join(" "))?);
+      return Ok(());
+   }
+   let input = matches.opt_str("input").or(matches.free.first().cloned())
+      .unwrap_or(aoc2015_lib::DEFAULT_INPUT.to_string());
 
-impl Circuit {
-   fn new() -> Circuit {
-      Circuit{parts: HashMap::new()}
+   if let Some(wire) = matches.opt_str("watch") {
+      if input == aoc2015_lib::STDIN {
+         return Err(AocError::Invalid("Can't watch standard input for changes".to_string()));
+      }
+      watch(&input, &wire);
    }
 
-   fn add_element(&mut self, name: &str, spec: &str) {
-      self.parts.insert(name.to_string(),
-                   Element {
-                     spec: spec.parse::<ElementSpec>().unwrap(),
-                     name: name.to_string(),
-                     value: None,
-                   });
+   let mut circuit = match matches.opt_str("resume") {
+      Some(path) => {
+         let saved = fs::read_to_string(&path).map_err(|e| AocError::io(&path, e))?;
+         Circuit::from_json(&saved).map_err(|e| AocError::Invalid(format!("Invalid {}: {}", path, e)))?
+      }
+      None => load_netlist(&input)?,
+   };
+
+   let ports = matches.opt_strs("port");
+   let ports: Vec<(&str, &str)> = ports.iter().filter_map(|p| p.find('=').map(|i| (&p[..i], &p[i + 1..]))).collect();
+   for merge in matches.opt_strs("merge") {
+      let (prefix, path) = match merge.find('=') {
+         Some(i) => (Some(&merge[..i]), &merge[i + 1..]),
+         None => (None, &merge[..]),
+      };
+      let other = load_netlist(path)?;
+      circuit.merge(&other, prefix, &ports).map_err(|e| AocError::Invalid(format!("Can't merge {}: {}", path, e)))?;
    }
 
-   fn resolve_input(&mut self, input: &Input) -> u16 {
-      match input {
-         &Input::None => 0,
-         &Input::Value(ref v) => *v,
-         &Input::Element(ref e) => self.get_value(&e),
+   circuit.set_limits(Limits{
+      max_steps: parse_count(&matches, "max-steps")?.map(|n| n as usize),
+      timeout: parse_count(&matches, "timeout")?.map(Duration::from_millis),
+   });
+
+   // --set applies to whatever's done with the circuit from here on, not
+   // just to --query.
+   let overrides = matches.opt_strs("set");
+   let mut forced = Vec::new();
+   for o in &overrides {
+      let (name, v) = parse_override(o)?;
+      if circuit.element(&name).is_none() {
+         return Err(AocError::Invalid(format!("No wire named {}", name)));
+      }
+      forced.push((name, v));
+   }
+   if matches.opt_present("settle") {
+      let inputs: Vec<(&str, u16)> = forced.iter().map(|&(ref w, v)| (&w[..], v)).collect();
+      let rounds = circuit.settle(&inputs, MAX_SETTLE_ROUNDS)?;
+      info!("settled after {} rounds", rounds);
+   } else {
+      for (name, v) in forced {
+         circuit.force_value(&name, v);
       }
    }
 
-   fn get_value(&mut self, name: &str) -> u16 {
-       let do_update = match self.parts.get(name) {
-           Some(&Element { value: Some(v), .. }) => return v,
-           Some(_) => true,
-           None => false,
-       };
-       if !do_update { return 0; }
-       let mut to_update = self.parts.remove(name).expect("where'd it go");
-       let ret = to_update.spec.evaluate(
-           self.resolve_input(&to_update.spec.left),
-           self.resolve_input(&to_update.spec.right));
-       to_update.set_value(ret);
-       if let Some(prev) = self.parts.insert(name.to_string(), to_update) {
-           panic!("circular reference? something re-inserted {:?} under us: {:?}", name, prev);
-       }
-       return ret;
+   let undefined = circuit.undefined();
+   if !undefined.is_empty() {
+      warn!("never defined: {}", undefined.join(" "));
+   }
+
+   if let Some(target) = matches.opt_str("prune") {
+      let pruned = circuit.prune(&target);
+      println!("Pruned {} wires, {} feed {}", pruned, circuit.len(), target);
+   }
+
+   if matches.opt_present("optimize") {
+      println!("Optimized away {} gates", circuit.optimize());
+   }
+   // Not inside info!, which leaves its arguments alone when it's off.
+   let precomputed = circuit.precompute_constants()?;
+   info!("precomputed {} wires that only depend on constants", precomputed);
+
+   if let Some(path) = matches.opt_str("dot") {
+      return write_file(&path, &circuit.to_dot(), "Wrote");
    }
-   fn clear_cache(&mut self) {
-      for (_, v) in &mut self.parts {
-         v.clear_value();
+
+   if matches.opt_present("dump") {
+      let values = if matches.opt_present("parallel") {
+         circuit.par_resolve_all()
+      } else {
+         circuit.resolve_all()
+      };
+      for (name, v) in values? {
+         println!("{} = {}", name, v);
+      }
+      if matches.opt_present("stats") {
+         print!("{}", circuit.cache_stats());
       }
+      return Ok(());
    }
-   fn force_value(&mut self, name: &str, val: u16) {
-      if let Some(e) = self.parts.get_mut(&name.to_string()) {
-         e.set_value(val);
+
+   if let Some(format) = matches.opt_str("output") {
+      if format != "json" {
+         return Err(AocError::Invalid(format!("Unknown output format {}, expected json", format)));
+      }
+      let json = circuit.values_json()?;
+      match matches.opt_str("output-file") {
+         Some(path) => write_file(&path, &format!("{}\n", json), "Wrote")?,
+         None => println!("{}", json),
       }
+      return Ok(());
    }
 
-}
+   if let Some(wire) = matches.opt_str("explain") {
+      print!("{}", circuit.explain(&wire)?);
+      return Ok(());
+   }
 
-fn main() {
-   let f = File::open("input.txt").unwrap();
-   let line_buffer = BufReader::new(&f);
+   if let Some(wire) = matches.opt_str("order") {
+      for w in circuit.evaluation_order(&wire)? {
+         println!("{}", w);
+      }
+      return Ok(());
+   }
 
-   let mut circuit = Circuit::new();
-   for line in line_buffer.lines() {
-      let curr = line.unwrap();
-      let parts = curr.split(" -> ").collect::<Vec<_>>();
-      circuit.add_element(parts[1], parts[0]);
+   if matches.opt_present("fan-out") {
+      for (wire, readers) in circuit.fan_out() {
+         println!("{:>6} {}", readers, wire);
+      }
+      return Ok(());
+   }
+
+   if matches.opt_present("aliases") {
+      for (alias, wire) in circuit.aliases() {
+         println!("{} is an alias of {}", alias, wire);
+      }
+      return Ok(());
    }
 
-   let a = circuit.get_value("a");
-   circuit.clear_cache();
-   circuit.force_value("b", a);
-   println!("a is {}", circuit.get_value("a"));
+   if matches.opt_present("loops") {
+      for wires in circuit.feedback_loops() {
+         println!("{} wires: {}", wires.len(), wires.join(" "));
+      }
+      return Ok(());
+   }
+
+   if matches.opt_present("stats") {
+      print!("{}", circuit.stats()?);
+      // Carry on with the usual queries, to see how the cache does on them.
+      circuit.reset_cache_stats();
+   }
+
+   if let Some(wire) = matches.opt_str("truth-table") {
+      let inputs = matches.opt_str("inputs").unwrap_or(String::new());
+      let inputs: Vec<&str> = inputs.split(',').filter(|w| !w.is_empty()).collect();
+      let rows = circuit.truth_table(&wire, &inputs)?;
+      println!("{} | {}", inputs.join(" "), wire);
+      for (values, out) in rows {
+         let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+         println!("{} | {}", values.join(" "), out);
+      }
+      return Ok(());
+   }
+
+   if let Some(wire) = matches.opt_str("minimize") {
+      let inputs = matches.opt_str("inputs").unwrap_or(String::new());
+      let inputs: Vec<&str> = inputs.split(',').filter(|w| !w.is_empty()).collect();
+      println!("{} = {}", wire, circuit.minimize(&wire, &inputs)?);
+      return Ok(());
+   }
+
+   if let Some(wire) = matches.opt_str("critical-path") {
+      let (delay, path) = circuit.critical_path(&wire)?;
+      println!("{} gates: {}", delay, path.join(" -> "));
+      return Ok(());
+   }
 
+   if matches.opt_present("animate") {
+      let delay = matches.opt_str("delay").and_then(|d| d.parse::<u64>().ok()).unwrap_or(300);
+      let mut level = 0;
+      circuit.resolve_by_level(|circuit, fresh| {
+         level += 1;
+         print!("{}", animation_frame(circuit, level, fresh));
+         let _ = std::io::stdout().flush();
+         thread::sleep(Duration::from_millis(delay));
+      })?;
+      return Ok(());
+   }
+
+   if matches.opt_present("repl") {
+      repl(&mut circuit);
+      return Ok(());
+   }
+
+   if let Some(path) = matches.opt_str("verilog") {
+      return write_file(&path, &circuit.to_verilog(), "Wrote");
+   }
+
+   let query = matches.opt_str("query").unwrap_or("a".to_string());
+   if let Some(path) = matches.opt_str("svg") {
+      return write_file(&path, &circuit.to_svg(&query)?, "Wrote");
+   }
+
+   if matches.opt_present("four-state") {
+      println!("{} is {}", query, circuit.get_logic(&query)?);
+      return Ok(());
+   }
+
+   if let Some(path) = matches.opt_str("equivalent") {
+      let other = load_netlist(&path)?;
+      match circuit.equivalent(&other, &query)? {
+         None => println!("{} is the same in both", query),
+         Some(assignment) => {
+            let assignment: Vec<String> = assignment.iter().map(|&(ref w, v)| format!("{}={}", w, v)).collect();
+            println!("{} differs when {}", query, assignment.join(" "));
+         }
+      }
+      return Ok(());
+   }
+
+   if let Some(goal) = matches.opt_str("solve") {
+      let (target, want) = parse_override(&goal)?;
+      let vary = matches.opt_str("vary").ok_or(AocError::Invalid("--solve needs --vary".to_string()))?;
+      match circuit.solve(&target, want, &vary)? {
+         Some(v) => println!("{} is {} when {} is {}", target, want, vary, v),
+         None => println!("No value of {} makes {} {}", vary, target, want),
+      }
+      return Ok(());
+   }
+
+   if !overrides.is_empty() || matches.opt_present("query") || matches.opt_present("settle") {
+      // A comma-separated list, unless it's a bus concatenation like {x,y}.
+      if query.contains(',') && !query.starts_with('{') {
+         let wires: Vec<&str> = query.split(',').filter(|w| !w.is_empty()).collect();
+         for (wire, v) in circuit.get_values(&wires)? {
+            println!("{} is {}", wire, v);
+         }
+      } else {
+         // Buses print each lane, most significant first.
+         let values: Vec<String> = circuit.get_bus(&query)?.iter().map(|v| v.to_string()).collect();
+         println!("{} is {}", query, values.join(" "));
+      }
+   } else {
+      let parts = match matches.opt_str("part") {
+         None => vec![1, 2],
+         Some(p) => vec![aoc2015_lib::parse_part(&p).map_err(AocError::Invalid)?],
+      };
+      let (from, to) = parse_feedback(&matches.opt_str("feedback").unwrap_or("a=b".to_string()))?;
+      if parts == [1] && matches.opt_present("feedback") {
+         return Err(AocError::Invalid("--feedback only applies to --part 2".to_string()));
+      }
+      for part in parts {
+         let v = match part {
+            1 => circuit.get_value(&from)?,
+            _ => circuit.feedback(&from, &to)?,
+         };
+         println!("Part {}: {} is {}", part, from, v);
+      }
+   }
+
+   if matches.opt_present("stats") {
+      print!("{}", circuit.cache_stats());
+   }
+
+   if let Some(path) = matches.opt_str("save") {
+      write_file(&path, &circuit.to_json(), "Saved")?;
+   }
+   return Ok(());
 }
 
 #[test]
-fn test_number() {
+fn test_repl() {
    let mut circuit = Circuit::new();
-   circuit.add_element("x", "123");
-   circuit.add_element("y", "456");
-   circuit.add_element("d", "x AND y");
-   circuit.add_element("e", "x OR y");
-   circuit.add_element("f", "x LSHIFT 2");
-   circuit.add_element("g", "y RSHIFT 2");
-   circuit.add_element("h", "NOT x");
-   circuit.add_element("i", "NOT y");
-
-   assert_eq!(circuit.get_value("d"), 72);
-   assert_eq!(circuit.get_value("e"), 507);
-   assert_eq!(circuit.get_value("f"), 492);
-   assert_eq!(circuit.get_value("g"), 114);
-   assert_eq!(circuit.get_value("h"), 65412);
-   assert_eq!(circuit.get_value("i"), 65079);
-   assert_eq!(circuit.get_value("x"), 123);
-   assert_eq!(circuit.get_value("y"), 456);
+   circuit.add_element("x", "123").unwrap();
+   circuit.add_element("y", "456").unwrap();
+   circuit.add_element("d", "x AND y").unwrap();
+
+   assert_eq!(repl_command(&mut circuit, "get d"), "d = 72");
+   assert_eq!(repl_command(&mut circuit, "set x 1"), "x = 1");
+   assert_eq!(repl_command(&mut circuit, "get d"), "d = 0");
+   assert_eq!(repl_command(&mut circuit, "undo x"), "Unset x");
+   assert_eq!(repl_command(&mut circuit, "get d"), "d = 72");
+   assert_eq!(repl_command(&mut circuit, "undo x"), "x isn't set");
+   assert_eq!(repl_command(&mut circuit, "clear"), "Cleared");
+   assert_eq!(repl_command(&mut circuit, "set y 65535"), "y = 65535");
+   assert_eq!(repl_command(&mut circuit, "show x OR y"), "x OR y = 65535");
+   assert_eq!(repl_command(&mut circuit, "get d"), "d = 123");
+   assert_eq!(repl_command(&mut circuit, "deps d"), "d = x AND y\ndepends on 2 wires: x y");
+   assert_eq!(repl_command(&mut circuit, "set q 1"), "No wire named q");
+   assert_eq!(repl_command(&mut circuit, "show x FOO y"), "Can't parse x FOO y");
+   assert_eq!(repl_command(&mut circuit, "define d x OR 4"), "d = x OR 4");
+   assert_eq!(repl_command(&mut circuit, "get d"), "d = 127");
+   assert_eq!(repl_command(&mut circuit, "bogus"), REPL_HELP);
+}
+
+#[test]
+fn test_show() {
+   let mut circuit: Circuit = "123 -> x\n456 -> y\nx AND y -> d".parse().unwrap();
+   assert_eq!(show(&mut circuit, "d OR 1").unwrap(), "d OR 1 = 73");
+   assert_eq!(show(&mut circuit, "NOT  x").unwrap(), "NOT  x = 65412");
+   assert_eq!(show(&mut circuit, "x FROB y").unwrap(), "Can't parse x FROB y");
+   assert!(show(&mut circuit, "q AND 1").is_err());
+   assert_eq!(circuit.len(), 3);
+}
+
+#[test]
+fn test_parse_feedback() {
+   assert_eq!(parse_feedback("c=x").unwrap(), ("c".to_string(), "x".to_string()));
+   assert!(parse_feedback("c=").is_err());
+}
+
+#[test]
+fn test_log_filter() {
+   assert_eq!(log_filter(0, false), "warn");
+   assert_eq!(log_filter(2, false), "debug");
+   assert_eq!(log_filter(5, false), "trace");
+   assert_eq!(log_filter(2, true), "off");
+}
+
+#[test]
+fn test_parse_override() {
+   assert_eq!(parse_override("b=1234").unwrap(), ("b".to_string(), 1234));
+   assert!(parse_override("b").is_err());
+   assert!(parse_override("=1").is_err());
+   assert!(parse_override("b=70000").is_err());
+   assert!(parse_override("b=x").is_err());
+}
+
+#[test]
+fn test_animation_frame() {
+   let mut circuit = "123 -> x\nNOT x -> a".parse::<Circuit>().unwrap();
+   circuit.force_value("x", 123);
+   assert_eq!(animation_frame(&circuit, 1, &["x".to_string()]),
+              "\x1b[2J\x1b[HLevel 1\na=?          \x1b[1;32mx=123       \x1b[0m\n");
 }
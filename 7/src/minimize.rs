@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+use {Circuit, CircuitError};
+
+// Quine-McCluskey's work grows quickly with the number of inputs, so this
+// is kept well under what truth_table would allow.
+pub const MAX_MINIMIZE_INPUTS: usize = 10;
+
+// A product term over the inputs, as the input bits it requires (value)
+// and the ones it doesn't care about (free).  Bits are numbered like
+// truth_table's rows, with the first input the most significant.
+type Implicant = (usize, usize);
+
+fn covers(term: Implicant, minterm: usize) -> bool {
+   minterm & !term.1 == term.0
+}
+
+// Every prime implicant of the function that's true on minterms, found
+// by repeatedly merging terms that differ in a single fixed bit.
+fn prime_implicants(minterms: &[usize]) -> Vec<Implicant> {
+   let mut primes = BTreeSet::new();
+   let mut current: BTreeSet<Implicant> = minterms.iter().map(|&m| (m, 0)).collect();
+   while !current.is_empty() {
+      let terms: Vec<Implicant> = current.into_iter().collect();
+      let mut merged = vec![false; terms.len()];
+      let mut next = BTreeSet::new();
+      for i in 0..terms.len() {
+         for j in i + 1..terms.len() {
+            let diff = terms[i].0 ^ terms[j].0;
+            if terms[i].1 == terms[j].1 && diff.count_ones() == 1 {
+               next.insert((terms[i].0 & !diff, terms[i].1 | diff));
+               merged[i] = true;
+               merged[j] = true;
+            }
+         }
+      }
+      primes.extend(terms.iter().zip(&merged).filter(|&(_, &m)| !m).map(|(&t, _)| t));
+      current = next;
+   }
+   return primes.into_iter().collect();
+}
+
+// The primes needed to cover every minterm: the essential ones, then
+// greedily whichever covers the most of what's left.  The greedy step
+// isn't guaranteed to find the smallest cover, but it's close for
+// functions of a handful of inputs.
+fn cover(primes: &[Implicant], minterms: &[usize]) -> Vec<Implicant> {
+   let mut chosen = BTreeSet::new();
+   for &m in minterms {
+      let covering: Vec<&Implicant> = primes.iter().filter(|&&p| covers(p, m)).collect();
+      if covering.len() == 1 {
+         chosen.insert(*covering[0]);
+      }
+   }
+   let mut remaining: Vec<usize> = minterms.iter().cloned()
+      .filter(|&m| !chosen.iter().any(|&p| covers(p, m))).collect();
+   while !remaining.is_empty() {
+      let best = *primes.iter().max_by_key(|&&p| remaining.iter().filter(|&&m| covers(p, m)).count()).unwrap();
+      chosen.insert(best);
+      remaining.retain(|&m| !covers(best, m));
+   }
+   return chosen.into_iter().collect();
+}
+
+fn product(term: Implicant, inputs: &[&str]) -> String {
+   let mut literals = Vec::new();
+   for (i, input) in inputs.iter().enumerate() {
+      let bit = 1 << (inputs.len() - 1 - i);
+      if term.1 & bit == 0 {
+         literals.push(if term.0 & bit != 0 { input.to_string() } else { format!("NOT {}", input) });
+      }
+   }
+   return literals.join(" AND ");
+}
+
+impl Circuit {
+   // The lowest bit of wire as a sum of products over inputs, each a free
+   // one-bit variable as in truth_table, simplified with Quine-McCluskey.
+   // The result is in netlist syntax, like `(NOT p AND q) OR (p AND NOT q)`,
+   // so it can be pasted back in as a spec.
+   pub fn minimize(&self, wire: &str, inputs: &[&str]) -> Result<String, CircuitError> {
+      if inputs.len() > MAX_MINIMIZE_INPUTS {
+         return Err(CircuitError::TooManyInputs(inputs.len()));
+      }
+      let minterms: Vec<usize> = self.truth_table(wire, inputs)?.into_iter().enumerate()
+         .filter(|&(_, (_, out))| out & 1 == 1).map(|(row, _)| row).collect();
+      let terms = cover(&prime_implicants(&minterms), &minterms);
+      return Ok(match terms.len() {
+         0 => "0".to_string(),
+         1 if terms[0].1 == (1 << inputs.len()) - 1 => "1".to_string(),
+         1 => product(terms[0], inputs),
+         _ => {
+            let products: Vec<String> = terms.iter().map(|&t| match product(t, inputs) {
+               ref p if p.contains(' ') => format!("({})", p),
+               p => p,
+            }).collect();
+            products.join(" OR ")
+         }
+      });
+   }
+}
+
+#[test]
+fn test_minimize() {
+   let circuit = "p XOR q -> sum\np AND q -> carry\n\
+                  a AND b -> x\na AND NOT b -> y\nx OR y -> z\n\
+                  (a AND b) OR (NOT a AND c) OR (b AND c) -> consensus\n\
+                  a OR NOT a -> always".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.minimize("sum", &["p", "q"]).unwrap(), "(NOT p AND q) OR (p AND NOT q)");
+   assert_eq!(circuit.minimize("carry", &["p", "q"]).unwrap(), "p AND q");
+   assert_eq!(circuit.minimize("z", &["a", "b"]).unwrap(), "a");
+   assert_eq!(circuit.minimize("always", &["a"]).unwrap(), "1");
+   assert_eq!(circuit.minimize("carry", &["p", "q", "r"]).unwrap(), "p AND q");
+   let many: Vec<&str> = (0..11).map(|_| "p").collect();
+   assert_eq!(circuit.minimize("sum", &many), Err(CircuitError::TooManyInputs(11)));
+
+   // The simplified form computes the same thing.
+   let inputs = ["a", "b", "c"];
+   let simplified = circuit.minimize("consensus", &inputs).unwrap();
+   assert_eq!(simplified, "(NOT a AND c) OR (a AND b)");
+   let mut both = circuit.clone();
+   both.add_element("simplified", &simplified).unwrap();
+   let low_bits = |wire| -> Vec<u16> {
+      both.truth_table(wire, &inputs).unwrap().into_iter().map(|(_, out)| out & 1).collect()
+   };
+   assert_eq!(low_bits("simplified"), low_bits("consensus"));
+}
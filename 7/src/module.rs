@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use {bus, parse_literal, InvalidInput, Operation};
+
+// A reusable group of gates, written in a netlist like
+//
+//    module half_adder(a, b) -> (sum, carry)
+//    a XOR b -> sum
+//    a AND b -> carry
+//    end
+//
+// and instantiated with `half_adder(x, y) -> (s, c)`.  Instances get
+// flattened into ordinary wires: ports become whatever they're connected
+// to, and everything else inside is prefixed with a name unique to the
+// instance, like `half_adder1.t`.
+#[derive(Debug, Clone)]
+pub struct Module {
+   pub inputs: Vec<String>,
+   pub outputs: Vec<String>,
+   pub body: Vec<String>,
+}
+
+fn is_identifier(s: &str) -> bool {
+   !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn port_list(s: &str) -> Result<Vec<String>, InvalidInput> {
+   let s = s.trim();
+   if s.is_empty() {
+      return Ok(Vec::new());
+   }
+   let ports: Vec<String> = s.split(',').map(|p| p.trim().to_string()).collect();
+   if ports.iter().any(|p| p.is_empty() || p.contains(' ')) {
+      return Err(InvalidInput);
+   }
+   return Ok(ports);
+}
+
+// Splits `name(a, b) -> (c, d)` into its name, arguments and outputs.  The
+// parentheses around the outputs are optional.
+pub fn parse_call(text: &str) -> Result<(String, Vec<String>, Vec<String>), InvalidInput> {
+   let sides: Vec<&str> = text.split("->").collect();
+   if sides.len() != 2 {
+      return Err(InvalidInput);
+   }
+   let call = sides[0].trim();
+   let open = call.find('(').ok_or(InvalidInput)?;
+   let name = call[..open].trim();
+   if !is_identifier(name) || !call.ends_with(')') {
+      return Err(InvalidInput);
+   }
+   let mut outputs = sides[1].trim();
+   if outputs.starts_with('(') && outputs.ends_with(')') {
+      outputs = &outputs[1..outputs.len() - 1];
+   }
+   let outputs = port_list(outputs)?;
+   if outputs.is_empty() {
+      return Err(InvalidInput);
+   }
+   return Ok((name.to_string(), port_list(&call[open + 1..call.len() - 1])?, outputs));
+}
+
+// The module name if text instantiates one of modules.
+pub fn instantiates<'a>(text: &str, modules: &'a HashMap<String, Module>) -> Option<&'a str> {
+   let open = text.find('(')?;
+   return modules.get_key_value(text[..open].trim()).map(|(name, _)| &name[..]);
+}
+
+impl Module {
+   // Rewrites one wire reference for the instance, keeping any bus slice.
+   fn rename(&self, token: &str, ports: &HashMap<&str, &str>, prefix: &str) -> String {
+      if token == "NOT" || token == "(" || token == ")" || token.parse::<Operation>().is_ok() || parse_literal(token).is_some() {
+         return token.to_string();
+      }
+      if token.starts_with('{') && token.ends_with('}') && token.len() >= 2 {
+         let parts: Vec<String> = token[1..token.len() - 1].split(',').map(|p| self.rename(p, ports, prefix)).collect();
+         return format!("{{{}}}", parts.join(","));
+      }
+      let split = token.find('[').unwrap_or(token.len());
+      let (wire, slice) = token.split_at(split);
+      return match ports.get(wire) {
+         Some(outside) => format!("{}{}", outside, slice),
+         None => format!("{}.{}{}", prefix, wire, slice),
+      };
+   }
+
+   // The body with ports connected to args and outputs, and internal wires
+   // prefixed.  Nested instantiations come back as instantiations, with
+   // their connections renamed.
+   pub fn flatten(&self, args: &[String], outputs: &[String], prefix: &str, modules: &HashMap<String, Module>)
+      -> Result<Vec<String>, InvalidInput> {
+      if args.len() != self.inputs.len() || outputs.len() != self.outputs.len() {
+         return Err(InvalidInput);
+      }
+      let mut ports = HashMap::new();
+      for (port, outside) in self.inputs.iter().zip(args).chain(self.outputs.iter().zip(outputs)) {
+         ports.insert(&port[..], &outside[..]);
+      }
+
+      let mut lines = Vec::with_capacity(self.body.len());
+      for line in &self.body {
+         if let Some(name) = instantiates(line, modules) {
+            let (_, inner_args, inner_outputs) = parse_call(line)?;
+            let rename_all = |wires: &[String]| -> Vec<String> {
+               wires.iter().map(|w| self.rename(w, &ports, prefix)).collect()
+            };
+            lines.push(format!("{}({}) -> ({})", name, rename_all(&inner_args).join(", "),
+                               rename_all(&inner_outputs).join(", ")));
+            continue;
+         }
+         let sides: Vec<&str> = line.split("->").map(|s| s.trim()).collect();
+         if sides.len() != 2 {
+            return Err(InvalidInput);
+         }
+         let spec: Vec<String> = bus::tokens(sides[0]).iter().map(|t| self.rename(t, &ports, prefix)).collect();
+         lines.push(format!("{} -> {}", spec.join(" "), self.rename(sides[1], &ports, prefix)));
+      }
+      return Ok(lines);
+   }
+}
+
+#[test]
+fn test_parse_call() {
+   assert_eq!(parse_call("half_adder(a, b) -> (sum, carry)").unwrap(),
+              ("half_adder".to_string(), vec!["a".to_string(), "b".to_string()], vec!["sum".to_string(), "carry".to_string()]));
+   assert_eq!(parse_call("m() -> x").unwrap(), ("m".to_string(), Vec::new(), vec!["x".to_string()]));
+   for bad in &["m(a -> x", "m(a) -> ()", "(a) -> x", "m x(a) -> x", "m(a,,b) -> x", "m(a) -> x -> y"] {
+      assert!(parse_call(bad).is_err(), "{:?}", bad);
+   }
+}
@@ -0,0 +1,84 @@
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use std::fs::File;
+use std::io::BufReader;
+use {Circuit, CircuitError};
+
+// The circuit engine for Python, built into an extension module called
+// `circuit` (e.g. with `maturin develop --features python`):
+//
+//    >>> from circuit import Circuit
+//    >>> c = Circuit.from_file("input.txt")
+//    >>> c["a"]
+//    46065
+//    >>> c.force("b", 46065)
+//    >>> c.clear()
+#[pyclass(name = "Circuit")]
+pub struct PyCircuit {
+   circuit: Circuit,
+}
+
+fn to_py_err(e: CircuitError) -> PyErr {
+   match e {
+      CircuitError::Undefined(wire) => PyKeyError::new_err(wire),
+      other => PyValueError::new_err(other.to_string()),
+   }
+}
+
+#[pymethods]
+impl PyCircuit {
+   // An empty circuit, or one loaded from netlist text.
+   #[new]
+   #[pyo3(signature = (netlist=""))]
+   fn new(netlist: &str) -> PyResult<PyCircuit> {
+      let circuit = netlist.parse::<Circuit>().map_err(|e| PyValueError::new_err(e.to_string()))?;
+      return Ok(PyCircuit{circuit: circuit});
+   }
+
+   #[staticmethod]
+   fn from_file(path: &str) -> PyResult<PyCircuit> {
+      let f = File::open(path)?;
+      let circuit = Circuit::from_reader(BufReader::new(f)).map_err(|e| PyValueError::new_err(e.to_string()))?;
+      return Ok(PyCircuit{circuit: circuit});
+   }
+
+   fn __getitem__(&mut self, wire: &str) -> PyResult<u16> {
+      return self.circuit.get_value(wire).map_err(to_py_err);
+   }
+
+   fn __contains__(&self, wire: &str) -> bool {
+      return self.circuit.element(wire).is_some();
+   }
+
+   fn __len__(&self) -> usize {
+      return self.circuit.len();
+   }
+
+   // Adds a netlist line like "x AND y -> d".
+   fn add_line(&mut self, line: &str) -> PyResult<()> {
+      return self.circuit.add_line(0, line).map_err(|e| PyValueError::new_err(e.to_string()));
+   }
+
+   fn force(&mut self, wire: &str, value: u16) -> PyResult<()> {
+      if self.circuit.element(wire).is_none() {
+         return Err(PyKeyError::new_err(wire.to_string()));
+      }
+      self.circuit.force_value(wire, value);
+      return Ok(());
+   }
+
+   // Forgets every resolved and forced value.
+   fn clear(&mut self) {
+      self.circuit.clear_cache();
+   }
+
+   fn wires(&self) -> Vec<String> {
+      return self.circuit.sorted_names().iter().map(|w| w.to_string()).collect();
+   }
+}
+
+#[pymodule]
+fn circuit(m: &Bound<PyModule>) -> PyResult<()> {
+   m.add_class::<PyCircuit>()?;
+   return Ok(());
+}
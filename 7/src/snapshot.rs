@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use {Circuit, CircuitError};
+
+// A saved set of wire values and overrides, from Circuit::snapshot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateId(usize);
+
+// What snapshot keeps: every wire's cached value, by id, and the forced
+// ones with their undo history, along with the revision of the
+// definitions they were computed from.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+   revision: usize,
+   values: Vec<Option<u16>>,
+   overrides: HashMap<usize, u16>,
+   forced: Vec<(usize, Option<u16>)>,
+}
+
+impl Circuit {
+   // Saves the values resolved so far and any forced wires, so a what-if
+   // can be tried and then undone with restore without re-evaluating
+   // everything from scratch.
+   pub fn snapshot(&mut self) -> StateId {
+      self.snapshots.push(Snapshot {
+         revision: self.revision,
+         values: self.elements.iter().map(|e| e.as_ref().and_then(|e| e.value)).collect(),
+         overrides: self.overrides.clone(),
+         forced: self.forced.clone(),
+      });
+      return StateId(self.snapshots.len() - 1);
+   }
+
+   // Puts back the values and overrides saved by snapshot.  A snapshot can
+   // be restored any number of times, but not once a wire has been
+   // (re)defined since, as its values might no longer add up.
+   pub fn restore(&mut self, state: StateId) -> Result<(), CircuitError> {
+      let snapshot = match self.snapshots.get(state.0) {
+         Some(s) if s.revision == self.revision => s,
+         _ => return Err(CircuitError::StaleSnapshot),
+      };
+      for (id, element) in self.elements.iter_mut().enumerate() {
+         if let Some(ref mut e) = *element {
+            match snapshot.values.get(id).cloned().unwrap_or(None) {
+               Some(v) => e.set_value(v),
+               None => e.clear_value(),
+            }
+         }
+      }
+      self.overrides = snapshot.overrides.clone();
+      self.forced = snapshot.forced.clone();
+      return Ok(());
+   }
+}
+
+#[test]
+fn test_snapshot() {
+   let mut circuit: Circuit = "123 -> x\n456 -> y\nx AND y -> d\nNOT x -> e".parse().unwrap();
+   let empty = circuit.snapshot();
+   assert_eq!(circuit.get_value("d").unwrap(), 72);
+   let resolved = circuit.snapshot();
+
+   circuit.force_value("x", 1);
+   assert_eq!(circuit.get_value("d").unwrap(), 0);
+   let forced = circuit.snapshot();
+
+   circuit.restore(resolved).unwrap();
+   assert_eq!(circuit.element("d").unwrap().value, Some(72));
+   assert_eq!(circuit.element("e").unwrap().value, None);
+   assert_eq!(circuit.get_value("x").unwrap(), 123);
+
+   circuit.restore(forced).unwrap();
+   assert_eq!(circuit.get_value("d").unwrap(), 0);
+   // x is still forced, not just cached.
+   circuit.force_value("y", 0xffff);
+   assert_eq!(circuit.get_value("d").unwrap(), 1);
+
+   circuit.restore(empty).unwrap();
+   assert_eq!(circuit.element("x").unwrap().value, None);
+   circuit.redefine("y", "7").unwrap();
+   assert_eq!(circuit.restore(resolved), Err(CircuitError::StaleSnapshot));
+}
@@ -0,0 +1,45 @@
+use aoc2015_lib::{AocError, Solver};
+use {Circuit, CircuitError, ParseError};
+
+impl From<ParseError> for AocError {
+   fn from(e: ParseError) -> AocError {
+      return AocError::parse(e.line, None, format!("can't parse {:?}", e.text));
+   }
+}
+
+impl From<CircuitError> for AocError {
+   fn from(e: CircuitError) -> AocError {
+      return AocError::Unsolvable(e.to_string());
+   }
+}
+
+// The puzzle itself: the signal on a, and then on a again once b has been
+// given the first one.
+pub struct Day07;
+
+impl Solver for Day07 {
+   type Parsed = Circuit;
+
+   fn parse(input: &str) -> Result<Circuit, AocError> {
+      return Ok(input.parse::<Circuit>()?);
+   }
+
+   // Each part works on its own copy of the circuit, so neither gets a
+   // head start from what the other left cached.
+   fn part1(circuit: &Circuit) -> Result<String, AocError> {
+      return Ok(circuit.clone().get_value("a")?.to_string());
+   }
+
+   fn part2(circuit: &Circuit) -> Result<String, AocError> {
+      return Ok(circuit.clone().feedback("a", "b")?.to_string());
+   }
+}
+
+#[test]
+fn test_solver() {
+   let circuit = Day07::parse("123 -> b\nb OR 4 -> a\nb AND 3 -> c\n").unwrap();
+   assert_eq!(Day07::part1(&circuit).unwrap(), "127");
+   assert_eq!(Day07::part2(&circuit).unwrap(), "127");
+   assert_eq!(Day07::parse("1 -> b\nb -> a -> c").unwrap_err().to_string(), "line 2: can't parse \"b -> a -> c\"");
+   assert!(Day07::part1(&Day07::parse("b -> c").unwrap()).is_err());
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use {gate_detail, Circuit, CircuitError};
+
+const BOX_WIDTH: usize = 110;
+const BOX_HEIGHT: usize = 36;
+const COLUMN_WIDTH: usize = 170;
+const ROW_HEIGHT: usize = 56;
+const MARGIN: usize = 20;
+
+fn escape(s: &str) -> String {
+   s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl Circuit {
+   // A schematic of everything feeding target as an SVG image.  Wires are
+   // laid out in columns by how many gates lie between them and the
+   // circuit's inputs, so signals flow left to right and target ends up
+   // alone on the right.  Within a column wires are sorted by name, so the
+   // same circuit always draws the same way.  Undefined wires show up as
+   // dashed boxes on the far left.
+   pub fn to_svg(&self, target: &str) -> Result<String, CircuitError> {
+      let id = match self.id(target) {
+         Some(id) if self.elements[id].is_some() => id,
+         _ => return Err(CircuitError::Undefined(target.to_string())),
+      };
+      let order = self.simulate(&[id], |_| None, Some(0u16))?;
+
+      let mut column: HashMap<usize, usize> = HashMap::new();
+      for &(wire, _) in &order {
+         let e = self.elements[wire].as_ref().unwrap();
+         let mut c = 0;
+         for w in e.wire_operands() {
+            c = c.max(*column.entry(w).or_insert(0) + 1);
+         }
+         column.insert(wire, c);
+      }
+
+      let columns = column.values().cloned().max().unwrap_or(0) + 1;
+      let mut by_column: Vec<Vec<usize>> = vec![Vec::new(); columns];
+      for (&wire, &c) in &column {
+         by_column[c].push(wire);
+      }
+      let mut position = HashMap::new();
+      for wires in &mut by_column {
+         wires.sort_by(|&a, &b| self.names[a].cmp(&self.names[b]));
+      }
+      for (c, wires) in by_column.iter().enumerate() {
+         for (row, &wire) in wires.iter().enumerate() {
+            position.insert(wire, (MARGIN + c * COLUMN_WIDTH, MARGIN + row * ROW_HEIGHT));
+         }
+      }
+      let rows = by_column.iter().map(|w| w.len()).max().unwrap_or(1);
+      let width = 2 * MARGIN + (columns - 1) * COLUMN_WIDTH + BOX_WIDTH;
+      let height = 2 * MARGIN + (rows - 1) * ROW_HEIGHT + BOX_HEIGHT;
+
+      let mut out = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+                             font-family=\"monospace\" font-size=\"12\">\n", width, height);
+      // Edges go first so the boxes are drawn over their ends.
+      for &(wire, _) in &order {
+         let (x2, y2) = position[&wire];
+         for w in self.elements[wire].as_ref().unwrap().wire_operands() {
+            let (x1, y1) = position[&w];
+            let (x1, y1, y2) = (x1 + BOX_WIDTH, y1 + BOX_HEIGHT / 2, y2 + BOX_HEIGHT / 2);
+            let bend = (x2 - x1) / 2;
+            out.push_str(&format!("   <path d=\"M {} {} C {} {}, {} {}, {} {}\" fill=\"none\" stroke=\"gray\"/>\n",
+                                  x1, y1, x1 + bend, y1, x2 - bend, y2, x2, y2));
+         }
+      }
+      for wires in &by_column {
+         for &wire in wires {
+            let (x, y) = position[&wire];
+            let (detail, dash) = match self.elements[wire] {
+               Some(ref e) => (gate_detail(&e.spec).join(" "), ""),
+               None => ("undefined".to_string(), " stroke-dasharray=\"4\""),
+            };
+            out.push_str(&format!("   <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"black\"{}/>\n",
+                                  x, y, BOX_WIDTH, BOX_HEIGHT, dash));
+            out.push_str(&format!("   <text x=\"{}\" y=\"{}\" font-weight=\"bold\">{}</text>\n",
+                                  x + 6, y + 15, escape(&self.names[wire])));
+            out.push_str(&format!("   <text x=\"{}\" y=\"{}\">{}</text>\n", x + 6, y + 30, escape(&detail)));
+         }
+      }
+      out.push_str("</svg>\n");
+      return Ok(out);
+   }
+}
+
+#[test]
+fn test_svg() {
+   let circuit = "123 -> x\nx AND q -> d\nd -> a".parse::<Circuit>().unwrap();
+   assert_eq!(circuit.to_svg("a").unwrap(), r#"<svg xmlns="http://www.w3.org/2000/svg" width="490" height="132" font-family="monospace" font-size="12">
+   <path d="M 130 94 C 160 94, 160 38, 190 38" fill="none" stroke="gray"/>
+   <path d="M 130 38 C 160 38, 160 38, 190 38" fill="none" stroke="gray"/>
+   <path d="M 300 38 C 330 38, 330 38, 360 38" fill="none" stroke="gray"/>
+   <rect x="20" y="20" width="110" height="36" fill="white" stroke="black" stroke-dasharray="4"/>
+   <text x="26" y="35" font-weight="bold">q</text>
+   <text x="26" y="50">undefined</text>
+   <rect x="20" y="76" width="110" height="36" fill="white" stroke="black"/>
+   <text x="26" y="91" font-weight="bold">x</text>
+   <text x="26" y="106">123</text>
+   <rect x="190" y="20" width="110" height="36" fill="white" stroke="black"/>
+   <text x="196" y="35" font-weight="bold">d</text>
+   <text x="196" y="50">AND</text>
+   <rect x="360" y="20" width="110" height="36" fill="white" stroke="black"/>
+   <text x="366" y="35" font-weight="bold">a</text>
+   <text x="366" y="50"></text>
+</svg>
+"#);
+   assert_eq!(escape("a<b&\"c\">"), "a&lt;b&amp;&quot;c&quot;&gt;");
+}
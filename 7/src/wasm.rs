@@ -0,0 +1,46 @@
+use wasm_bindgen::prelude::*;
+use Circuit;
+
+// A circuit for JavaScript, loaded from netlist text:
+//
+//    const circuit = new Circuit("123 -> x\nNOT x -> a");
+//    circuit.get_value("a");  // 65412
+//    circuit.force_value("x", 0);
+//
+// Errors come back as exceptions carrying the message.
+#[wasm_bindgen(js_name = Circuit)]
+pub struct WasmCircuit {
+   circuit: Circuit,
+}
+
+#[wasm_bindgen(js_class = Circuit)]
+impl WasmCircuit {
+   #[wasm_bindgen(constructor)]
+   pub fn new(netlist: &str) -> Result<WasmCircuit, JsValue> {
+      let circuit = netlist.parse::<Circuit>().map_err(|e| JsValue::from_str(&e.to_string()))?;
+      return Ok(WasmCircuit{circuit: circuit});
+   }
+
+   pub fn get_value(&mut self, wire: &str) -> Result<u16, JsValue> {
+      return self.circuit.get_value(wire).map_err(|e| JsValue::from_str(&e.to_string()));
+   }
+
+   pub fn force_value(&mut self, wire: &str, value: u16) {
+      self.circuit.force_value(wire, value);
+   }
+
+   // Forgets every resolved and forced value.
+   pub fn clear(&mut self) {
+      self.circuit.clear_cache();
+   }
+
+   // Every defined wire, sorted, for listing in the page.
+   pub fn wires(&self) -> Vec<String> {
+      return self.circuit.sorted_names().iter().map(|w| w.to_string()).collect();
+   }
+
+   // The circuit as a Graphviz graph, for drawing it.
+   pub fn to_dot(&self) -> String {
+      return self.circuit.to_dot();
+   }
+}
@@ -0,0 +1,90 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+extern crate aoc2015_lib;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use aoc2015_lib::{parse_lines, AocError, Solver};
+
+pub fn unescape_len(s: &str) -> Result<usize, String> {
+   let mut itr = s.chars();
+   let (start, end) = (itr.next(), itr.next_back());
+   if start != Some('"') || end != Some('"') {
+      return Err(format!("expected a quoted string, not {}", s));
+   }
+   let mut count = 0;
+   while let Some(next) = itr.next() {
+      count += match next {
+         '\\' => {
+            match itr.next() {
+               Some('\\') => 1,
+               Some('"') => 1,
+               Some('x') => {itr.next(); itr.next(); 1 },
+               Some(x) => return Err(format!("unknown escape sequence \\{} in {}", x, s)),
+               None => return Err(format!("{} ends in the middle of an escape", s)),
+            }
+         }
+         _ => 1,
+      };
+   }
+   return Ok(count);
+}
+
+pub fn escape_len(s: &str) -> usize {
+   // min size with quotes
+   let mut count = 2;
+   for next in s.chars() {
+      count += match next {
+         '\\' => 2,
+         '"' => 2,
+         _ => 1,
+      };
+   }
+   return count;
+}
+
+// The length of each line in input as written, unescaped and escaped
+// again.
+pub fn lengths(input: &str) -> Result<Vec<(usize, usize, usize)>, AocError> {
+   return parse_lines(input, |curr| Ok((curr.len(), unescape_len(curr)?, escape_len(curr))));
+}
+
+// The same lengths, totalled.
+pub fn totals(input: &str) -> Result<(usize, usize, usize), AocError> {
+   return Ok(lengths(input)?.into_iter().fold((0, 0, 0), |t, l| (t.0 + l.0, t.1 + l.1, t.2 + l.2)));
+}
+
+// The puzzle itself: what unescaping saves, then what escaping costs.
+pub struct Day08;
+
+impl Solver for Day08 {
+   type Parsed = Vec<(usize, usize, usize)>;
+
+   fn parse(input: &str) -> Result<Vec<(usize, usize, usize)>, AocError> {
+      return lengths(input);
+   }
+
+   fn part1(lengths: &Vec<(usize, usize, usize)>) -> Result<String, AocError> {
+      return Ok(lengths.iter().map(|l| l.0 - l.1).sum::<usize>().to_string());
+   }
+
+   fn part2(lengths: &Vec<(usize, usize, usize)>) -> Result<String, AocError> {
+      return Ok(lengths.iter().map(|l| l.2 - l.0).sum::<usize>().to_string());
+   }
+}
+
+#[test]
+fn known() {
+   assert_eq!(unescape_len(r#""""#), Ok(0));
+   assert_eq!(unescape_len(r#""abc""#), Ok(3));
+   assert_eq!(unescape_len(r#""aaa\"aaa""#), Ok(7));
+   assert_eq!(unescape_len(r#""\x27""#), Ok(1));
+   assert!(unescape_len(r#""\q""#).is_err());
+   assert_eq!(totals("\"a\"\nabc\n").unwrap_err().to_string(), "line 2: expected a quoted string, not abc");
+
+   assert_eq!(escape_len(r#""""#), 6);
+   assert_eq!(escape_len(r#""abc""#), 9);
+   assert_eq!(escape_len(r#""aaa\"aaa""#), 16);
+   assert_eq!(escape_len(r#""\x27""#), 11);
+}
@@ -0,0 +1,149 @@
+#![no_std]
+#[macro_use]
+extern crate alloc;
+extern crate aoc2015_lib;
+
+use aoc2015_lib::parse::fields;
+use aoc2015_lib::{parse_lines, AocError, Solver};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Debug,Clone)]
+pub struct Path {
+   pub path: Vec<String>,
+   pub distance: u32,
+}
+
+impl Path {
+   fn push(&mut self, next: &str, dist: u32) {
+      self.path.push(next.to_string());
+      self.distance += dist
+   }
+
+   fn best(one: Option<Path>, two: Path) -> Path {
+      return match one {
+         Some(x) => return if x.distance < two.distance { x } else { two },
+         None => two,
+      }
+   } 
+   fn worst(one: Option<Path>, two: Path) -> Path {
+      return match one {
+         Some(x) => return if x.distance > two.distance { x } else { two },
+         None => two,
+      }
+   } 
+}
+
+#[derive(Debug)]
+pub struct Map {
+   pub distances: BTreeMap<(String, String), u32>,
+   pub places: BTreeSet<String>,
+}
+
+impl Map {
+   // The distances between places, one pair per line like
+   // `London to Dublin = 464`.
+   pub fn parse(input: &str) -> Result<Map, AocError> {
+      let mut distances = BTreeMap::new();
+      let mut places = BTreeSet::new();
+
+      let roads = parse_lines(input, |curr| {
+         return match fields(curr, &[" to ", " = "]).as_ref().map(|f| &f[..]) {
+            Some(&[to, from, dist]) => match dist.parse::<u32>() {
+               Ok(dist) => Ok((to.to_string(), from.to_string(), dist)),
+               Err(_) => Err(format!("expected a distance, not {:?}", dist)),
+            },
+            _ => Err(format!("expected a distance like `London to Dublin = 464`, not {:?}", curr)),
+         };
+      })?;
+      for (to, from, dist) in roads {
+         distances.insert((to.clone(), from.clone()), dist);
+         distances.insert((from.clone(), to.clone()), dist);
+         places.insert(to);
+         places.insert(from);
+      }
+      return Ok(Map{distances: distances, places: places});
+   }
+
+   // The shortest and longest routes that visit every place once.
+   pub fn routes(&self) -> Result<(Path, Path), AocError> {
+      let short = self.shortest_route(&self.places, Path{path: vec![], distance: 0})?;
+      let long = self.longest_route(&self.places, Path{path: vec![], distance: 0})?;
+      return Ok((short, long));
+   }
+
+   fn distance(&self, a: &str, b: &str) -> Result<u32, AocError> {
+      return self.distances.get(&(a.to_string(), b.to_string())).cloned()
+         .ok_or_else(|| AocError::Unsolvable(format!("there's no way from {} to {}", a, b)));
+   }
+
+   pub fn shortest_route(&self, left: &BTreeSet<String>, prev: Path) -> Result<Path, AocError> {
+      if left.is_empty() {
+         return Ok(prev);
+      }
+
+      let mut best: Option<Path> = None;
+      for next in left {
+         let mut distance = 0;
+         if !prev.path.is_empty() {
+            distance = self.distance(prev.path.last().unwrap(), next)?;
+         }
+         let mut next_path = prev.clone();
+         next_path.push(next, distance);
+         let mut next_left: BTreeSet<String> = left.clone();
+         next_left.remove(next);
+         best = Some(Path::best(best, self.shortest_route(&next_left, next_path)?));
+      }
+      return Ok(best.unwrap());
+   }
+
+   pub fn longest_route(&self, left: &BTreeSet<String>, prev: Path) -> Result<Path, AocError> {
+      if left.is_empty() {
+         return Ok(prev);
+      }
+
+      let mut worst: Option<Path> = None;
+      for next in left {
+         let mut distance = 0;
+         if !prev.path.is_empty() {
+            distance = self.distance(prev.path.last().unwrap(), next)?;
+         }
+         let mut next_path = prev.clone();
+         next_path.push(next, distance);
+         let mut next_left: BTreeSet<String> = left.clone();
+         next_left.remove(next);
+         worst = Some(Path::worst(worst, self.longest_route(&next_left, next_path)?));
+      }
+      return Ok(worst.unwrap());
+   }
+}
+
+// The puzzle itself: the shortest route, then the longest.
+pub struct Day09;
+
+impl Solver for Day09 {
+   type Parsed = Map;
+
+   fn parse(input: &str) -> Result<Map, AocError> {
+      return Map::parse(input);
+   }
+
+   fn part1(map: &Map) -> Result<String, AocError> {
+      return Ok(map.shortest_route(&map.places, Path{path: vec![], distance: 0})?.distance.to_string());
+   }
+
+   fn part2(map: &Map) -> Result<String, AocError> {
+      return Ok(map.longest_route(&map.places, Path{path: vec![], distance: 0})?.distance.to_string());
+   }
+}
+
+#[test]
+fn test_routes() {
+   let map = Map::parse("London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141\n").unwrap();
+   let (short, long) = map.routes().unwrap();
+   assert_eq!((short.distance, long.distance), (605, 982));
+   assert_eq!(Map::parse("London to Dublin = far").unwrap_err().to_string(), "line 1: expected a distance, not \"far\"");
+   let map = Map::parse("London to Dublin = 464\nParis to Rome = 1\n").unwrap();
+   assert!(map.routes().is_err());
+}
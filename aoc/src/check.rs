@@ -0,0 +1,112 @@
+use aoc2015_lib::AocError;
+use config::Config;
+use days::{workspace_root, DAYS};
+use fetch;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use toml;
+
+// The known answers for our inputs, kept so that reworking a solver can
+// be checked against them.
+pub fn answers_file() -> PathBuf {
+   return workspace_root().join("answers.toml");
+}
+
+// Expected answers by (day, part), from a file like
+//
+//    [day07]
+//    part1 = 46065
+//    part2 = 14134
+pub struct Answers(BTreeMap<(u32, u32), String>);
+
+impl Answers {
+   pub fn parse(s: &str) -> Result<Answers, String> {
+      let table: toml::Table = s.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+      let mut answers = BTreeMap::new();
+      for (key, parts) in &table {
+         let day = match key.strip_prefix("day").map(|d| d.parse::<u32>()) {
+            Some(Ok(day)) => day,
+            _ => return Err(format!("Expected a day like [day07], not [{}]", key)),
+         };
+         let parts = parts.as_table().ok_or(format!("Expected [{}] to be a table", key))?;
+         for (name, answer) in parts {
+            let part = match &name[..] {
+               "part1" => 1,
+               "part2" => 2,
+               _ => return Err(format!("Expected part1 or part2 in [{}], not {}", key, name)),
+            };
+            let answer = match *answer {
+               toml::Value::String(ref s) => s.clone(),
+               toml::Value::Integer(i) => i.to_string(),
+               _ => return Err(format!("Expected {}.{} to be a number or a string", key, name)),
+            };
+            answers.insert((day, part), answer);
+         }
+      }
+      return Ok(Answers(answers));
+   }
+
+   pub fn load() -> Result<Answers, String> {
+      let path = answers_file();
+      let s = fs::read_to_string(&path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+      return Answers::parse(&s).map_err(|e| format!("{}: {}", path.display(), e));
+   }
+
+   pub fn expected(&self, day: u32, part: u32) -> Option<&str> {
+      return self.0.get(&(day, part)).map(|a| &a[..]);
+   }
+}
+
+// Runs every part of every day that has a known answer, printing whether
+// it still gets it.  Returns false if any didn't.
+pub fn check(config: &Config, answers: &Answers) -> bool {
+   let mut ok = true;
+   for day in DAYS {
+      let known: Vec<(u32, &str)> = (1..3)
+         .filter_map(|part| answers.expected(day.day, part).map(|a| (part, a))).collect();
+      if known.is_empty() {
+         println!("Day {}: no known answers", day.day);
+         continue;
+      }
+      let input = fetch::find_input(config, day)
+         .and_then(|path| fs::read_to_string(&path).map_err(|e| AocError::io(&path.to_string_lossy(), e)));
+      let puzzle = match input.and_then(|input| (day.parse)(&input)) {
+         Ok(puzzle) => puzzle,
+         Err(e) => {
+            println!("Day {}: FAIL, {}", day.day, e);
+            ok = false;
+            continue;
+         }
+      };
+      for (part, expected) in known {
+         match puzzle.part(part) {
+            Ok(ref answer) if answer == expected => println!("Day {} part {}: ok", day.day, part),
+            Ok(answer) => {
+               println!("Day {} part {}: FAIL, got {} but expected {}", day.day, part, answer, expected);
+               ok = false;
+            }
+            Err(e) => {
+               println!("Day {} part {}: FAIL, {}", day.day, part, e);
+               ok = false;
+            }
+         }
+      }
+   }
+   return ok;
+}
+
+#[test]
+fn test_parse_answers() {
+   let answers = Answers::parse("[day07]\npart1 = 46065\npart2 = \"14134\"\n\n[day01]\npart1 = -3\n").unwrap();
+   assert_eq!(answers.expected(7, 1), Some("46065"));
+   assert_eq!(answers.expected(7, 2), Some("14134"));
+   assert_eq!(answers.expected(1, 1), Some("-3"));
+   assert_eq!(answers.expected(1, 2), None);
+   assert!(Answers::parse("[seven]\npart1 = 1\n").is_err());
+   assert!(Answers::parse("[day07]\npart3 = 1\n").is_err());
+   assert!(Answers::parse("[day07]\npart1 = 1.5\n").is_err());
+   // The answers we've got should all be for days that are solved.
+   let ours = Answers::load().unwrap();
+   assert!(ours.0.keys().all(|&(day, _)| DAYS.iter().any(|d| d.day == day)));
+}
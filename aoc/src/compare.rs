@@ -0,0 +1,68 @@
+// One day solved for several inputs side by side, as in
+// `aoc run 7 --inputs alice.txt,bob.txt`, for a study group comparing
+// what everyone's input came to and how long it took.
+use aoc2015_lib::{self, AocError};
+use days::Day;
+use results::{self, Run};
+use timing;
+
+// Runs day's parts on each input in turn, rather than all at once, so
+// that none of them is slowed down by the others.
+pub fn compare(day: &Day, parts: &[u32], inputs: &[&str]) -> Vec<Run> {
+   return inputs.iter().map(|&path| {
+      let input = aoc2015_lib::read(path).map_err(|e| AocError::io(path, e));
+      return results::run(day, parts, input, false);
+   }).collect();
+}
+
+// A row for each input, with each part's answer and how long it took.
+// Inputs that couldn't be read or parsed at all are listed under it
+// instead, since why would be far wider than any answer.
+pub fn table(inputs: &[&str], parts: &[u32], runs: &[Run]) -> String {
+   let mut header = vec!["input".to_string()];
+   for part in parts {
+      header.push(format!("part {}", part));
+      header.push("took".to_string());
+   }
+   let mut rows = vec![header];
+   let mut failed = String::new();
+   for (input, run) in inputs.iter().zip(runs) {
+      if let Some(ref e) = run.failed {
+         failed.push_str(&format!("{} failed: {}\n", input, e));
+         continue;
+      }
+      let mut row = vec![input.to_string()];
+      for &(part, ref answer) in &run.answers {
+         row.push(match *answer {
+            Ok(ref answer) => answer.clone(),
+            Err(ref e) => format!("failed: {}", e),
+         });
+         row.push(run.timing.parts[part as usize - 1].map(timing::ms).unwrap_or_default());
+      }
+      rows.push(row);
+   }
+   return timing::layout(&rows, false) + &failed;
+}
+
+#[test]
+fn test_compare() {
+   let day = ::days::find(7).unwrap();
+   let inputs = [day.default_input().to_string_lossy().into_owned(), "nobody.txt".to_string()];
+   let inputs: Vec<&str> = inputs.iter().map(|i| &i[..]).collect();
+   let mut runs = compare(day, &[1, 2], &inputs);
+   assert!(runs[0].ok());
+   assert!(!runs[1].ok());
+   for run in runs.iter_mut() {
+      run.timing.parts = [Some(::std::time::Duration::from_millis(2)), None];
+   }
+   runs[1].failed = Some("no input".to_string());
+   runs.push(compare(day, &[1, 2], &[inputs[0]]).remove(0));
+   runs[2].answers[1].1 = Err("no".to_string());
+   runs[2].timing.parts = [Some(::std::time::Duration::from_millis(1)), None];
+   assert_eq!(table(&["alice", "bob", "carol"], &[1, 2], &runs), "\
+input  part 1     took      part 2  took
+alice   46065  2.000ms       14134
+carol   46065  1.000ms  failed: no
+bob failed: no input
+");
+}
@@ -0,0 +1,163 @@
+use days::workspace_root;
+use results::Format;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml;
+
+// ~/.config/aoc2015/config.toml, or $AOC_CONFIG if that's set.
+pub fn config_file() -> Option<PathBuf> {
+   if let Some(path) = env::var_os("AOC_CONFIG") {
+      return Some(PathBuf::from(path));
+   }
+   let config = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+      .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+   return config.map(|c| c.join("aoc2015").join("config.toml"));
+}
+
+// The text of the config file at path, or None if there isn't one.  One
+// that's there but can't be read is an error, as one that can't be parsed
+// is, rather than being quietly left out.
+fn read_file(path: &Path) -> Result<Option<String>, String> {
+   return match fs::read_to_string(path) {
+      Ok(s) => Ok(Some(s)),
+      Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+      Err(e) => Err(format!("{}: {}", path.display(), e)),
+   };
+}
+
+// The settings fetch, submit and run share, from a file like
+//
+//    session = "53616c7465645f5f..."
+//    user = "kevin"
+//    inputs = "/home/me/aoc/inputs"
+//    format = "csv"
+//    offline = true
+//
+// any of which $AOC_SESSION, $AOC_USER, $AOC_INPUTS, $AOC_FORMAT and
+// $AOC_OFFLINE override.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+   // The adventofcode.com session cookie.
+   pub session: Option<String>,
+   // Whose inputs these are, since everyone's are different, which is
+   // $USER if nothing says otherwise.
+   pub user: String,
+   // Where downloaded inputs and the submissions log are kept, in a
+   // directory for each user.
+   pub inputs: PathBuf,
+   // What aoc run prints without --format.
+   pub format: Format,
+   // Never to reach adventofcode.com, as with --offline.
+   pub offline: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct File {
+   session: Option<String>,
+   user: Option<String>,
+   inputs: Option<String>,
+   format: Option<String>,
+   offline: Option<bool>,
+}
+
+impl Config {
+   // The config from the text of a config file, if there is one, with
+   // env's variables laid over it.
+   pub fn parse<E: Fn(&str) -> Option<String>>(file: Option<&str>, env: E) -> Result<Config, String> {
+      let file: File = match file {
+         Some(s) => toml::from_str(s).map_err(|e| e.message().to_string())?,
+         None => File::default(),
+      };
+      let setting = |var: &str, value: Option<String>| {
+         return env(var).filter(|v| !v.trim().is_empty()).or(value).map(|v| v.trim().to_string());
+      };
+      let format = match setting("AOC_FORMAT", file.format) {
+         Some(format) => format.parse::<Format>()?,
+         None => Format::Text,
+      };
+      let offline = match setting("AOC_OFFLINE", file.offline.map(|o| o.to_string())).as_ref().map(|o| &o[..]) {
+         None | Some("false") | Some("0") => false,
+         Some("true") | Some("1") => true,
+         Some(other) => return Err(format!("Expected offline to be true or false, not {:?}", other)),
+      };
+      let user = setting("AOC_USER", file.user).or_else(|| setting("USER", None)).unwrap_or("default".to_string());
+      let mut config = Config{
+         session: setting("AOC_SESSION", file.session),
+         user: String::new(),
+         inputs: setting("AOC_INPUTS", file.inputs).map(PathBuf::from).unwrap_or_else(|| workspace_root().join("inputs")),
+         format: format,
+         offline: offline,
+      };
+      config.set_user(&user)?;
+      return Ok(config);
+   }
+
+   // Switches to user's inputs, as --user does.  A user is a directory
+   // under inputs, so it has to be a plain name.
+   pub fn set_user(&mut self, user: &str) -> Result<(), String> {
+      if user.is_empty() || user.starts_with('.') || user.contains(['/', '\\']) {
+         return Err(format!("Invalid user {:?}, expected a name that can be a directory", user));
+      }
+      self.user = user.to_string();
+      return Ok(());
+   }
+
+   pub fn load() -> Result<Config, String> {
+      let path = config_file();
+      let file = match path {
+         Some(ref path) => read_file(path)?,
+         None => None,
+      };
+      let config = Config::parse(file.as_ref().map(|s| &s[..]), |var| env::var(var).ok());
+      return config.map_err(|e| match path {
+         Some(ref path) if file.is_some() => format!("{}: {}", path.display(), e),
+         _ => e,
+      });
+   }
+
+   // Where the user's inputs and submissions log are.
+   pub fn user_inputs(&self) -> PathBuf {
+      return self.inputs.join(&self.user);
+   }
+
+   // Where fetch keeps a day's input, like inputs/kevin/day07.txt.
+   pub fn input_file(&self, day: u32) -> PathBuf {
+      return self.user_inputs().join(format!("day{:02}.txt", day));
+   }
+}
+
+#[test]
+fn test_parse_config() {
+   let none = |_: &str| None;
+   let defaults = Config::parse(None, none).unwrap();
+   assert_eq!(defaults.session, None);
+   assert_eq!(defaults.user, "default");
+   assert!(defaults.input_file(7).ends_with("inputs/default/day07.txt"));
+   assert_eq!(defaults.format, Format::Text);
+
+   let file = "session = \"abc\"\ninputs = \"/tmp/aoc\"\nformat = \"json\"\n";
+   let config = Config::parse(Some(file), none).unwrap();
+   assert_eq!(config, Config{session: Some("abc".to_string()), user: "default".to_string(), inputs: PathBuf::from("/tmp/aoc"), format: Format::Json, offline: false});
+   let env = |var: &str| match var {
+      "AOC_FORMAT" => Some("csv".to_string()),
+      "USER" => Some("kevin".to_string()),
+      "AOC_OFFLINE" => Some("1".to_string()),
+      _ => None,
+   };
+   let mut config = Config::parse(Some(file), env).unwrap();
+   assert_eq!(config.format, Format::Csv);
+   assert!(config.offline);
+   assert_eq!(config.input_file(7), PathBuf::from("/tmp/aoc/kevin/day07.txt"));
+   config.set_user("ann").unwrap();
+   assert_eq!(config.input_file(7), PathBuf::from("/tmp/aoc/ann/day07.txt"));
+   assert!(config.set_user("../ann").is_err());
+
+   assert!(Config::parse(Some("format = \"xml\""), none).unwrap_err().contains("xml"));
+   assert!(Config::parse(Some("sesion = \"abc\""), none).is_err());
+
+   assert_eq!(read_file(&workspace_root().join("no such config.toml")), Ok(None));
+   assert!(read_file(&workspace_root()).is_err());
+}
@@ -0,0 +1,138 @@
+// Runs each day's solver against the worked examples from its puzzle,
+// which live in examples/ as dayNN_K.txt, the Kth example's input, and
+// dayNN_K.toml, its answers in the same form as answers.toml.  An example
+// only has the parts the puzzle gives answers for.  aoc verify runs them
+// all, and so do the tests, a test for each day.
+use check::Answers;
+use days::{Day, workspace_root};
+use std::fs;
+use std::path::{Path, PathBuf};
+use timing;
+
+// Every example input for day, in order.
+pub fn example_inputs(day: u32) -> Vec<PathBuf> {
+   let prefix = format!("day{:02}_", day);
+   let mut inputs: Vec<_> = fs::read_dir(workspace_root().join("examples")).into_iter().flatten()
+      .map(|entry| entry.unwrap().path())
+      .filter(|path| {
+         let name = path.file_name().unwrap().to_string_lossy();
+         name.starts_with(&prefix) && name.ends_with(".txt")
+      })
+      .collect();
+   inputs.sort();
+   return inputs;
+}
+
+// The answers an example input should give.
+pub fn example_answers(input: &Path) -> Answers {
+   return fs::read_to_string(input.with_extension("toml")).map_err(|e| e.to_string())
+      .and_then(|s| Answers::parse(&s))
+      .unwrap_or_else(|e| panic!("Can't read the answers for {}: {}", input.display(), e));
+}
+
+// How a day did on its examples: of those with an answer for each part,
+// how many it got, and what it got wrong.
+pub struct Verified {
+   pub day: u32,
+   pub passed: [usize; 2],
+   pub tried: [usize; 2],
+   pub failures: Vec<String>,
+}
+
+impl Verified {
+   pub fn ok(&self) -> bool {
+      return self.failures.is_empty();
+   }
+}
+
+pub fn verify(day: &Day) -> Verified {
+   let mut verified = Verified{day: day.day, passed: [0, 0], tried: [0, 0], failures: Vec::new()};
+   for input in example_inputs(day.day) {
+      let answers = example_answers(&input);
+      let puzzle = fs::read_to_string(&input).map_err(|e| e.to_string())
+         .and_then(|text| (day.parse)(&text).map_err(|e| e.to_string()));
+      for part in 1..3 {
+         if let Some(expected) = answers.expected(day.day, part) {
+            let got = puzzle.as_ref().map_err(|e| e.clone()).and_then(|p| p.part(part).map_err(|e| e.to_string()));
+            verified.tried[part as usize - 1] += 1;
+            if got.as_ref().ok().map(|a| &a[..]) == Some(expected) {
+               verified.passed[part as usize - 1] += 1;
+            } else {
+               verified.failures.push(format!("{} part {}: got {:?}, expected {}", input.display(), part, got, expected));
+            }
+         }
+      }
+   }
+   return verified;
+}
+
+// A row for each day with how many of each part's examples it passed,
+// and what went wrong after it.
+pub fn matrix(days: &[Verified]) -> String {
+   let mut rows = vec![vec!["day".to_string(), "part 1".to_string(), "part 2".to_string()]];
+   for verified in days {
+      let mut row = vec![verified.day.to_string()];
+      for part in 0..2 {
+         let (passed, tried) = (verified.passed[part], verified.tried[part]);
+         row.push(match tried {
+            0 => String::new(),
+            _ if passed == tried => format!("pass {}/{}", passed, tried),
+            _ => format!("FAIL {}/{}", passed, tried),
+         });
+      }
+      rows.push(row);
+   }
+   let mut text = timing::layout(&rows, false);
+   for failure in days.iter().flat_map(|v| &v.failures) {
+      text.push_str(&format!("\n{}", failure));
+   }
+   if days.iter().any(|v| !v.ok()) {
+      text.push('\n');
+   }
+   return text;
+}
+
+#[cfg(test)]
+fn check_examples(day: u32) {
+   let verified = verify(::days::find(day).unwrap());
+   assert!(verified.tried != [0, 0], "Day {} has no examples", day);
+   assert!(verified.ok(), "\n{}", verified.failures.join("\n"));
+}
+
+macro_rules! examples {
+   ($($name:ident: $day:expr,)*) => {
+      $(
+         #[test]
+         fn $name() {
+            check_examples($day);
+         }
+      )*
+   };
+}
+
+#[test]
+fn test_matrix() {
+   let days = [
+      Verified{day: 1, passed: [7, 5], tried: [7, 5], failures: Vec::new()},
+      Verified{day: 7, passed: [0, 0], tried: [1, 0], failures: vec!["day07_1.txt part 1: got Ok(\"1\"), expected 2".to_string()]},
+   ];
+   assert_eq!(matrix(&days), "\
+day    part 1    part 2
+1    pass 7/7  pass 5/5
+7    FAIL 0/1
+
+day07_1.txt part 1: got Ok(\"1\"), expected 2
+");
+}
+
+examples! {
+   test_day01_examples: 1,
+   test_day02_examples: 2,
+   test_day03_examples: 3,
+   test_day04_examples: 4,
+   test_day05_examples: 5,
+   test_day06_examples: 6,
+   test_day07_examples: 7,
+   test_day08_examples: 8,
+   test_day09_examples: 9,
+}
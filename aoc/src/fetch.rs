@@ -0,0 +1,143 @@
+use aoc2015_lib::AocError;
+use config::Config;
+use days::Day;
+use std::fs;
+use std::net::ToSocketAddrs;
+use std::path::PathBuf;
+use tokio;
+use ureq;
+
+// adventofcode.com asks automated tools to say who's behind them.
+const USER_AGENT: &str = "github.com/habnabit/adventofcode-2015 by kkress@gmail.com";
+
+pub fn input_url(day: u32) -> String {
+   return format!("https://adventofcode.com/2015/day/{}/input", day);
+}
+
+// How many inputs fetch --all downloads at once, few enough that it
+// isn't a flood of requests to adventofcode.com.
+pub const CONCURRENCY: usize = 4;
+
+// The session token to log in with, which everything that downloads
+// needs.
+fn session(config: &Config) -> Result<&str, AocError> {
+   let missing = "No session token: set AOC_SESSION, or session in the config file, to your adventofcode.com session cookie";
+   return config.session.as_ref().map(|s| &s[..]).ok_or(AocError::Network(missing.to_string()));
+}
+
+// Whether adventofcode.com is there to be reached, so that without it
+// aoc fails straight away with a reason instead of after a timeout: not
+// when config is offline, nor when its name can't be looked up, which is
+// what happens on a plane or in a sandbox.
+pub fn online(config: &Config) -> Result<(), AocError> {
+   if config.offline {
+      return Err(AocError::Network("aoc is offline, so it won't reach adventofcode.com (leave out --offline, or unset AOC_OFFLINE or offline in the config file)".to_string()));
+   }
+   if ("adventofcode.com", 443).to_socket_addrs().is_err() {
+      return Err(AocError::Network("adventofcode.com can't be reached from here, so aoc is working offline".to_string()));
+   }
+   return Ok(());
+}
+
+// request, logged in as whoever's session token it is.
+pub fn authorized(config: &Config, request: ureq::Request) -> Result<ureq::Request, AocError> {
+   online(config)?;
+   let token = session(config)?;
+   return Ok(request.set("Cookie", &format!("session={}", token)).set("User-Agent", USER_AGENT));
+}
+
+// The body of a response from url, or why there isn't one.
+pub fn response_text(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, AocError> {
+   return match response {
+      Ok(r) => r.into_string().map_err(|e| AocError::Network(format!("Can't read {}: {}", url, e))),
+      Err(ureq::Error::Status(code, r)) => {
+         let body = r.into_string().unwrap_or_default();
+         Err(AocError::Network(format!("{} said {}: {}", url, code, body.trim())))
+      }
+      Err(e) => Err(AocError::Network(format!("Can't fetch {}: {}", url, e))),
+   };
+}
+
+// Downloads day's input to the config's input_file, returning where it
+// went.
+pub fn fetch(config: &Config, day: u32) -> Result<PathBuf, AocError> {
+   let url = input_url(day);
+   let text = response_text(&url, authorized(config, ureq::get(&url))?.call())?;
+   let path = config.input_file(day);
+   fs::create_dir_all(path.parent().unwrap())
+      .and_then(|_| fs::write(&path, text))
+      .map_err(|e| AocError::io(&path.to_string_lossy(), e))?;
+   return Ok(path);
+}
+
+// Where a day's input went, or None if it was there already, or why it
+// couldn't be downloaded.
+pub type Fetched = Result<Option<PathBuf>, AocError>;
+
+// Downloads the input for each of days that hasn't been already, at most
+// concurrency at a time, giving what became of each in the order of
+// days.  One day failing doesn't stop the rest.  This crate's edition has
+// no async blocks, so each download is a blocking ureq call on a small
+// tokio runtime's blocking pool, whose size is what bounds them.
+pub fn fetch_all(config: &Config, days: &[u32], concurrency: usize) -> Result<Vec<(u32, Fetched)>, AocError> {
+   // Offline or without a token, every day would fail the same way.
+   online(config)?;
+   session(config)?;
+   let runtime = tokio::runtime::Builder::new_current_thread()
+      .max_blocking_threads(concurrency.max(1))
+      .build()
+      .map_err(|e| AocError::Network(format!("Can't start downloading: {}", e)))?;
+   let downloads: Vec<_> = days.iter().map(|&day| {
+      let config = config.clone();
+      return (day, runtime.spawn_blocking(move || {
+         let path = config.input_file(day);
+         if fs::metadata(&path).map(|m| m.len() > 0).unwrap_or(false) {
+            return Ok(None);
+         }
+         return fetch(&config, day).map(Some);
+      }));
+   }).collect();
+   return Ok(downloads.into_iter().map(|(day, download)| {
+      let fetched = runtime.block_on(download)
+         .unwrap_or_else(|e| Err(AocError::Network(format!("Downloading day {} failed: {}", day, e))));
+      return (day, fetched);
+   }).collect());
+}
+
+// The input to run day on when none was given: a fetched one, the one
+// the day's directory has always had, or failing both a fresh download,
+// if aoc isn't offline.  An empty file, like the input.txt that aoc
+// new-day leaves, doesn't count.
+pub fn find_input(config: &Config, day: &Day) -> Result<PathBuf, AocError> {
+   for path in [config.input_file(day.day), day.default_input()].iter() {
+      if fs::metadata(path).map(|m| m.is_file() && m.len() > 0).unwrap_or(false) {
+         return Ok(path.clone());
+      }
+   }
+   let missing = |e: AocError| AocError::Network(format!("No input for day {} at {}: {}", day.day, config.input_file(day.day).display(), e));
+   online(config).map_err(missing)?;
+   return fetch(config, day.day);
+}
+
+#[test]
+fn test_input_url() {
+   assert_eq!(input_url(7), "https://adventofcode.com/2015/day/7/input");
+}
+
+#[test]
+fn test_fetch_all() {
+   let config = Config::parse(None, |var| if var == "AOC_OFFLINE" { Some("1".to_string()) } else { None }).unwrap();
+   let error = fetch_all(&config, &[1, 2], CONCURRENCY).unwrap_err();
+   assert!(error.to_string().contains("aoc is offline"), "{}", error);
+   let error = session(&Config::parse(None, |_| None).unwrap()).unwrap_err();
+   assert!(error.to_string().contains("No session token"), "{}", error);
+}
+
+#[test]
+fn test_offline() {
+   // Without a session token too, which being offline doesn't need.
+   let config = Config::parse(None, |var| if var == "AOC_OFFLINE" { Some("true".to_string()) } else { None }).unwrap();
+   assert!(online(&config).unwrap_err().to_string().contains("aoc is offline"));
+   let error = fetch(&config, 7).unwrap_err();
+   assert!(error.to_string().contains("aoc is offline"), "{}", error);
+}
@@ -0,0 +1,38 @@
+// Snapshots of everything each day says, for the input in its directory
+// and for each of its examples, kept in snapshots/ by insta.  Any change
+// to what a day prints fails here, so a rework that's meant to change
+// nothing can be shown to, and one that is meant to can be accepted with
+// `cargo insta review`.  Examples are only run for the parts they have
+// answers for, since some parts would take ages on them.
+use days::DAYS;
+use examples::{example_answers, example_inputs};
+use insta;
+use results;
+use std::fs;
+
+// What day prints for its input and examples, each headed by which it is.
+fn output(day: &::days::Day) -> Option<String> {
+   let input = fs::read_to_string(day.default_input()).unwrap_or_default();
+   if input.is_empty() {
+      return None;
+   }
+   let mut text = format!("{}/input.txt\n{}", day.day, results::run(day, &[1, 2], Ok(input), false).text());
+   for path in example_inputs(day.day) {
+      let answers = example_answers(&path);
+      let parts: Vec<u32> = (1..3).filter(|&part| answers.expected(day.day, part).is_some()).collect();
+      let input = fs::read_to_string(&path).unwrap();
+      let name = path.file_name().unwrap().to_string_lossy().into_owned();
+      text.push_str(&format!("\nexamples/{}\n{}", name, results::run(day, &parts, Ok(input), false).text()));
+   }
+   return Some(text);
+}
+
+#[test]
+fn test_golden() {
+   // A day that's only just been started has no input to snapshot yet.
+   for day in DAYS {
+      if let Some(text) = output(day) {
+         insta::assert_snapshot!(format!("day{:02}", day.day), text);
+      }
+   }
+}
@@ -0,0 +1,159 @@
+// Every aoc run on a user's own input, kept in history.tsv beside the
+// submissions log, so that aoc history 7 can show how day 7's times have
+// gone from one commit to the next.
+use aoc2015_lib::AocError;
+use config::Config;
+use days::workspace_root;
+use results::Run;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use timing::{self, Timing};
+
+// One run of a day, from a line of the history file: when, the commit,
+// the day, how many microseconds parsing took, then each part's answer
+// and microseconds, separated by tabs.  A part that wasn't run is empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+   pub when: u64,
+   pub commit: String,
+   pub day: u32,
+   pub parse: Duration,
+   pub parts: [Option<(String, Duration)>; 2],
+}
+
+impl Entry {
+   // What got recorded of run, or None if any of it failed, since
+   // there'd be no times worth keeping.
+   pub fn new(when: u64, commit: &str, run: &Run) -> Option<Entry> {
+      if run.failed.is_some() || !run.ok() {
+         return None;
+      }
+      let mut entry = Entry{when: when, commit: commit.to_string(), day: run.day, parse: run.timing.parse, parts: [None, None]};
+      for &(part, ref answer) in &run.answers {
+         let took = run.timing.parts[part as usize - 1].unwrap_or_default();
+         entry.parts[part as usize - 1] = Some((answer.clone().ok()?, took));
+      }
+      return Some(entry);
+   }
+
+   pub fn line(&self) -> String {
+      let mut fields = vec![self.when.to_string(), self.commit.clone(), self.day.to_string(), self.parse.as_micros().to_string()];
+      for part in self.parts.iter() {
+         match *part {
+            Some((ref answer, took)) => fields.extend([answer.replace(['\t', '\n'], " "), took.as_micros().to_string()]),
+            None => fields.extend([String::new(), String::new()]),
+         }
+      }
+      return fields.join("\t") + "\n";
+   }
+
+   pub fn parse(line: &str) -> Option<Entry> {
+      let fields: Vec<&str> = line.split('\t').collect();
+      let micros = |s: &str| s.parse().ok().map(Duration::from_micros);
+      let part = |answer: &str, took: &str| if answer.is_empty() && took.is_empty() {
+         Some(None)
+      } else {
+         micros(took).map(|took| Some((answer.to_string(), took)))
+      };
+      return match fields[..] {
+         [when, commit, day, parse, answer1, took1, answer2, took2] => Some(Entry{
+            when: when.parse().ok()?,
+            commit: commit.to_string(),
+            day: day.parse().ok()?,
+            parse: micros(parse)?,
+            parts: [part(answer1, took1)?, part(answer2, took2)?],
+         }),
+         _ => None,
+      };
+   }
+
+   fn timing(&self) -> Timing {
+      return Timing{day: self.day, parse: self.parse, parts: [self.parts[0].as_ref().map(|p| p.1), self.parts[1].as_ref().map(|p| p.1)], peak: None};
+   }
+}
+
+pub fn history_file(config: &Config) -> PathBuf {
+   return config.user_inputs().join("history.tsv");
+}
+
+// The commit the workspace is at, like 35f6dcc, with -dirty after it if
+// it's been changed since, or unknown if git can't say.
+pub fn commit() -> String {
+   let output = Command::new("git").arg("-C").arg(workspace_root()).args(["describe", "--always", "--dirty"]).output();
+   return match output {
+      Ok(ref o) if o.status.success() => String::from_utf8_lossy(&o.stdout).trim().to_string(),
+      _ => "unknown".to_string(),
+   };
+}
+
+// Adds the runs that worked to the history file.
+pub fn record(config: &Config, runs: &[Run]) -> Result<(), AocError> {
+   let path = history_file(config);
+   let when = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+   let commit = commit();
+   let lines: String = runs.iter().filter_map(|run| Entry::new(when, &commit, run)).map(|e| e.line()).collect();
+   if lines.is_empty() {
+      return Ok(());
+   }
+   return fs::create_dir_all(path.parent().unwrap())
+      .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+      .and_then(|mut f| f.write_all(lines.as_bytes()))
+      .map_err(|e| AocError::io(&path.to_string_lossy(), e));
+}
+
+// Every run of day in the history file, oldest first.
+pub fn load(config: &Config, day: u32) -> Result<Vec<Entry>, AocError> {
+   let path = history_file(config);
+   let text = match fs::read_to_string(&path) {
+      Ok(text) => text,
+      Err(ref e) if e.kind() == ::std::io::ErrorKind::NotFound => String::new(),
+      Err(e) => return Err(AocError::io(&path.to_string_lossy(), e)),
+   };
+   return Ok(text.lines().filter_map(Entry::parse).filter(|e| e.day == day).collect());
+}
+
+// A Unix time as a UTC date and time, like 2015-12-07 05:00.
+pub fn date(when: u64) -> String {
+   // Howard Hinnant's days_from_civil, the other way round.
+   let days = (when / 86400) as i64 + 719468;
+   let era = days.div_euclid(146097);
+   let day_of_era = days - era * 146097;
+   let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+   let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+   let mp = (5 * day_of_year + 2) / 153;
+   let day = day_of_year - (153 * mp + 2) / 5 + 1;
+   let month = if mp < 10 { mp + 3 } else { mp - 9 };
+   let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+   return format!("{}-{:02}-{:02} {:02}:{:02}", year, month, day, when % 86400 / 3600, when % 3600 / 60);
+}
+
+// A table of entries, a row for each run with its answers and how long it
+// took.
+pub fn table(entries: &[Entry]) -> String {
+   let mut rows = vec![["when", "commit", "answers", "parse", "part 1", "part 2", "total"].iter().map(|s| s.to_string()).collect::<Vec<_>>()];
+   let cell = |d: Option<Duration>| d.map(timing::ms).unwrap_or_default();
+   for entry in entries {
+      let t = entry.timing();
+      let answers: Vec<&str> = entry.parts.iter().filter_map(|p| p.as_ref().map(|p| &p.0[..])).collect();
+      rows.push(vec![date(entry.when), entry.commit.clone(), answers.join(", "), timing::ms(t.parse), cell(t.parts[0]), cell(t.parts[1]), timing::ms(t.total())]);
+   }
+   return timing::layout(&rows, false);
+}
+
+#[test]
+fn test_entry() {
+   let ms = Duration::from_millis;
+   let entry = Entry{when: 1449464400, commit: "35f6dcc".to_string(), day: 7, parse: ms(2), parts: [None, Some(("14134".to_string(), ms(250)))]};
+   assert_eq!(entry.line(), "1449464400\t35f6dcc\t7\t2000\t\t\t14134\t250000\n");
+   assert_eq!(Entry::parse(entry.line().trim_end()), Some(entry.clone()));
+   assert_eq!(Entry::parse("1449464400\t35f6dcc\t7\t2000"), None);
+   assert_eq!(date(entry.when), "2015-12-07 05:00");
+   assert_eq!(table(&[entry]), "\
+when               commit  answers    parse  part 1     part 2      total
+2015-12-07 05:00  35f6dcc    14134  2.000ms          250.000ms  252.000ms
+");
+}
@@ -0,0 +1,333 @@
+extern crate aoc2015_lib;
+extern crate aoc2015_days as days;
+extern crate getopts;
+extern crate indicatif;
+#[cfg(test)]
+extern crate insta;
+extern crate pprof;
+extern crate ratatui;
+extern crate rayon;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate tokio;
+extern crate toml;
+#[macro_use]
+extern crate tracing;
+extern crate ureq;
+
+mod check;
+mod compare;
+mod config;
+mod examples;
+mod fetch;
+#[cfg(test)]
+mod golden;
+mod history;
+mod memory;
+mod meta;
+mod profile;
+mod progress;
+mod report;
+mod results;
+mod scaffold;
+mod submit;
+mod timing;
+mod tui;
+
+use aoc2015_lib::AocError;
+use config::Config;
+use profile::Profile;
+use getopts::{Matches, Options};
+use rayon::prelude::*;
+use std::env;
+use std::process;
+use results::{Format, Run};
+use timing::Timing;
+
+// Says why on stderr, and gives up.
+fn fail(why: &str, code: i32) -> ! {
+   eprintln!("{}", why);
+   process::exit(code);
+}
+
+// The puzzle input for day: --input's, or else whatever find_input finds.
+fn read_input(config: &Config, day: &days::Day, matches: &Matches) -> Result<String, AocError> {
+   let path = match matches.opt_str("input") {
+      Some(path) => path,
+      None => fetch::find_input(config, day)?.to_string_lossy().into_owned(),
+   };
+   return aoc2015_lib::read(&path).map_err(|e| AocError::io(&path, e));
+}
+
+// The parts --part asks for, or both.
+fn parse_parts(matches: &Matches) -> Vec<u32> {
+   return match matches.opt_str("part").map(|p| aoc2015_lib::parse_part(&p)) {
+      None => vec![1, 2],
+      Some(Ok(part)) => vec![part],
+      Some(Err(e)) => fail(&e, 2),
+   };
+}
+
+// Runs days, printing their answers in --format, or the config's format
+// if not given, and their timings too if --time asks, and adds them to
+// the history.  Returns false if any of them failed.
+fn run_days(config: &Config, days: &[&days::Day], matches: &Matches) -> bool {
+   let format = match matches.opt_str("format").map(|f| f.parse::<Format>()) {
+      None if matches.opt_present("time") => Format::Text,
+      None => config.format,
+      Some(Ok(format)) => format,
+      Some(Err(e)) => fail(&e, 2),
+   };
+   if format != Format::Text && matches.opt_present("time") {
+      fail("--time is for the text format; json and csv have the timings already", 2);
+   }
+   let parts = parse_parts(matches);
+   let parallel = matches.opt_present("parallel");
+   let profile = match matches.opt_str("profile").map(|p| p.parse::<Profile>()) {
+      None => None,
+      Some(Ok(_)) if parallel => fail("--profile can't tell the days apart with --parallel", 2),
+      Some(Ok(profile)) => Some(profile),
+      Some(Err(e)) => fail(&e, 2),
+   };
+   let (runs, took) = timing::time(|| {
+      if parallel {
+         // Everything's printed once they've all finished, in order.  The
+         // days' memory all gets counted together, so none of it's theirs.
+         return days.par_iter().map(|&day| {
+            let mut run = results::run(day, &parts, read_input(config, day, matches), false);
+            run.timing.peak = None;
+            return run;
+         }).collect::<Vec<Run>>();
+      }
+      let mut runs = Vec::new();
+      for &day in days {
+         let input = read_input(config, day, matches);
+         let (run, written) = match profile {
+            None => (results::run(day, &parts, input, true), None),
+            Some(Profile::Flamegraph) => {
+               let (run, written) = profile::flamegraph(day.day, || results::run(day, &parts, input, true));
+               (run, Some(written))
+            }
+         };
+         if format == Format::Text {
+            print!("{}", run.text());
+         }
+         match written {
+            Some(Ok(path)) => eprintln!("Wrote {}", path.display()),
+            Some(Err(e)) => eprintln!("{}", e),
+            None => (),
+         }
+         runs.push(run);
+      }
+      return runs;
+   });
+   if parallel && format == Format::Text {
+      for run in &runs {
+         print!("{}", run.text());
+      }
+   }
+   // Only the user's own input, run on its own, gives times that can be
+   // set beside the last ones.
+   if !matches.opt_present("input") && !parallel && profile.is_none() {
+      if let Err(e) = history::record(config, &runs) {
+         eprintln!("Can't add to the history: {}", e);
+      }
+   }
+   let ok = runs.iter().all(Run::ok);
+   match format {
+      Format::Text => {
+         let timings: Vec<Timing> = runs.into_iter().map(|r| r.timing).collect();
+         if matches.opt_present("time") {
+            print!("\n{}", timing::table(&timings));
+         } else if parallel {
+            let solving = timing::ms(timings.iter().map(Timing::total).sum());
+            let threads = rayon::current_num_threads();
+            println!("\nTook {} on {} thread{}, for {} of solving", timing::ms(took), threads, if threads == 1 { "" } else { "s" }, solving);
+         } else if timings.len() > 1 {
+            println!("\nTook {}", timing::ms(timings.iter().map(Timing::total).sum()));
+         }
+      }
+      Format::Json => print!("{}", results::json(&results::records(&runs))),
+      Format::Csv => print!("{}", results::csv(&results::records(&runs))),
+   }
+   return ok;
+}
+
+fn main() {
+   let args: Vec<String> = env::args().collect();
+   let mut opts = Options::new();
+   opts.optopt("", "part", "run only part 1 or 2", "1|2");
+   opts.optopt("", "input", "the puzzle input, or - for standard input, instead of the downloaded one or the one in the day's directory", "PATH");
+   opts.optopt("", "user", "use this user's downloaded inputs and submissions, instead of $USER's", "NAME");
+   opts.optopt("", "inputs", "run the day on each of these inputs and compare their answers and timings", "PATH,PATH,...");
+   opts.optflag("", "all", "run every day solved so far, or fetch every day's input");
+   opts.optflag("", "parallel", "with --all, run the days at the same time on every core");
+   opts.optopt("", "profile", "write a flamegraph of each day run to dayNN-flamegraph.svg", "flamegraph");
+   opts.optflag("", "time", "print how long parsing and each part took");
+   opts.optopt("", "format", "print answers as text, the default, or as json or csv records with their timings; a report can be markdown or html", "FORMAT");
+   opts.optflag("", "offline", "never reach adventofcode.com, so fetch and submit fail and run only uses inputs that are already here");
+   opts.optflag("h", "help", "print this help");
+   let usage = format!("Usage: {0} run DAY [--user NAME] [--part 1|2] [--input PATH] [--time | --format FORMAT] [--profile flamegraph]\n       {0} run DAY --inputs PATH,PATH,... [--part 1|2]\n       {0} run --all [--user NAME] [--parallel] [--part 1|2] [--time | --format FORMAT]\n       {0} fetch DAY|--all [--user NAME]\n       {0} history DAY [--user NAME]\n       {0} submit DAY PART [--user NAME] [--input PATH]\n       {0} check\n       {0} verify\n       {0} report [--format markdown|html]\n       {0} tui\n       {0} new-day DAY", args[0]);
+   let matches = match opts.parse(&args[1..]) {
+      Ok(m) => m,
+      Err(e) => fail(&e.to_string(), 2),
+   };
+   aoc2015_lib::init_tracing("warn");
+   if matches.opt_present("h") {
+      print!("{}", opts.usage(&usage));
+      return;
+   }
+
+   let mut config = Config::load().unwrap_or_else(|e| fail(&e, 2));
+   if let Some(user) = matches.opt_str("user") {
+      config.set_user(&user).unwrap_or_else(|e| fail(&e, 2));
+   }
+   if matches.opt_present("offline") {
+      config.offline = true;
+   }
+   let free: Vec<&str> = matches.free.iter().map(|s| &s[..]).collect();
+   if matches.opt_present("profile") && free.first() != Some(&"run") {
+      fail("--profile is only for run", 2);
+   }
+   if free[..] == ["check"] {
+      let answers = check::Answers::load().unwrap_or_else(|e| fail(&e, 1));
+      if !check::check(&config, &answers) {
+         process::exit(1);
+      }
+      return;
+   }
+   if free[..] == ["verify"] {
+      let verified: Vec<examples::Verified> = days::DAYS.iter().map(examples::verify).collect();
+      print!("{}", examples::matrix(&verified));
+      if !verified.iter().all(examples::Verified::ok) {
+         process::exit(1);
+      }
+      return;
+   }
+   if free[..] == ["tui"] {
+      let answers = check::Answers::load().unwrap_or_else(|e| fail(&e, 1));
+      if let Err(e) = tui::tui(&config, &answers) {
+         fail(&e.to_string(), 1);
+      }
+      return;
+   }
+   if free[..] == ["report"] {
+      if matches.opt_present("input") {
+         fail("--input is for running one day, not a report on them all", 2);
+      }
+      let format = match matches.opt_str("format").map(|f| f.parse::<report::Format>()) {
+         None => report::Format::Markdown,
+         Some(Ok(format)) => format,
+         Some(Err(e)) => fail(&e, 2),
+      };
+      let runs: Vec<Run> = days::DAYS.iter().map(|day| results::run(day, &[1, 2], read_input(&config, day, &matches), true)).collect();
+      print!("{}", report::report(format, &runs));
+      if !runs.iter().all(Run::ok) {
+         process::exit(1);
+      }
+      return;
+   }
+   if free[..] == ["run"] && matches.opt_present("all") {
+      if matches.opt_present("input") {
+         fail("--input is for running one day, not --all", 2);
+      }
+      let all: Vec<&days::Day> = days::DAYS.iter().collect();
+      if !run_days(&config, &all, &matches) {
+         process::exit(1);
+      }
+      return;
+   }
+   if free[..] == ["fetch"] && matches.opt_present("all") {
+      let all: Vec<u32> = (1..26).collect();
+      let fetched = fetch::fetch_all(&config, &all, fetch::CONCURRENCY).unwrap_or_else(|e| fail(&e.to_string(), 1));
+      let mut failed = Vec::new();
+      for (day, result) in fetched {
+         match result {
+            Ok(Some(path)) => println!("Saved {}", path.display()),
+            Ok(None) => {}
+            Err(e) => failed.push(format!("Day {}: {}", day, e)),
+         }
+      }
+      if !failed.is_empty() {
+         fail(&format!("{} of {} inputs couldn't be downloaded:\n{}", failed.len(), all.len(), failed.join("\n")), 1);
+      }
+      return;
+   }
+   if matches.opt_present("parallel") {
+      fail("--parallel is only for run --all", 2);
+   }
+   let day = match free.get(1).map(|d| d.parse::<u32>()) {
+      Some(Ok(day)) => day,
+      _ => fail(&usage, 2),
+   };
+   if free[..] == ["new-day", free[1]] {
+      match scaffold::new_day(day) {
+         Ok(files) => {
+            for file in files {
+               println!("Wrote {}", file.display());
+            }
+         }
+         Err(e) => fail(&e, 1),
+      }
+      return;
+   }
+   if free[..] == ["history", free[1]] {
+      match history::load(&config, day) {
+         Ok(ref entries) if entries.is_empty() => println!("Day {} hasn't been run yet, or not on {}'s input", day, config.user),
+         Ok(entries) => print!("{}", history::table(&entries)),
+         Err(e) => fail(&e.to_string(), 1),
+      }
+      return;
+   }
+   if free[..] == ["fetch", free[1]] {
+      match fetch::fetch(&config, day) {
+         Ok(path) => println!("Saved {}", path.display()),
+         Err(e) => fail(&e.to_string(), 1),
+      }
+      return;
+   }
+
+   let day = match days::find(day) {
+      Some(day) => day,
+      None if meta::find(day).is_some() => fail(&format!("Day {} isn't in this build, which needs the day{:02} feature", day, day), 2),
+      None => fail(&format!("Day {} isn't solved yet", day), 2),
+   };
+   match free[..] {
+      ["run", _] if matches.opt_present("inputs") => {
+         if ["input", "format", "time", "profile"].iter().any(|&o| matches.opt_present(o)) {
+            fail("--inputs prints a table of its own, and can't have --input, --format, --time or --profile", 2);
+         }
+         let inputs = matches.opt_str("inputs").unwrap();
+         let inputs: Vec<&str> = inputs.split(',').filter(|i| !i.is_empty()).collect();
+         let parts = parse_parts(&matches);
+         let runs = compare::compare(day, &parts, &inputs);
+         print!("{}", compare::table(&inputs, &parts, &runs));
+         if !runs.iter().all(Run::ok) {
+            process::exit(1);
+         }
+      }
+      ["run", _] => {
+         if !run_days(&config, &[day], &matches) {
+            process::exit(1);
+         }
+      }
+      ["submit", _, part] => {
+         let part = aoc2015_lib::parse_part(part).unwrap_or_else(|e| fail(&e, 2));
+         let input = read_input(&config, day, &matches).unwrap_or_else(|e| fail(&e.to_string(), 1));
+         let answer = day.solve(part, &input).unwrap_or_else(|e| fail(&format!("Day {} part {} failed: {}", day.day, part, e), 1));
+         match submit::submit(&config, day.day, part, &answer) {
+            Ok(outcome) => {
+               println!("Day {} part {}: {} is {}", day.day, part, answer, outcome);
+               let right = submit::Outcome::AlreadyAnswered(submit::Outcome::Right.to_string());
+               if outcome != submit::Outcome::Right && outcome != right {
+                  process::exit(1);
+               }
+            }
+            Err(e) => fail(&e.to_string(), 1),
+         }
+      }
+      _ => fail(&usage, 2),
+   }
+}
@@ -0,0 +1,74 @@
+// How much memory a day takes, from an allocator that counts what's
+// allocated, which is only put in with the memory feature since it's one
+// more atomic for every allocation.  It's everything allocated, so it
+// can't tell days apart when they run at the same time.
+#[cfg(feature = "memory")]
+use std::alloc::{GlobalAlloc, Layout, System};
+#[cfg(feature = "memory")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "memory")]
+struct Counting;
+
+#[cfg(feature = "memory")]
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "memory")]
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "memory")]
+unsafe impl GlobalAlloc for Counting {
+   unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+      let ptr = System.alloc(layout);
+      if !ptr.is_null() {
+         let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+         PEAK.fetch_max(allocated, Ordering::Relaxed);
+      }
+      return ptr;
+   }
+
+   unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+      System.dealloc(ptr, layout);
+      ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+   }
+}
+
+#[cfg(feature = "memory")]
+#[global_allocator]
+static COUNTING: Counting = Counting;
+
+// Runs f, and says the most it had allocated at once on top of what
+// there was already, if that's being counted.
+#[cfg(feature = "memory")]
+pub fn peak<T, F: FnOnce() -> T>(f: F) -> (T, Option<usize>) {
+   let before = ALLOCATED.load(Ordering::Relaxed);
+   PEAK.store(before, Ordering::Relaxed);
+   let result = f();
+   return (result, Some(PEAK.load(Ordering::Relaxed).saturating_sub(before)));
+}
+
+#[cfg(not(feature = "memory"))]
+pub fn peak<T, F: FnOnce() -> T>(f: F) -> (T, Option<usize>) {
+   return (f(), None);
+}
+
+// A number of bytes the way a person would say it, like 4.8MB.
+pub fn size(bytes: usize) -> String {
+   let units = ["B", "KB", "MB", "GB"];
+   let mut size = bytes as f64;
+   let mut unit = 0;
+   while size >= 1024.0 && unit < units.len() - 1 {
+      size /= 1024.0;
+      unit += 1;
+   }
+   return if unit == 0 { format!("{}B", bytes) } else { format!("{:.1}{}", size, units[unit]) };
+}
+
+#[test]
+fn test_peak() {
+   // How much is hard to say while the other tests are running too.
+   let (total, peak) = peak(|| vec![[0u32; 1000]; 1000].len());
+   assert_eq!(total, 1000);
+   assert_eq!(peak.is_some(), cfg!(feature = "memory"));
+   assert_eq!(size(512), "512B");
+   assert_eq!(size(5 << 20), "5.0MB");
+}
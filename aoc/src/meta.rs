@@ -0,0 +1,39 @@
+// What there is to say about each day besides its answers, for aoc report.
+pub struct Meta {
+   pub day: u32,
+   pub title: &'static str,
+   pub notes: &'static str,
+}
+
+pub const META: &[Meta] = &[
+   Meta{day: 1, title: "Not Quite Lisp", notes: "\
+A running count of the parentheses, watching for the first time it goes below zero."},
+   Meta{day: 2, title: "I Was Told There Would Be No Math", notes: "\
+Each present works out its own paper and ribbon from its two smallest sides."},
+   Meta{day: 3, title: "Perfectly Spherical Houses in a Vacuum", notes: "\
+A map of the presents each house gets, with the moves dealt out in turn when there's more than one Santa."},
+   Meta{day: 4, title: "The Ideal Stocking Stuffer", notes: "\
+Brute force, hashing every number in turn until the MD5 starts with enough zeros.  \
+It's by far the slowest day, nearly all of it in part 2."},
+   Meta{day: 5, title: "Doesn't He Have Intern-Elves For This?", notes: "\
+A pass over each string per rule, for both sets of rules."},
+   Meta{day: 6, title: "Probably a Fire Hazard", notes: "\
+Two million-light grids updated for every instruction, one on or off and one with brightnesses."},
+   Meta{day: 7, title: "Some Assembly Required", notes: "\
+The netlist becomes a circuit of gates that resolves a wire by resolving what feeds it, caching \
+along the way.  It has grown into a simulator of its own, with event-driven settling, \
+modules and more; see `7/`."},
+   Meta{day: 8, title: "Matchsticks", notes: "\
+Counts the characters each string would have unescaped, and escaped again, without building either."},
+   Meta{day: 9, title: "All in a Single Night", notes: "\
+Every ordering of the places is tried, keeping the shortest and the longest."},
+];
+
+pub fn find(day: u32) -> Option<&'static Meta> {
+   return META.iter().find(|m| m.day == day);
+}
+
+#[test]
+fn test_every_day_has_meta() {
+   assert!(::days::DAYS.iter().all(|d| find(d.day).is_some()));
+}
@@ -0,0 +1,56 @@
+use pprof::ProfilerGuardBuilder;
+use std::fs::File;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+// Samples a second, a prime so as not to fall into step with anything
+// that happens at a round rate.
+const FREQUENCY: i32 = 997;
+
+// What --profile writes for each day run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Profile {
+   Flamegraph,
+}
+
+impl FromStr for Profile {
+   type Err = String;
+
+   fn from_str(s: &str) -> Result<Profile, String> {
+      return match s {
+         "flamegraph" => Ok(Profile::Flamegraph),
+         _ => Err(format!("Invalid profile {:?}, expected flamegraph", s)),
+      };
+   }
+}
+
+// Where day's flamegraph goes: day07-flamegraph.svg, in the current
+// directory.
+pub fn flamegraph_file(day: u32) -> PathBuf {
+   return PathBuf::from(format!("day{:02}-flamegraph.svg", day));
+}
+
+// Runs f with the sampling profiler on, and writes what it saw as day's
+// flamegraph.  f's result comes back whether or not that worked, along
+// with where the flamegraph went or why it didn't.
+pub fn flamegraph<T, F: FnOnce() -> T>(day: u32, f: F) -> (T, Result<PathBuf, String>) {
+   let guard = ProfilerGuardBuilder::default().frequency(FREQUENCY)
+      .blocklist(&["libc", "libgcc", "pthread", "vdso"]).build();
+   let result = f();
+   let path = flamegraph_file(day);
+   let written = guard
+      .and_then(|guard| guard.report().build())
+      .map_err(|e| format!("Can't profile day {}: {}", day, e))
+      .and_then(|report| {
+         let file = File::create(&path).map_err(|e| format!("Can't write {}: {}", path.display(), e))?;
+         return report.flamegraph(file).map_err(|e| format!("Can't write {}: {}", path.display(), e));
+      });
+   return (result, written.map(|_| path));
+}
+
+#[test]
+fn test_flamegraph() {
+   assert_eq!("flamegraph".parse(), Ok(Profile::Flamegraph));
+   assert!("perf".parse::<Profile>().is_err());
+   assert_eq!(flamegraph_file(7), PathBuf::from("day07-flamegraph.svg"));
+}
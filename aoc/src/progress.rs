@@ -0,0 +1,38 @@
+use aoc2015_lib::Progress;
+use indicatif::{ProgressBar, ProgressStyle};
+
+// A part's progress as a bar on stderr, which only shows up if the part
+// reports any and stderr is a terminal.  It's cleared away when dropped,
+// leaving just the answer.
+pub struct Bar(ProgressBar);
+
+impl Bar {
+   pub fn new(day: u32, part: u32) -> Bar {
+      let bar = ProgressBar::new_spinner().with_prefix(format!("Day {} part {}", day, part));
+      bar.set_style(ProgressStyle::with_template("{spinner} {prefix}: {human_pos} [{elapsed}, {per_sec}]").unwrap());
+      return Bar(bar);
+   }
+
+   // A bar that never shows, for when several parts are running at once
+   // and their bars would only garble each other.
+   pub fn hidden() -> Bar {
+      return Bar(ProgressBar::hidden());
+   }
+}
+
+impl Progress for Bar {
+   fn set_length(&mut self, steps: u64) {
+      self.0.set_length(steps);
+      self.0.set_style(ProgressStyle::with_template("{prefix}: {wide_bar} {human_pos}/{human_len} [{elapsed}, {eta} left]").unwrap());
+   }
+
+   fn set_position(&mut self, step: u64) {
+      self.0.set_position(step);
+   }
+}
+
+impl Drop for Bar {
+   fn drop(&mut self) {
+      self.0.finish_and_clear();
+   }
+}
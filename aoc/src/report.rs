@@ -0,0 +1,161 @@
+use meta;
+use results::Run;
+use std::str::FromStr;
+use timing;
+
+// A page with {{name}} where each value goes.  Values are put in as they
+// are: escaping them is up to whoever fills the template in, since some
+// are fragments rendered from another template.
+pub struct Template(pub &'static str);
+
+impl Template {
+   pub fn render(&self, values: &[(&str, &str)]) -> String {
+      let mut out = String::new();
+      let mut rest = self.0;
+      while let Some(start) = rest.find("{{") {
+         out.push_str(&rest[..start]);
+         let end = start + rest[start..].find("}}").expect("unclosed {{ in a template");
+         let name = &rest[start + 2..end];
+         match values.iter().find(|&&(n, _)| n == name) {
+            Some(&(_, value)) => out.push_str(value),
+            None => panic!("Nothing for {{{{{}}}}} in a template", name),
+         }
+         rest = &rest[end + 2..];
+      }
+      out.push_str(rest);
+      return out;
+   }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+   Markdown,
+   Html,
+}
+
+impl FromStr for Format {
+   type Err = String;
+
+   fn from_str(s: &str) -> Result<Format, String> {
+      return match s {
+         "markdown" | "md" => Ok(Format::Markdown),
+         "html" => Ok(Format::Html),
+         _ => Err(format!("Invalid report format {:?}, expected markdown or html", s)),
+      };
+   }
+}
+
+// The report's page, a row of its table for each day, and a section for
+// each day's notes.
+struct Templates {
+   page: Template,
+   row: Template,
+   notes: Template,
+}
+
+const MARKDOWN: Templates = Templates{
+   page: Template("\
+# Advent of Code 2015
+
+| Day | Puzzle | Part 1 | Part 2 | Time |
+|----:|--------|--------|--------|-----:|
+{{rows}}
+It all took {{total}}.
+
+{{notes}}"),
+   row: Template("| {{day}} | [{{title}}]({{url}}) | {{part1}} | {{part2}} | {{time}} |\n"),
+   notes: Template("## Day {{day}}: {{title}}\n\n{{notes}}\n\n"),
+};
+
+const HTML: Templates = Templates{
+   page: Template("\
+<!DOCTYPE html>
+<html>
+<head><meta charset=\"utf-8\"><title>Advent of Code 2015</title></head>
+<body>
+<h1>Advent of Code 2015</h1>
+<table>
+<tr><th>Day</th><th>Puzzle</th><th>Part 1</th><th>Part 2</th><th>Time</th></tr>
+{{rows}}</table>
+<p>It all took {{total}}.</p>
+{{notes}}</body>
+</html>
+"),
+   row: Template("<tr><td>{{day}}</td><td><a href=\"{{url}}\">{{title}}</a></td><td>{{part1}}</td><td>{{part2}}</td><td>{{time}}</td></tr>\n"),
+   notes: Template("<h2>Day {{day}}: {{title}}</h2>\n<p>{{notes}}</p>\n"),
+};
+
+impl Format {
+   fn templates(&self) -> &'static Templates {
+      return match *self {
+         Format::Markdown => &MARKDOWN,
+         Format::Html => &HTML,
+      };
+   }
+
+   // s as text that's safe to put in this format's templates.
+   fn escape(&self, s: &str) -> String {
+      return match *self {
+         Format::Markdown => s.replace('|', "\\|"),
+         Format::Html => s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;"),
+      };
+   }
+}
+
+// A write-up of runs: every day's answers and timings, then its notes.
+pub fn report(format: Format, runs: &[Run]) -> String {
+   let templates = format.templates();
+   let mut rows = String::new();
+   let mut notes = String::new();
+   for run in runs {
+      let meta = meta::find(run.day);
+      let title = format.escape(meta.map(|m| m.title).unwrap_or("?"));
+      let answer = |part: u32| match run.answers.iter().find(|&&(p, _)| p == part) {
+         Some(&(_, Ok(ref answer))) => format.escape(answer),
+         Some(&(_, Err(ref e))) => format.escape(&format!("failed: {}", e)),
+         None => String::new(),
+      };
+      let day = run.day.to_string();
+      let url = format!("https://adventofcode.com/2015/day/{}", run.day);
+      rows.push_str(&templates.row.render(&[
+         ("day", &day),
+         ("title", &title),
+         ("url", &url),
+         ("part1", &answer(1)),
+         ("part2", &answer(2)),
+         ("time", &timing::ms(run.timing.total())),
+      ]));
+      if let Some(meta) = meta {
+         notes.push_str(&templates.notes.render(&[("day", &day), ("title", &title), ("notes", &format.escape(meta.notes))]));
+      }
+   }
+   let total = timing::ms(runs.iter().map(|r| r.timing.total()).sum());
+   return templates.page.render(&[("rows", &rows), ("total", &total), ("notes", &notes)]);
+}
+
+#[test]
+fn test_template() {
+   let t = Template("{{a}} and {{b}}, {{a}}");
+   assert_eq!(t.render(&[("a", "x"), ("b", "{{a}}")]), "x and {{a}}, x");
+   assert_eq!(Template("none").render(&[]), "none");
+}
+
+#[test]
+fn test_report() {
+   use std::time::Duration;
+   use timing::Timing;
+   let ms = Duration::from_millis;
+   let runs = [Run{
+      day: 8,
+      timing: Timing{day: 8, parse: ms(1), parts: [Some(ms(2)), None], peak: None},
+      failed: None,
+      answers: vec![(1, Ok("12".to_string())), (2, Err("a < b".to_string()))],
+   }];
+   let markdown = report(Format::Markdown, &runs);
+   assert!(markdown.contains("| 8 | [Matchsticks](https://adventofcode.com/2015/day/8) | 12 | failed: a < b | 3.000ms |\n"));
+   assert!(markdown.contains("It all took 3.000ms.\n\n## Day 8: Matchsticks\n\nCounts"));
+   let html = report(Format::Html, &runs);
+   assert!(html.contains("<td>12</td><td>failed: a &lt; b</td>"));
+   assert!(!html.contains("{{"));
+   assert_eq!("md".parse(), Ok(Format::Markdown));
+}
@@ -0,0 +1,187 @@
+use aoc2015_lib::AocError;
+use days::Day;
+use memory;
+use progress::Bar;
+use serde_json;
+use std::str::FromStr;
+use std::time::Duration;
+use timing::{self, Timing};
+
+// What running one day came to.
+pub struct Run {
+   pub day: u32,
+   pub timing: Timing,
+   // Why none of the day could be run, if it couldn't.
+   pub failed: Option<String>,
+   pub answers: Vec<(u32, Result<String, String>)>,
+}
+
+impl Run {
+   pub fn ok(&self) -> bool {
+      return self.answers.iter().all(|&(_, ref a)| a.is_ok());
+   }
+
+   // The answers as lines like `Day 7 part 1: 46065`.
+   pub fn text(&self) -> String {
+      if let Some(ref e) = self.failed {
+         return format!("Day {} failed: {}\n", self.day, e);
+      }
+      let mut text = String::new();
+      for &(part, ref answer) in &self.answers {
+         text.push_str(&match *answer {
+            Ok(ref answer) => format!("Day {} part {}: {}\n", self.day, part, answer),
+            Err(ref e) => format!("Day {} part {} failed: {}\n", self.day, part, e),
+         });
+      }
+      return text;
+   }
+}
+
+// Runs day's parts on its input, timing the parsing and each part, and
+// showing their progress if asked, and how much memory it took if that's
+// being counted.
+pub fn run(day: &Day, parts: &[u32], input: Result<String, AocError>, progress: bool) -> Run {
+   let (mut run, peak) = memory::peak(|| run_parts(day, parts, input, progress));
+   run.timing.peak = peak;
+   return run;
+}
+
+fn run_parts(day: &Day, parts: &[u32], input: Result<String, AocError>, progress: bool) -> Run {
+   let mut run = Run{day: day.day, timing: Timing{day: day.day, parse: Duration::from_secs(0), parts: [None, None], peak: None}, failed: None, answers: Vec::new()};
+   let _span = info_span!("day", day = day.day).entered();
+   let (puzzle, parse) = timing::time(|| input.and_then(|input| (day.parse)(&input)));
+   run.timing.parse = parse;
+   let puzzle = match puzzle {
+      Ok(puzzle) => puzzle,
+      Err(e) => {
+         let e = e.to_string();
+         run.answers = parts.iter().map(|&part| (part, Err(e.clone()))).collect();
+         run.failed = Some(e);
+         return run;
+      }
+   };
+   for &part in parts {
+      let _span = info_span!("part", part = part).entered();
+      let mut bar = if progress { Bar::new(day.day, part) } else { Bar::hidden() };
+      let (answer, took) = timing::time(|| puzzle.part_with_progress(part, &mut bar).map_err(|e| e.to_string()));
+      run.timing.parts[part as usize - 1] = Some(took);
+      debug!(?took, ?answer, "finished");
+      run.answers.push((part, answer));
+   }
+   return run;
+}
+
+// How aoc run prints what it found.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+   Text,
+   Json,
+   Csv,
+}
+
+impl FromStr for Format {
+   type Err = String;
+
+   fn from_str(s: &str) -> Result<Format, String> {
+      return match s {
+         "text" => Ok(Format::Text),
+         "json" => Ok(Format::Json),
+         "csv" => Ok(Format::Csv),
+         _ => Err(format!("Invalid format {:?}, expected text, json or csv", s)),
+      };
+   }
+}
+
+// One part's answer, as the json and csv formats give it.  answer is
+// missing if the part failed, and error says why; solve_ms is missing if
+// it never got as far as trying, and peak_bytes, the day's peak memory,
+// unless the memory feature's counting it.
+#[derive(Debug, Serialize)]
+pub struct Record {
+   pub day: u32,
+   pub part: u32,
+   pub answer: Option<String>,
+   pub error: Option<String>,
+   pub parse_ms: f64,
+   pub solve_ms: Option<f64>,
+   pub peak_bytes: Option<usize>,
+}
+
+fn ms(d: Duration) -> f64 {
+   return d.as_secs_f64() * 1000.0;
+}
+
+pub fn records(runs: &[Run]) -> Vec<Record> {
+   let mut records = Vec::new();
+   for run in runs {
+      for &(part, ref answer) in &run.answers {
+         records.push(Record{
+            day: run.day,
+            part: part,
+            answer: answer.as_ref().ok().cloned(),
+            error: answer.as_ref().err().cloned(),
+            parse_ms: ms(run.timing.parse),
+            solve_ms: run.timing.parts[part as usize - 1].map(ms),
+            peak_bytes: run.timing.peak,
+         });
+      }
+   }
+   return records;
+}
+
+pub fn json(records: &[Record]) -> String {
+   return serde_json::to_string_pretty(records).unwrap() + "\n";
+}
+
+// A field quoted if it has to be, as spreadsheets expect.
+fn csv_field(s: &str) -> String {
+   if s.contains([',', '"', '\n']) {
+      return format!("\"{}\"", s.replace('"', "\"\""));
+   }
+   return s.to_string();
+}
+
+pub fn csv(records: &[Record]) -> String {
+   let mut csv = "day,part,answer,parse_ms,solve_ms,peak_bytes,error\n".to_string();
+   for r in records {
+      let fields = [
+         r.day.to_string(),
+         r.part.to_string(),
+         csv_field(r.answer.as_ref().map(|a| &a[..]).unwrap_or("")),
+         format!("{:.3}", r.parse_ms),
+         r.solve_ms.map(|ms| format!("{:.3}", ms)).unwrap_or_default(),
+         r.peak_bytes.map(|b| b.to_string()).unwrap_or_default(),
+         csv_field(r.error.as_ref().map(|e| &e[..]).unwrap_or("")),
+      ];
+      csv.push_str(&fields.join(","));
+      csv.push('\n');
+   }
+   return csv;
+}
+
+#[test]
+fn test_records() {
+   let day = ::days::find(7).unwrap();
+   let runs = [run(day, &[1, 2], Ok("123 -> b\nb OR 4 -> a\n".to_string()), false), run(day, &[2], Err(AocError::Network("Can't read it, sorry".to_string())), false)];
+   assert!(runs[0].ok());
+   assert!(!runs[1].ok());
+   assert_eq!(runs[1].text(), "Day 7 failed: Can't read it, sorry\n");
+   let mut records = records(&runs);
+   assert_eq!(records.len(), 3);
+   assert_eq!(records[2].answer, None);
+   assert_eq!(records[2].solve_ms, None);
+   for r in records.iter_mut() {
+      r.parse_ms = 1.0;
+      r.solve_ms = r.solve_ms.map(|_| 2.5);
+      r.peak_bytes = None;
+   }
+   assert_eq!(csv(&records), "\
+day,part,answer,parse_ms,solve_ms,peak_bytes,error
+7,1,127,1.000,2.500,,
+7,2,127,1.000,2.500,,
+7,2,,1.000,,,\"Can't read it, sorry\"
+");
+   assert!(json(&records[..1]).contains("\"answer\": \"127\",\n    \"error\": null,\n    \"parse_ms\": 1.0,"));
+   assert_eq!("csv".parse(), Ok(Format::Csv));
+   assert!("xml".parse::<Format>().is_err());
+}
@@ -0,0 +1,177 @@
+use aoc2015_lib::AocError;
+use days::{self, workspace_root};
+use report::Template;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CARGO_TOML: Template = Template(include_str!("../templates/Cargo.toml.in"));
+const LIB_RS: Template = Template(include_str!("../templates/lib.rs.in"));
+const MAIN_RS: Template = Template(include_str!("../templates/main.rs.in"));
+const EXAMPLE_TOML: Template = Template(include_str!("../templates/example.toml.in"));
+
+// text with line put in after the last line that matches, or None if
+// none do.
+fn insert_after_last<F: Fn(&str) -> bool>(text: &str, matches: F, line: &str) -> Option<String> {
+   let lines: Vec<&str> = text.lines().collect();
+   let at = lines.iter().rposition(|l| matches(l))? + 1;
+   return Some(join(&lines[..at], line, &lines[at..]));
+}
+
+// text with line put in just before the first closing line that comes
+// after opening, or None if there's no such thing.
+fn insert_before_closing(text: &str, opening: &str, closing: &str, line: &str) -> Option<String> {
+   let lines: Vec<&str> = text.lines().collect();
+   let start = lines.iter().position(|l| l.starts_with(opening))?;
+   let at = start + lines[start..].iter().position(|l| *l == closing)?;
+   return Some(join(&lines[..at], line, &lines[at..]));
+}
+
+fn join(before: &[&str], line: &str, after: &[&str]) -> String {
+   let mut lines = before.to_vec();
+   lines.push(line);
+   lines.extend_from_slice(after);
+   return lines.join("\n") + "\n";
+}
+
+// The workspace's Cargo.toml with dir added to the end of its members.
+fn add_member(text: &str, dir: &str) -> Option<String> {
+   let lines: Vec<&str> = text.lines().collect();
+   let at = lines.iter().rposition(|l| l.starts_with("   \"") && l.ends_with("\","))?;
+   let member = format!("{} \"{}\",", lines[at], dir);
+   return Some(join(&lines[..at], &member, &lines[at + 1..]));
+}
+
+// A Cargo.toml with feature added to the end of its default features.
+fn add_default(text: &str, feature: &str) -> Option<String> {
+   let lines: Vec<&str> = text.lines().collect();
+   let at = lines.iter().position(|l| l.starts_with("default = [") && l.ends_with(']'))?;
+   let default = format!("{}, \"{}\"]", &lines[at][..lines[at].len() - 1], feature);
+   return Some(join(&lines[..at], &default, &lines[at + 1..]));
+}
+
+// One edit to a file that's already there, registering the new day: its
+// text with the day added, or None if there's nowhere to add it.
+type EditFn = Box<dyn Fn(&str) -> Option<String>>;
+
+struct Edit {
+   path: &'static str,
+   edit: EditFn,
+}
+
+fn registry_edits(day: u32) -> Vec<Edit> {
+   let nn = format!("{:02}", day);
+   let dir = day.to_string();
+   let feature = format!("day{}", nn);
+   let aoc_default = feature.clone();
+   let dependency = format!("day{} = {{ path = \"../{}\", default-features = false, optional = true }}", nn, day);
+   let days_feature = format!("day{0} = [\"dep:day{0}\"]", nn);
+   let aoc_feature = format!("day{0} = [\"aoc2015-days/day{0}\"]", nn);
+   let cfg = format!("#[cfg(feature = \"day{}\")]", nn);
+   let extern_crate = format!("{}\nextern crate day{};", cfg, nn);
+   let import = format!("{}\nuse day{1}::Day{1};", cfg, nn);
+   let bench_dependency = format!("day{} = {{ path = \"../{}\", default-features = false }}", nn, day);
+   let bench_extern_crate = format!("extern crate day{};", nn);
+   let bench_import = format!("use day{0}::Day{0};", nn);
+   let bench = format!("   bench::<Day{}>(c, {});", nn, day);
+   let entry = format!("   {}\n   Day{{day: {}, parse: solver::<Day{}>}},", cfg, day, nn);
+   let meta = format!("   Meta{{day: {}, title: \"Day {}\", notes: \"\\\nNot written up yet.\"}},", day, day);
+   let test = format!("   test_day{}_examples: {},", nn, day);
+   let solve = format!("   solve_day{0}: {1}, \"day{0}\",", nn, day);
+   let is_day = |prefix: &'static str| move |l: &str| l.starts_with(prefix) && l[prefix.len()..].starts_with(|c: char| c.is_ascii_digit());
+   return vec![
+      Edit{path: "Cargo.toml", edit: Box::new(move |text| add_member(text, &dir))},
+      Edit{path: "days/Cargo.toml", edit: Box::new(move |text| {
+         let text = insert_after_last(text, |l| is_day("day")(l) && l.contains("path = "), &dependency)?;
+         let text = insert_after_last(&text, |l| is_day("day")(l) && l.contains("[\"dep:"), &days_feature)?;
+         return add_default(&text, &feature);
+      })},
+      Edit{path: "aoc/Cargo.toml", edit: Box::new(move |text| add_default(&insert_after_last(text, is_day("day"), &aoc_feature)?, &aoc_default))},
+      Edit{path: "days/src/lib.rs", edit: Box::new(move |text| {
+         let text = insert_after_last(text, is_day("extern crate day"), &extern_crate)?;
+         let text = insert_after_last(&text, is_day("use day"), &import)?;
+         let text = insert_before_closing(&text, "pub const DAYS", "];", &entry)?;
+         return insert_before_closing(&text, "solve_days! {", "}", &solve);
+      })},
+      Edit{path: "benches/Cargo.toml", edit: Box::new(move |text| insert_after_last(text, |l| is_day("day")(l) && l.contains("path = "), &bench_dependency))},
+      Edit{path: "benches/benches/days.rs", edit: Box::new(move |text| {
+         let text = insert_after_last(text, is_day("extern crate day"), &bench_extern_crate)?;
+         let text = insert_after_last(&text, is_day("use day"), &bench_import)?;
+         return insert_before_closing(&text, "fn solving", "}", &bench);
+      })},
+      Edit{path: "aoc/src/meta.rs", edit: Box::new(move |text| insert_before_closing(text, "pub const META", "];", &meta))},
+      Edit{path: "aoc/src/examples.rs", edit: Box::new(move |text| insert_before_closing(text, "examples! {", "}", &test))},
+   ];
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), AocError> {
+   return path.parent().map_or(Ok(()), fs::create_dir_all)
+      .and_then(|_| fs::write(path, contents))
+      .map_err(|e| AocError::io(&path.to_string_lossy(), e));
+}
+
+// Starts day off: a workspace member for it with a Solver that doesn't
+// solve anything yet, an empty input.txt to put the input in, an empty
+// example to fill in, and the day added to everything that lists the
+// days.  Returns every file written, from the top of the workspace.
+pub fn new_day(day: u32) -> Result<Vec<PathBuf>, String> {
+   if !(1..26).contains(&day) {
+      return Err(format!("There's no day {}, only 1 to 25", day));
+   }
+   let root = workspace_root();
+   let dir = root.join(day.to_string());
+   if days::find(day).is_some() || dir.exists() {
+      return Err(format!("Day {} is already there, in {}", day, dir.display()));
+   }
+
+   // Every edit is worked out before anything's written, so that one
+   // that can't be made leaves everything as it was.
+   let mut edited = Vec::new();
+   for edit in registry_edits(day) {
+      let path = root.join(edit.path);
+      let text = fs::read_to_string(&path).map_err(|e| AocError::io(&path.to_string_lossy(), e).to_string())?;
+      let text = (edit.edit)(&text).ok_or(format!("Can't see where day {} goes in {}", day, path.display()))?;
+      edited.push((path, text));
+   }
+   let nn = format!("{:02}", day);
+   let values = [("nn", &nn[..])];
+   let examples = root.join("examples");
+   let mut files = vec![
+      (dir.join("Cargo.toml"), CARGO_TOML.render(&values)),
+      (dir.join("src").join("lib.rs"), LIB_RS.render(&values)),
+      (dir.join("src").join("main.rs"), MAIN_RS.render(&values)),
+      (dir.join("input.txt"), String::new()),
+      (examples.join(format!("day{}_1.txt", nn)), String::new()),
+      (examples.join(format!("day{}_1.toml", nn)), EXAMPLE_TOML.render(&values)),
+   ];
+   files.extend(edited);
+   for &(ref path, ref contents) in &files {
+      write(path, contents).map_err(|e| e.to_string())?;
+   }
+   return Ok(files.into_iter().map(|(path, _)| path.strip_prefix(&root).map(Path::to_path_buf).unwrap_or(path)).collect());
+}
+
+#[test]
+fn test_registry_edits() {
+   let edit = |path: &str, text: &str| {
+      let edits = registry_edits(10);
+      let edit = edits.iter().find(|e| e.path == path).unwrap();
+      return (edit.edit)(text);
+   };
+   assert_eq!(edit("Cargo.toml", "members = [\n   \"aoc\",\n   \"1\", \"2\",\n]\n").unwrap(), "members = [\n   \"aoc\",\n   \"1\", \"2\", \"10\",\n]\n");
+   assert_eq!(
+      edit("days/src/lib.rs", "extern crate day09;\n\nuse day09::Map;\n\npub const DAYS: &[Day] = &[\n   Day{day: 9, parse: nine},\n];\n\nsolve_days! {\n}\n").unwrap(),
+      "extern crate day09;\n#[cfg(feature = \"day10\")]\nextern crate day10;\n\nuse day09::Map;\n#[cfg(feature = \"day10\")]\nuse day10::Day10;\n\npub const DAYS: &[Day] = &[\n   Day{day: 9, parse: nine},\n   #[cfg(feature = \"day10\")]\n   Day{day: 10, parse: solver::<Day10>},\n];\n\nsolve_days! {\n   solve_day10: 10, \"day10\",\n}\n");
+   assert_eq!(edit("aoc/Cargo.toml", "[features]\ndefault = [\"day09\"]\nday09 = [\"aoc2015-days/day09\"]\n").unwrap(), "[features]\ndefault = [\"day09\", \"day10\"]\nday09 = [\"aoc2015-days/day09\"]\nday10 = [\"aoc2015-days/day10\"]\n");
+   assert_eq!(edit("aoc/src/examples.rs", "examples! {\n   test_day09_examples: 9,\n}\n").unwrap(), "examples! {\n   test_day09_examples: 9,\n   test_day10_examples: 10,\n}\n");
+   assert_eq!(edit("benches/benches/days.rs", "extern crate day09;\n\nuse day09::Day09;\n\nfn solving(c: &mut Criterion) {\n   bench::<Day09>(c, 9);\n}\n").unwrap(), "extern crate day09;\nextern crate day10;\n\nuse day09::Day09;\nuse day10::Day10;\n\nfn solving(c: &mut Criterion) {\n   bench::<Day09>(c, 9);\n   bench::<Day10>(c, 10);\n}\n");
+   assert!(edit("days/Cargo.toml", "[dependencies]\naoc2015-lib = \"1\"\n").is_none());
+   // The real files all have somewhere for it to go.
+   for e in registry_edits(10) {
+      assert!((e.edit)(&fs::read_to_string(workspace_root().join(e.path)).unwrap()).is_some(), "{}", e.path);
+   }
+   assert!(LIB_RS.render(&[("nn", "10")]).contains("pub struct Day10;"));
+   // Like every day's binary, it runs only the --part asked for.
+   let main = MAIN_RS.render(&[("nn", "10")]);
+   assert!(main.contains("for part in aoc2015_lib::parts()"));
+   assert!(main.contains("solve::<Day10>(&input, part)?"));
+}
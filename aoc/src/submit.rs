@@ -0,0 +1,146 @@
+use aoc2015_lib::AocError;
+use config::Config;
+use fetch::{authorized, response_text};
+use std::fmt;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ureq;
+
+// What adventofcode.com made of an answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+   Right,
+   Wrong,
+   TooHigh,
+   TooLow,
+   // Answered too soon after the last one, with how long is left to wait,
+   // like "4m 12s".
+   Wait(String),
+   // The part has already been solved, so there's nothing to answer.
+   AlreadySolved,
+   // Not sent, since the same answer already got this verdict.
+   AlreadyAnswered(String),
+   // Anything else, as the text of the page.
+   Unknown(String),
+}
+
+impl fmt::Display for Outcome {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         Outcome::Right => write!(f, "right"),
+         Outcome::Wrong => write!(f, "wrong"),
+         Outcome::TooHigh => write!(f, "too high"),
+         Outcome::TooLow => write!(f, "too low"),
+         Outcome::Wait(ref left) => write!(f, "wait {}", left),
+         Outcome::AlreadySolved => write!(f, "already solved"),
+         Outcome::AlreadyAnswered(ref verdict) => write!(f, "already answered, and was {}", verdict),
+         Outcome::Unknown(ref text) => write!(f, "unrecognised response: {}", text),
+      }
+   }
+}
+
+// The text of the page's <article>, where the verdict is, without tags.
+fn article_text(html: &str) -> String {
+   let start = html.find("<article").unwrap_or(0);
+   let end = html[start..].find("</article>").map_or(html.len(), |e| start + e);
+   let mut text = String::new();
+   let mut in_tag = false;
+   for c in html[start..end].chars() {
+      match c {
+         '<' => in_tag = true,
+         '>' => in_tag = false,
+         c if !in_tag => text.push(c),
+         _ => (),
+      }
+   }
+   return text.split_whitespace().collect::<Vec<_>>().join(" ");
+}
+
+pub fn parse_response(html: &str) -> Outcome {
+   let text = article_text(html);
+   if text.contains("That's the right answer") {
+      return Outcome::Right;
+   }
+   if text.contains("That's not the right answer") {
+      return if text.contains("too high") {
+         Outcome::TooHigh
+      } else if text.contains("too low") {
+         Outcome::TooLow
+      } else {
+         Outcome::Wrong
+      };
+   }
+   if let (Some(start), Some(end)) = (text.find("You have "), text.find(" left to wait")) {
+      if start + "You have ".len() <= end {
+         return Outcome::Wait(text[start + "You have ".len()..end].to_string());
+      }
+   }
+   if text.contains("You don't seem to be solving the right level") {
+      return Outcome::AlreadySolved;
+   }
+   return Outcome::Unknown(text);
+}
+
+// Every answer given, one per line: when, the day, part, answer and what
+// came of it, separated by tabs.
+pub fn log_file(config: &Config) -> PathBuf {
+   return config.user_inputs().join("submissions.tsv");
+}
+
+// What came of giving answer before, going by log, if it got a verdict.
+pub fn previous<'a>(log: &'a str, day: u32, part: u32, answer: &str) -> Option<&'a str> {
+   return log.lines().rev().filter_map(|line| {
+      let fields: Vec<&str> = line.split('\t').collect();
+      match fields[..] {
+         [_, d, p, a, outcome] if d == day.to_string() && p == part.to_string() && a == answer => Some(outcome),
+         _ => None,
+      }
+   }).find(|&outcome| ["right", "wrong", "too high", "too low"].contains(&outcome));
+}
+
+fn record(config: &Config, day: u32, part: u32, answer: &str, outcome: &Outcome) -> Result<(), AocError> {
+   let path = log_file(config);
+   let when = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+   let line = format!("{}\t{}\t{}\t{}\t{}\n", when, day, part, answer, outcome.to_string().replace(['\t', '\n'], " "));
+   return fs::create_dir_all(path.parent().unwrap())
+      .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+      .and_then(|mut f| f.write_all(line.as_bytes()))
+      .map_err(|e| AocError::io(&path.to_string_lossy(), e));
+}
+
+// Gives answer for day's part, and logs what came of it.  An answer
+// that's already had a verdict isn't sent again.
+pub fn submit(config: &Config, day: u32, part: u32, answer: &str) -> Result<Outcome, AocError> {
+   let log = fs::read_to_string(log_file(config)).unwrap_or_default();
+   if let Some(verdict) = previous(&log, day, part, answer) {
+      return Ok(Outcome::AlreadyAnswered(verdict.to_string()));
+   }
+   let url = format!("https://adventofcode.com/2015/day/{}/answer", day);
+   let response = authorized(config, ureq::post(&url))?.send_form(&[("level", &part.to_string()), ("answer", answer)]);
+   let outcome = parse_response(&response_text(&url, response)?);
+   record(config, day, part, answer, &outcome)?;
+   return Ok(outcome);
+}
+
+#[test]
+fn test_parse_response() {
+   let page = |article: &str| format!("<html><main><article><p>{}</p></article></main></html>", article);
+   assert_eq!(parse_response(&page("That's the right answer!  You are <em>one gold star</em> closer.")), Outcome::Right);
+   assert_eq!(parse_response(&page("That's not the right answer; your answer is too high.  Please wait one minute.")), Outcome::TooHigh);
+   assert_eq!(parse_response(&page("That's not the right answer.  If you're stuck...")), Outcome::Wrong);
+   assert_eq!(parse_response(&page("You gave an answer too recently.  You have 4m 12s left to wait.")), Outcome::Wait("4m 12s".to_string()));
+   assert_eq!(parse_response(&page("You don't seem to be solving the right level.  Did you already complete it?")), Outcome::AlreadySolved);
+   assert_eq!(parse_response("<p>Server\n error</p>"), Outcome::Unknown("Server error".to_string()));
+}
+
+#[test]
+fn test_previous() {
+   let log = "1\t7\t1\t123\ttoo low\n2\t7\t1\t456\twait 1m\n3\t7\t2\t456\tright\n";
+   assert_eq!(previous(log, 7, 1, "123"), Some("too low"));
+   assert_eq!(previous(log, 7, 1, "456"), None);
+   assert_eq!(previous(log, 7, 2, "456"), Some("right"));
+   assert_eq!(previous(log, 8, 2, "456"), None);
+}
@@ -0,0 +1,112 @@
+use memory;
+use std::time::{Duration, Instant};
+
+// How long one day took: parsing its input, then each part that was run.
+// peak is the most the day had allocated at once, if the memory feature's
+// counting.
+pub struct Timing {
+   pub day: u32,
+   pub parse: Duration,
+   pub parts: [Option<Duration>; 2],
+   pub peak: Option<usize>,
+}
+
+impl Timing {
+   pub fn total(&self) -> Duration {
+      return self.parse + self.parts.iter().filter_map(|p| *p).sum::<Duration>();
+   }
+}
+
+// Runs f, and says how long it took as well.
+pub fn time<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+   let started = Instant::now();
+   let result = f();
+   return (result, started.elapsed());
+}
+
+pub fn ms(d: Duration) -> String {
+   return format!("{:.3}ms", d.as_secs_f64() * 1000.0);
+}
+
+// A table of timings, a row for each day and their totals at the bottom.
+// Parts that weren't run are left blank.  Each day's peak memory is in a
+// column of its own if any were measured, with the biggest at the bottom.
+pub fn table(timings: &[Timing]) -> String {
+   let peaks = timings.iter().any(|t| t.peak.is_some());
+   let mut rows = vec![vec!["day".to_string(), "parse".to_string(), "part 1".to_string(), "part 2".to_string(), "total".to_string()]];
+   let cell = |d: Option<Duration>| d.map(ms).unwrap_or_default();
+   for t in timings {
+      rows.push(vec![t.day.to_string(), ms(t.parse), cell(t.parts[0]), cell(t.parts[1]), ms(t.total())]);
+   }
+   let sum = |f: &dyn Fn(&Timing) -> Option<Duration>| {
+      let times: Vec<Duration> = timings.iter().filter_map(f).collect();
+      return cell(if times.is_empty() { None } else { Some(times.into_iter().sum()) });
+   };
+   rows.push(vec![
+      "all".to_string(),
+      sum(&|t| Some(t.parse)),
+      sum(&|t| t.parts[0]),
+      sum(&|t| t.parts[1]),
+      sum(&|t| Some(t.total())),
+   ]);
+   if peaks {
+      rows[0].push("peak".to_string());
+      for (row, t) in rows[1..].iter_mut().zip(timings) {
+         row.push(t.peak.map(memory::size).unwrap_or_default());
+      }
+      let most = timings.iter().filter_map(|t| t.peak).max();
+      rows.last_mut().unwrap().push(most.map(memory::size).unwrap_or_default());
+   }
+   return layout(&rows, true);
+}
+
+// rows lined up in columns, the first to the left and the rest to the
+// right, with the last row ruled off from the others if it's totals.
+pub fn layout(rows: &[Vec<String>], totals: bool) -> String {
+   let mut widths = vec![0; rows[0].len()];
+   for row in rows {
+      for (width, cell) in widths.iter_mut().zip(row.iter()) {
+         *width = (*width).max(cell.len());
+      }
+   }
+   let mut table = String::new();
+   for (i, row) in rows.iter().enumerate() {
+      if totals && i == rows.len() - 1 {
+         let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+         table.push_str(&rule.join("  "));
+         table.push('\n');
+      }
+      let cells: Vec<String> = row.iter().zip(widths.iter()).enumerate()
+         .map(|(i, (cell, &w))| if i == 0 { format!("{:<1$}", cell, w) } else { format!("{:>1$}", cell, w) })
+         .collect();
+      table.push_str(cells.join("  ").trim_end());
+      table.push('\n');
+   }
+   return table;
+}
+
+#[test]
+fn test_table() {
+   let ms = Duration::from_millis;
+   let timings = [
+      Timing{day: 1, parse: ms(0), parts: [Some(ms(1)), Some(ms(2))], peak: None},
+      Timing{day: 7, parse: ms(10), parts: [None, Some(ms(250))], peak: None},
+   ];
+   assert!(table(&timings[1..]).ends_with("\nall  10.000ms          250.000ms  260.000ms\n"));
+   assert_eq!(timings[1].total(), ms(260));
+   assert_eq!(table(&timings), "\
+day     parse   part 1     part 2      total
+1     0.000ms  1.000ms    2.000ms    3.000ms
+7    10.000ms           250.000ms  260.000ms
+---  --------  -------  ---------  ---------
+all  10.000ms  1.000ms  252.000ms  263.000ms
+");
+   let peaks = [Timing{peak: Some(512), ..timings[0]}, Timing{peak: Some(5 << 20), ..timings[1]}];
+   assert_eq!(table(&peaks), "\
+day     parse   part 1     part 2      total   peak
+1     0.000ms  1.000ms    2.000ms    3.000ms   512B
+7    10.000ms           250.000ms  260.000ms  5.0MB
+---  --------  -------  ---------  ---------  -----
+all  10.000ms  1.000ms  252.000ms  263.000ms  5.0MB
+");
+}
@@ -0,0 +1,226 @@
+use aoc2015_lib::AocError;
+use check::Answers;
+use config::Config;
+use days::{self, Day};
+use fetch;
+use ratatui;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::Frame;
+use results::{self, Run};
+use std::fs;
+use std::io;
+use std::slice;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+use timing;
+
+const CELL_WIDTH: u16 = 14;
+const CELL_HEIGHT: u16 = 4;
+
+// Where a day has got to, as its cell in the grid shows it.
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+   Unsolved,
+   NotRun,
+   Queued,
+   Running(Instant),
+   // How long it took, either way.
+   Passed(Duration),
+   Failed(Duration),
+}
+
+impl Status {
+   fn label(&self) -> (String, Color) {
+      return match *self {
+         Status::Unsolved => ("unsolved".to_string(), Color::DarkGray),
+         Status::NotRun => ("not run".to_string(), Color::Reset),
+         Status::Queued => ("queued".to_string(), Color::Yellow),
+         Status::Running(started) => (format!("{:.1}s...", started.elapsed().as_secs_f64()), Color::Yellow),
+         Status::Passed(took) => (format!("ok {}", timing::ms(took)), Color::Green),
+         Status::Failed(took) => (format!("FAIL {}", timing::ms(took)), Color::Red),
+      };
+   }
+}
+
+// What the worker thread says as it gets through the days it's sent.
+enum Message {
+   Started(u32),
+   Finished(Run),
+}
+
+// Runs every day it's sent, one after another, on its own thread so the
+// grid keeps drawing meanwhile.
+fn worker(config: Config, jobs: Receiver<&'static Day>, messages: Sender<Message>) {
+   for day in jobs {
+      if messages.send(Message::Started(day.day)).is_err() {
+         return;
+      }
+      let input = fetch::find_input(&config, day)
+         .and_then(|path| fs::read_to_string(&path).map_err(|e| AocError::io(&path.to_string_lossy(), e)));
+      if messages.send(Message::Finished(results::run(day, &[1, 2], input, false))).is_err() {
+         return;
+      }
+   }
+}
+
+// Whether run got every answer it should have, going by answers, and its
+// output: the answers, any that aren't what they should be, and how long
+// it all took.
+fn judge(run: &Run, answers: &Answers) -> (bool, String) {
+   let mut ok = run.ok();
+   let mut text = run.text();
+   for &(part, ref answer) in &run.answers {
+      if let (&Ok(ref got), Some(expected)) = (answer, answers.expected(run.day, part)) {
+         if got != expected {
+            ok = false;
+            text.push_str(&format!("Part {} should be {}\n", part, expected));
+         }
+      }
+   }
+   text.push('\n');
+   text.push_str(&timing::table(slice::from_ref(&run.timing)));
+   return (ok, text);
+}
+
+struct Dashboard<'a> {
+   answers: &'a Answers,
+   // By day, from day 1.
+   statuses: Vec<Status>,
+   outputs: Vec<String>,
+   selected: u32,
+   jobs: Sender<&'static Day>,
+}
+
+impl<'a> Dashboard<'a> {
+   fn queue(&mut self, day: u32) {
+      if let Some(found) = days::find(day) {
+         if self.jobs.send(found).is_ok() {
+            self.statuses[day as usize - 1] = Status::Queued;
+         }
+      }
+   }
+
+   // Moves the selection by delta days, if that's still on the grid.
+   fn select(&mut self, delta: i32) {
+      let day = self.selected as i32 + delta;
+      if (1..26).contains(&day) {
+         self.selected = day as u32;
+      }
+   }
+
+   fn heard(&mut self, message: Message) {
+      match message {
+         Message::Started(day) => self.statuses[day as usize - 1] = Status::Running(Instant::now()),
+         Message::Finished(run) => {
+            let (ok, text) = judge(&run, self.answers);
+            let took = run.timing.total();
+            let i = run.day as usize - 1;
+            self.statuses[i] = if ok { Status::Passed(took) } else { Status::Failed(took) };
+            self.outputs[i] = text;
+         }
+      }
+   }
+
+   fn draw(&self, frame: &mut Frame) {
+      let [main, help] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+      let [grid, output] = Layout::horizontal([Constraint::Length(5 * CELL_WIDTH), Constraint::Min(20)]).areas(main);
+      let rows = Layout::vertical([Constraint::Length(CELL_HEIGHT); 5]).split(grid);
+      for (r, row) in rows.iter().enumerate() {
+         let cells = Layout::horizontal([Constraint::Length(CELL_WIDTH); 5]).split(*row);
+         for (c, cell) in cells.iter().enumerate() {
+            let day = (r * 5 + c) as u32 + 1;
+            let (label, color) = self.statuses[day as usize - 1].label();
+            let mut block = Block::bordered().title(format!("Day {}", day));
+            if day == self.selected {
+               block = block.border_style(Style::new().add_modifier(Modifier::BOLD).fg(Color::Cyan));
+            }
+            frame.render_widget(Paragraph::new(Line::styled(label, Style::new().fg(color))).block(block), *cell);
+         }
+      }
+
+      let i = self.selected as usize - 1;
+      let text = match self.statuses[i] {
+         Status::Unsolved => "Not solved yet.".to_string(),
+         Status::NotRun => "Not run yet: enter runs it.".to_string(),
+         _ => self.outputs[i].clone(),
+      };
+      let block = Block::bordered().title(format!("Day {}", self.selected));
+      frame.render_widget(Paragraph::new(text).wrap(Wrap{trim: false}).block(block), output);
+      frame.render_widget(Line::styled("arrows select  enter run  a run all  q quit", Style::new().fg(Color::DarkGray)), help);
+   }
+}
+
+// A grid of all 25 days showing whether each passes and how long it
+// took, where any day can be picked to run again and see the output of.
+// Runs until q is pressed.
+pub fn tui(config: &Config, answers: &Answers) -> io::Result<()> {
+   let (jobs, queued) = mpsc::channel();
+   let (tell, messages) = mpsc::channel();
+   let worker_config = config.clone();
+   thread::spawn(move || worker(worker_config, queued, tell));
+
+   let mut dashboard = Dashboard{
+      answers: answers,
+      statuses: (1..26).map(|day| if days::find(day).is_some() { Status::NotRun } else { Status::Unsolved }).collect(),
+      outputs: vec![String::new(); 25],
+      selected: 1,
+      jobs: jobs,
+   };
+   let mut terminal = ratatui::init();
+   let result = (|| {
+      loop {
+         loop {
+            match messages.try_recv() {
+               Ok(message) => dashboard.heard(message),
+               Err(TryRecvError::Empty) => break,
+               Err(TryRecvError::Disconnected) => return Err(io::Error::other("the worker thread died")),
+            }
+         }
+         terminal.draw(|frame| dashboard.draw(frame))?;
+         if !event::poll(Duration::from_millis(100))? {
+            continue;
+         }
+         let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key.code,
+            _ => continue,
+         };
+         match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Left | KeyCode::Char('h') => dashboard.select(-1),
+            KeyCode::Right | KeyCode::Char('l') => dashboard.select(1),
+            KeyCode::Up | KeyCode::Char('k') => dashboard.select(-5),
+            KeyCode::Down | KeyCode::Char('j') => dashboard.select(5),
+            KeyCode::Enter | KeyCode::Char('r') => {
+               let day = dashboard.selected;
+               dashboard.queue(day);
+            }
+            KeyCode::Char('a') => {
+               for day in days::DAYS {
+                  dashboard.queue(day.day);
+               }
+            }
+            _ => (),
+         }
+      }
+   })();
+   ratatui::restore();
+   return result;
+}
+
+#[test]
+fn test_judge() {
+   let day = days::find(7).unwrap();
+   let run = results::run(day, &[1, 2], Ok("123 -> b\nb OR 4 -> a\n".to_string()), false);
+   let (ok, text) = judge(&run, &Answers::parse("[day07]\npart1 = 127\n").unwrap());
+   assert!(ok);
+   assert!(text.starts_with("Day 7 part 1: 127\nDay 7 part 2: 127\n\n"));
+   let (ok, text) = judge(&run, &Answers::parse("[day07]\npart2 = 128\n").unwrap());
+   assert!(!ok);
+   assert!(text.contains("Part 2 should be 128\n"));
+   assert_eq!(Status::Passed(Duration::from_millis(3)).label().1, Color::Green);
+}
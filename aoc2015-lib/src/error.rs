@@ -0,0 +1,80 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+// Where in the input something went wrong, counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+   pub line: usize,
+   pub column: Option<usize>,
+}
+
+impl fmt::Display for Location {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match self.column {
+         Some(column) => write!(f, "line {}, column {}", self.line, column),
+         None => write!(f, "line {}", self.line),
+      }
+   }
+}
+
+// Everything that can go wrong with a day, from getting its input to
+// finding there's no answer to it.
+#[derive(Error)]
+pub enum AocError {
+   #[cfg(feature = "std")]
+   #[error("{path}: {source}")]
+   Io { path: String, source: io::Error },
+   #[error("{location}: {message}")]
+   Parse { location: Location, message: String },
+   #[error("No answer: {0}")]
+   Unsolvable(String),
+   #[error("{0}")]
+   Network(String),
+   // An argument, or something asked of the input, that makes no sense,
+   // as a sentence saying why.
+   #[error("{0}")]
+   Invalid(String),
+}
+
+impl AocError {
+   #[cfg(feature = "std")]
+   pub fn io(path: &str, source: io::Error) -> AocError {
+      return AocError::Io{path: path.to_string(), source: source};
+   }
+
+   pub fn parse(line: usize, column: Option<usize>, message: String) -> AocError {
+      return AocError::Parse{location: Location{line: line, column: column}, message: message};
+   }
+}
+
+// The same as Display, so that a main returning Err(AocError) prints why
+// it failed rather than the variant it failed with.
+impl fmt::Debug for AocError {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      fmt::Display::fmt(self, f)
+   }
+}
+
+// f applied to every line of input, where an error it gives is put at
+// the line it came from.
+pub fn parse_lines<T, F: FnMut(&str) -> Result<T, String>>(input: &str, mut f: F) -> Result<Vec<T>, AocError> {
+   return input.lines().enumerate()
+      .map(|(i, line)| f(line).map_err(|message| AocError::parse(i + 1, None, message)))
+      .collect();
+}
+
+#[test]
+fn test_parse_lines() {
+   let parse = |l: &str| l.parse::<u32>().map_err(|_| format!("{:?} isn't a number", l));
+   assert_eq!(parse_lines("1\n2\n", parse).unwrap(), [1, 2]);
+   let err = parse_lines("1\n2\nthree\n", parse).unwrap_err();
+   assert_eq!(err.to_string(), "line 3: \"three\" isn't a number");
+   assert_eq!(AocError::parse(1, Some(4), "no".to_string()).to_string(), "line 1, column 4: no");
+   #[cfg(feature = "std")]
+   let err = AocError::io("input.txt", io::Error::new(io::ErrorKind::NotFound, "not there"));
+   #[cfg(feature = "std")]
+   assert_eq!(format!("{:?}", err), "input.txt: not there");
+}
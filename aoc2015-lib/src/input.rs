@@ -0,0 +1,132 @@
+use error::AocError;
+use getopts::Options;
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+use std::io::{BufReader, IsTerminal};
+use std::process;
+use tracing_subscriber;
+use tracing_subscriber::EnvFilter;
+
+// Where each day looks for its input unless told otherwise.
+pub const DEFAULT_INPUT: &str = "input.txt";
+
+// The input path that means standard input instead of a file.
+pub const STDIN: &str = "-";
+
+// What a day's binary was asked for on its command line.
+#[derive(Debug, PartialEq)]
+pub struct Args {
+   // The input named with --input, or DEFAULT_INPUT.
+   pub input: String,
+   // The parts --part asks for, or both.
+   pub parts: Vec<u32>,
+}
+
+// The binary's command line.  Anything on it that doesn't belong there is
+// a mistake, which ends the program with its usage.
+pub fn args() -> Args {
+   let args: Vec<String> = env::args().collect();
+   return match parse_args(&args[1..]) {
+      Ok(parsed) => parsed,
+      Err(e) => {
+         eprintln!("{}\nUsage: {} [--input PATH] [--part 1|2]", e, args[0]);
+         process::exit(2);
+      }
+   };
+}
+
+pub fn input_path() -> String {
+   return args().input;
+}
+
+pub fn parts() -> Vec<u32> {
+   return args().parts;
+}
+
+pub fn parse_part(part: &str) -> Result<u32, String> {
+   return match part {
+      "1" => Ok(1),
+      "2" => Ok(2),
+      _ => Err(format!("Invalid part {:?}, expected 1 or 2", part)),
+   };
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+   let mut opts = Options::new();
+   opts.optopt("", "input", "the puzzle input, or - for standard input", "PATH");
+   opts.optopt("", "part", "run only part 1 or 2", "1|2");
+   let matches = opts.parse(args).map_err(|e| e.to_string())?;
+   if let Some(arg) = matches.free.first() {
+      return Err(format!("Unexpected argument {:?}", arg));
+   }
+   let parts = match matches.opt_str("part") {
+      Some(part) => vec![parse_part(&part)?],
+      None => vec![1, 2],
+   };
+   return Ok(Args{input: matches.opt_str("input").unwrap_or(DEFAULT_INPUT.to_string()), parts: parts});
+}
+
+// path opened for reading, or standard input if it's STDIN.
+pub fn open(path: &str) -> io::Result<Box<dyn BufRead>> {
+   if path == STDIN {
+      return Ok(Box::new(io::stdin().lock()));
+   }
+   return Ok(Box::new(BufReader::new(File::open(path)?)));
+}
+
+pub fn read(path: &str) -> io::Result<String> {
+   let mut text = String::new();
+   open(path)?.read_to_string(&mut text)?;
+   return Ok(text);
+}
+
+// Every line of path, without their line endings.
+pub fn read_lines(path: &str) -> io::Result<Vec<String>> {
+   return open(path)?.lines().collect();
+}
+
+// Sends tracing's events to stderr, filtered by $RUST_LOG, like
+// RUST_LOG=day07=debug, or else by default, a filter of the same form such
+// as "warn".  Only the first call in a program does anything.
+pub fn init_tracing(default: &str) {
+   let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default));
+   let _ = tracing_subscriber::fmt().with_env_filter(filter).with_writer(io::stderr).with_ansi(io::stderr().is_terminal())
+      .without_time().try_init();
+}
+
+// The whole of the input from input_path.
+pub fn input() -> Result<String, AocError> {
+   let path = input_path();
+   return read(&path).map_err(|e| AocError::io(&path, e));
+}
+
+// The lines of the input from input_path.
+pub fn input_lines() -> Result<Vec<String>, AocError> {
+   let path = input_path();
+   return read_lines(&path).map_err(|e| AocError::io(&path, e));
+}
+
+#[test]
+fn test_read() {
+   let path = env::temp_dir().join(format!("aoc2015-lib-{}.txt", process::id()));
+   let path = path.to_str().unwrap();
+   File::create(path).unwrap().write_all(b"2x3x4\r\n1x1x10\n").unwrap();
+   assert_eq!(read(path).unwrap(), "2x3x4\r\n1x1x10\n");
+   assert_eq!(read_lines(path).unwrap(), ["2x3x4", "1x1x10"]);
+   std::fs::remove_file(path).unwrap();
+   assert!(read(path).is_err());
+}
+
+#[test]
+fn test_parse_args() {
+   let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+   assert_eq!(parse_args(&args(&[])), Ok(Args{input: DEFAULT_INPUT.to_string(), parts: vec![1, 2]}));
+   assert_eq!(parse_args(&args(&["--input", "-"])).unwrap().input, STDIN);
+   assert_eq!(parse_args(&args(&["--input=7/input.txt"])).unwrap().input, "7/input.txt");
+   assert!(parse_args(&args(&["input.txt"])).is_err());
+   assert!(parse_args(&args(&["--input"])).is_err());
+   assert_eq!(parse_args(&args(&["--part", "2", "--input", "-"])), Ok(Args{input: STDIN.to_string(), parts: vec![2]}));
+   assert_eq!(parse_args(&args(&["--part", "3"])).unwrap_err(), "Invalid part \"3\", expected 1 or 2");
+}
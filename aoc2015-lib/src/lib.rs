@@ -0,0 +1,29 @@
+// Plumbing shared by every day: finding the puzzle input and reading it,
+// setting up tracing, the Solver trait that days are run through, and the
+// AocError they all fail with.  Everything that touches files or the
+// command line is behind the io feature, on by default, so that the rest
+// builds for wasm32-unknown-unknown, and without std, which is behind the
+// std feature that io turns on, it builds for a bare-metal board.
+#![cfg_attr(not(feature = "std"), no_std)]
+#[macro_use]
+extern crate alloc;
+// Which no_std brings in by itself, but a 2015 crate with std has to ask for.
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(feature = "io")]
+extern crate getopts;
+#[macro_use]
+extern crate thiserror;
+#[cfg(feature = "io")]
+extern crate tracing_subscriber;
+
+mod error;
+#[cfg(feature = "io")]
+mod input;
+pub mod parse;
+mod solver;
+
+pub use error::{parse_lines, AocError, Location};
+#[cfg(feature = "io")]
+pub use input::{args, init_tracing, input, input_lines, input_path, open, parse_part, parts, read, read_lines, Args, DEFAULT_INPUT, STDIN};
+pub use solver::{solve, Progress, Solver};
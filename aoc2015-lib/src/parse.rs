@@ -0,0 +1,99 @@
+// Small helpers for the ways puzzle inputs are usually laid out, so that
+// each day needn't split and trim its lines by hand.
+use alloc::fmt;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::str::FromStr;
+use AocError;
+
+// Every number in s, in order, whatever's between them, so that
+// `numbers::<i32>("x=-3, y=14")` is [-3, 14].  A minus sign only counts
+// if T can be negative: for a u32, "1-3" is [1, 3].  Numbers too big for T
+// are left out.
+pub fn numbers<T: FromStr>(s: &str) -> Vec<T> {
+   let mut found = Vec::new();
+   let bytes = s.as_bytes();
+   let mut start = None;
+   for i in 0..(bytes.len() + 1) {
+      let digit = i < bytes.len() && bytes[i].is_ascii_digit();
+      match (start, digit) {
+         (None, true) => start = Some(i),
+         (Some(first), false) => {
+            let signed = if first > 0 && bytes[first - 1] == b'-' { first - 1 } else { first };
+            if let Some(n) = s[signed..i].parse().ok().or_else(|| s[first..i].parse().ok()) {
+               found.push(n);
+            }
+            start = None;
+         }
+         _ => {}
+      }
+   }
+   return found;
+}
+
+// s either side of the first delimiter, trimmed, or None if there isn't
+// one, so that `split_once_trim("x AND y -> z", "->")` is ("x AND y", "z").
+pub fn split_once_trim<'a>(s: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+   let at = s.find(delimiter)?;
+   return Some((s[..at].trim(), s[at + delimiter.len()..].trim()));
+}
+
+// line split at each of delimiters in turn, trimmed, so a line shaped
+// like `London to Dublin = 464` comes apart with [" to ", " = "] into
+// its three fields.  None if any delimiter's missing.
+pub fn fields<'a>(line: &'a str, delimiters: &[&str]) -> Option<Vec<&'a str>> {
+   let mut fields = Vec::new();
+   let mut rest = line;
+   for delimiter in delimiters {
+      let (field, after) = split_once_trim(rest, delimiter)?;
+      fields.push(field);
+      rest = after;
+   }
+   fields.push(rest.trim());
+   return Some(fields);
+}
+
+// Each line of some input parsed as a T, with an error that won't parse
+// put at its line.  Collect it into a Result<Vec<T>, AocError> to stop at
+// the first.
+pub struct Lines<'a, T> {
+   lines: core::iter::Enumerate<core::str::Lines<'a>>,
+   parsed: PhantomData<T>,
+}
+
+pub fn lines<T>(input: &str) -> Lines<'_, T> {
+   return Lines{lines: input.lines().enumerate(), parsed: PhantomData};
+}
+
+impl<'a, T: FromStr> Iterator for Lines<'a, T> where T::Err: fmt::Display {
+   type Item = Result<T, AocError>;
+
+   fn next(&mut self) -> Option<Result<T, AocError>> {
+      let (i, line) = self.lines.next()?;
+      return Some(line.parse().map_err(|e| AocError::parse(i + 1, None, format!("can't parse {:?}: {}", line, e))));
+   }
+}
+
+#[test]
+fn test_numbers() {
+   assert_eq!(numbers::<i32>("x=-3, y=14"), [-3, 14]);
+   assert_eq!(numbers::<u32>("1-3 a: abc"), [1, 3]);
+   assert_eq!(numbers::<u8>("2x300x4"), [2, 4]);
+   assert!(numbers::<u32>("none").is_empty());
+}
+
+#[test]
+fn test_fields() {
+   assert_eq!(split_once_trim("x AND y -> z", "->"), Some(("x AND y", "z")));
+   assert_eq!(split_once_trim("x AND y", "->"), None);
+   assert_eq!(fields("London to Dublin = 464", &[" to ", " = "]), Some(vec!["London", "Dublin", "464"]));
+   assert_eq!(fields("London to Dublin", &[" to ", " = "]), None);
+}
+
+#[test]
+fn test_lines() {
+   let parsed: Result<Vec<u32>, AocError> = lines("1\n2\n").collect();
+   assert_eq!(parsed.unwrap(), [1, 2]);
+   let parsed: Result<Vec<u32>, AocError> = lines("1\ntwo\n").collect();
+   assert_eq!(parsed.unwrap_err().to_string(), "line 2: can't parse \"two\": invalid digit found in string");
+}
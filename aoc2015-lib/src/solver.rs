@@ -0,0 +1,106 @@
+use alloc::string::String;
+use AocError;
+
+// A day's solution in the one shape that the runner, benchmarks and tests
+// can all drive: the input is parsed once, and either part is worked out
+// from what it was parsed into.  Parts can fail as well as parsing, for
+// inputs that parse but have no answer.
+pub trait Solver {
+   type Parsed;
+
+   fn parse(input: &str) -> Result<Self::Parsed, AocError>;
+   fn part1(parsed: &Self::Parsed) -> Result<String, AocError>;
+   fn part2(parsed: &Self::Parsed) -> Result<String, AocError>;
+
+   // One part, keeping progress up to date as it goes.  Only the slow
+   // parts need bother; the rest are over too soon for it to matter.
+   fn part_with_progress(parsed: &Self::Parsed, part: u32, _progress: &mut dyn Progress) -> Result<String, AocError> {
+      return match part {
+         1 => Self::part1(parsed),
+         2 => Self::part2(parsed),
+         _ => Err(AocError::Unsolvable(format!("there's no part {}", part))),
+      };
+   }
+}
+
+// Hears how far a slow part has got, so that whatever is running it can
+// show that (with a progress bar, say) without the part knowing how.
+pub trait Progress {
+   // How many steps there'll be, if that's known up front.
+   fn set_length(&mut self, _steps: u64) {}
+
+   fn set_position(&mut self, step: u64);
+}
+
+// For when nobody's watching.
+impl Progress for () {
+   fn set_position(&mut self, _step: u64) {}
+}
+
+// Parses input and works out one part of it, for when only one is wanted.
+pub fn solve<S: Solver>(input: &str, part: u32) -> Result<String, AocError> {
+   return S::part_with_progress(&S::parse(input)?, part, &mut ());
+}
+
+#[test]
+fn test_solve() {
+   // The lines, then the longest of them.
+   struct Lines;
+   impl Solver for Lines {
+      type Parsed = Vec<String>;
+      fn parse(input: &str) -> Result<Vec<String>, AocError> {
+         return Ok(input.lines().map(|l| l.to_string()).collect());
+      }
+      fn part1(lines: &Vec<String>) -> Result<String, AocError> {
+         return Ok(lines.len().to_string());
+      }
+      fn part2(lines: &Vec<String>) -> Result<String, AocError> {
+         return lines.iter().map(|l| l.len()).max().map(|n| n.to_string()).ok_or(AocError::Unsolvable("no lines".to_string()));
+      }
+   }
+   assert_eq!(solve::<Lines>("ab\nabcd\n", 1).unwrap(), "2");
+   assert_eq!(solve::<Lines>("ab\nabcd\n", 2).unwrap(), "4");
+   assert!(solve::<Lines>("", 2).is_err());
+   assert!(solve::<Lines>("ab", 3).is_err());
+}
+
+#[test]
+fn test_progress() {
+   // Counts to the number given, saying how it's going.
+   struct Count;
+   impl Solver for Count {
+      type Parsed = u64;
+      fn parse(input: &str) -> Result<u64, AocError> {
+         return input.trim().parse().map_err(|_| AocError::parse(1, None, "not a number".to_string()));
+      }
+      fn part1(n: &u64) -> Result<String, AocError> {
+         return Count::part_with_progress(n, 1, &mut ());
+      }
+      fn part2(_: &u64) -> Result<String, AocError> {
+         return Ok("0".to_string());
+      }
+      fn part_with_progress(n: &u64, part: u32, progress: &mut dyn Progress) -> Result<String, AocError> {
+         if part != 1 {
+            return Count::part2(n);
+         }
+         progress.set_length(*n);
+         for i in 1..(n + 1) {
+            progress.set_position(i);
+         }
+         return Ok(n.to_string());
+      }
+   }
+   struct Heard(Vec<u64>);
+   impl Progress for Heard {
+      fn set_length(&mut self, steps: u64) {
+         self.0.push(steps * 100);
+      }
+      fn set_position(&mut self, step: u64) {
+         self.0.push(step);
+      }
+   }
+   let mut heard = Heard(Vec::new());
+   assert_eq!(Count::part_with_progress(&3, 1, &mut heard).unwrap(), "3");
+   assert_eq!(heard.0, [300, 1, 2, 3]);
+   assert_eq!(solve::<Count>("3", 1).unwrap(), "3");
+}
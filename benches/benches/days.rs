@@ -0,0 +1,73 @@
+// Every day's Solver on the input in its directory, in a group for each
+// day with S::parse, S::part1 and S::part2 benchmarked apart, so that
+// `cargo bench -p aoc2015-benches -- day07` is just day 7.  A day without
+// an input yet, or with a part that has no answer, is left out, and says
+// so on stderr.
+#[macro_use]
+extern crate criterion;
+extern crate aoc2015_days as days;
+extern crate aoc2015_lib;
+extern crate circuit;
+extern crate day01;
+extern crate day02;
+extern crate day03;
+extern crate day04;
+extern crate day05;
+extern crate day06;
+extern crate day08;
+extern crate day09;
+
+use aoc2015_lib::Solver;
+use circuit::Day07;
+use criterion::Criterion;
+use day01::Day01;
+use day02::Day02;
+use day03::Day03;
+use day04::Day04;
+use day05::Day05;
+use day06::Day06;
+use day08::Day08;
+use day09::Day09;
+use std::fs;
+
+fn bench<S: Solver>(c: &mut Criterion, day: u32) {
+   let path = days::workspace_root().join(day.to_string()).join("input.txt");
+   let input = fs::read_to_string(&path).unwrap_or_default();
+   if input.is_empty() {
+      eprintln!("warning: day {} isn't benchmarked, there's no input in {}", day, path.display());
+      return;
+   }
+   let parsed = match S::parse(&input) {
+      Ok(parsed) => parsed,
+      Err(e) => panic!("day {} can't parse {}: {}", day, path.display(), e),
+   };
+
+   let mut group = c.benchmark_group(format!("day{:02}", day));
+   // Day 4's second part takes seconds, so ten samples is plenty.
+   group.sample_size(10);
+   group.bench_function("parse", |b| b.iter(|| S::parse(&input).unwrap()));
+   for part in 1..3 {
+      let solve = |parsed: &S::Parsed| if part == 1 { S::part1(parsed) } else { S::part2(parsed) };
+      if let Err(e) = solve(&parsed) {
+         eprintln!("warning: day {} part {} isn't benchmarked: {}", day, part, e);
+         continue;
+      }
+      group.bench_function(format!("part {}", part), |b| b.iter(|| solve(&parsed).unwrap()));
+   }
+   group.finish();
+}
+
+fn solving(c: &mut Criterion) {
+   bench::<Day01>(c, 1);
+   bench::<Day02>(c, 2);
+   bench::<Day03>(c, 3);
+   bench::<Day04>(c, 4);
+   bench::<Day05>(c, 5);
+   bench::<Day06>(c, 6);
+   bench::<Day07>(c, 7);
+   bench::<Day08>(c, 8);
+   bench::<Day09>(c, 9);
+}
+
+criterion_group!(benches, solving);
+criterion_main!(benches);
@@ -0,0 +1,191 @@
+// Every day that's been solved, each of them a Solver behind the one
+// Puzzle interface, for the aoc runner and for the browser: with the wasm
+// feature, solve is exported to JavaScript.
+extern crate aoc2015_lib;
+#[cfg(feature = "day07")]
+extern crate circuit;
+#[cfg(feature = "day01")]
+extern crate day01;
+#[cfg(feature = "day02")]
+extern crate day02;
+#[cfg(feature = "day03")]
+extern crate day03;
+#[cfg(feature = "day04")]
+extern crate day04;
+#[cfg(feature = "day05")]
+extern crate day05;
+#[cfg(feature = "day06")]
+extern crate day06;
+#[cfg(feature = "day08")]
+extern crate day08;
+#[cfg(feature = "day09")]
+extern crate day09;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
+use aoc2015_lib::{AocError, Progress, Solver};
+#[cfg(feature = "day07")]
+use circuit::Day07;
+#[cfg(feature = "day01")]
+use day01::Day01;
+#[cfg(feature = "day02")]
+use day02::Day02;
+#[cfg(feature = "day03")]
+use day03::Day03;
+#[cfg(feature = "day04")]
+use day04::Day04;
+#[cfg(feature = "day05")]
+use day05::Day05;
+#[cfg(feature = "day06")]
+use day06::Day06;
+#[cfg(feature = "day08")]
+use day08::Day08;
+#[cfg(feature = "day09")]
+use day09::Day09;
+use std::path::PathBuf;
+
+// A day's puzzle input, parsed once so that either part can be worked
+// out from it.
+pub trait Puzzle {
+   fn part1(&self) -> Result<String, AocError>;
+   fn part2(&self) -> Result<String, AocError>;
+
+   fn part(&self, part: u32) -> Result<String, AocError> {
+      return match part {
+         1 => self.part1(),
+         2 => self.part2(),
+         _ => Err(AocError::Unsolvable(format!("there's no part {}", part))),
+      };
+   }
+
+   fn part_with_progress(&self, part: u32, _progress: &mut dyn Progress) -> Result<String, AocError> {
+      return self.part(part);
+   }
+}
+
+pub type Parse = fn(&str) -> Result<Box<dyn Puzzle>, AocError>;
+
+pub struct Day {
+   pub day: u32,
+   pub parse: Parse,
+}
+
+impl Day {
+   pub fn solve(&self, part: u32, input: &str) -> Result<String, AocError> {
+      return (self.parse)(input)?.part(part);
+   }
+
+   // Where the day's own binary has always looked for its input.
+   pub fn default_input(&self) -> PathBuf {
+      return workspace_root().join(self.day.to_string()).join("input.txt");
+   }
+}
+
+pub fn workspace_root() -> PathBuf {
+   return PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+}
+
+// Every day solved so far, or those of them with their features on.
+pub const DAYS: &[Day] = &[
+   #[cfg(feature = "day01")]
+   Day{day: 1, parse: solver::<Day01>},
+   #[cfg(feature = "day02")]
+   Day{day: 2, parse: solver::<Day02>},
+   #[cfg(feature = "day03")]
+   Day{day: 3, parse: solver::<Day03>},
+   #[cfg(feature = "day04")]
+   Day{day: 4, parse: solver::<Day04>},
+   #[cfg(feature = "day05")]
+   Day{day: 5, parse: solver::<Day05>},
+   #[cfg(feature = "day06")]
+   Day{day: 6, parse: solver::<Day06>},
+   #[cfg(feature = "day07")]
+   Day{day: 7, parse: solver::<Day07>},
+   #[cfg(feature = "day08")]
+   Day{day: 8, parse: solver::<Day08>},
+   #[cfg(feature = "day09")]
+   Day{day: 9, parse: solver::<Day09>},
+];
+
+pub fn find(day: u32) -> Option<&'static Day> {
+   return DAYS.iter().find(|d| d.day == day);
+}
+
+// Both of day's answers for input, for tools that want the solvers
+// without the aoc runner or any files.
+pub fn answers(day: u32, input: &str) -> Result<(String, String), AocError> {
+   let puzzle = (find(day).ok_or_else(|| AocError::Unsolvable(format!("day {} isn't solved yet", day)))?.parse)(input)?;
+   return Ok((puzzle.part1()?, puzzle.part2()?));
+}
+
+// solve_day07(input) and so on, the same as answers(7, input) for each
+// day that's built.
+macro_rules! solve_days {
+   ($($name:ident: $day:expr, $feature:literal,)*) => {
+      $(
+         #[cfg(feature = $feature)]
+         pub fn $name(input: &str) -> Result<(String, String), AocError> {
+            return answers($day, input);
+         }
+      )*
+   };
+}
+
+solve_days! {
+   solve_day01: 1, "day01",
+   solve_day02: 2, "day02",
+   solve_day03: 3, "day03",
+   solve_day04: 4, "day04",
+   solve_day05: 5, "day05",
+   solve_day06: 6, "day06",
+   solve_day07: 7, "day07",
+   solve_day08: 8, "day08",
+   solve_day09: 9, "day09",
+}
+
+// Every day, since they're all Solvers: the input parsed once, and
+// either part worked out from that.
+pub struct Solved<S: Solver>(pub S::Parsed);
+
+pub fn solver<S: Solver + 'static>(input: &str) -> Result<Box<dyn Puzzle>, AocError> {
+   return Ok(Box::new(Solved::<S>(S::parse(input)?)));
+}
+
+impl<S: Solver> Puzzle for Solved<S> {
+   fn part1(&self) -> Result<String, AocError> {
+      return S::part1(&self.0);
+   }
+
+   fn part2(&self) -> Result<String, AocError> {
+      return S::part2(&self.0);
+   }
+
+   fn part_with_progress(&self, part: u32, progress: &mut dyn Progress) -> Result<String, AocError> {
+      return S::part_with_progress(&self.0, part, progress);
+   }
+}
+
+#[cfg(all(feature = "day01", feature = "day07"))]
+#[test]
+fn test_registry() {
+   let day = find(7).unwrap();
+   let puzzle = (day.parse)("123 -> b\nb OR 4 -> a\n").unwrap();
+   assert_eq!(puzzle.part1().unwrap(), "127");
+   assert_eq!(puzzle.part2().unwrap(), "127");
+   assert!(puzzle.part(3).is_err());
+   assert!(day.solve(1, "b -> a").is_err());
+   assert!(find(25).is_none());
+   assert!(DAYS.iter().all(|d| d.default_input().ends_with(format!("{}/input.txt", d.day))));
+   assert_eq!(find(1).unwrap().solve(2, "()())\n").unwrap(), "5");
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_answers() {
+   assert_eq!(solve_day07("123 -> b\nb OR 4 -> a\n").unwrap(), ("127".to_string(), "127".to_string()));
+   assert!(solve_day07("b -> a").is_err());
+   assert_eq!(answers(25, "").unwrap_err().to_string(), "No answer: day 25 isn't solved yet");
+}
@@ -0,0 +1,19 @@
+use wasm_bindgen::prelude::*;
+use {find, DAYS};
+
+// Day's part worked out from input, for JavaScript:
+//
+//    solve(7, 1, "123 -> b\nb OR 4 -> a");  // "127"
+//
+// Errors come back as exceptions carrying the message.
+#[wasm_bindgen]
+pub fn solve(day: u32, part: u32, input: &str) -> Result<String, JsValue> {
+   let solver = find(day).ok_or_else(|| JsValue::from_str(&format!("Day {} isn't solved yet", day)))?;
+   return solver.solve(part, input).map_err(|e| JsValue::from_str(&e.to_string()));
+}
+
+// Every day solved so far, for listing in the page.
+#[wasm_bindgen]
+pub fn days() -> Vec<u32> {
+   return DAYS.iter().map(|d| d.day).collect();
+}
@@ -0,0 +1,16 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate day01;
+
+use std::str;
+
+// Walks up and down any text, which should only ever be an error if
+// there's more than parentheses in it.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   let _ = day01::walk(text);
+});
@@ -0,0 +1,16 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate day02;
+
+use std::str;
+
+// Any text as a list of presents; a line that isn't LxWxH is an error.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   let _ = day02::presents(text);
+   let _ = day02::total(text);
+});
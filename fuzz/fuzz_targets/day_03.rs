@@ -0,0 +1,16 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate day03;
+
+use std::str;
+
+// Any text as directions, for one Santa and for two.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   let _ = day03::houses(text, 1);
+   let _ = day03::houses(text, 2);
+});
@@ -0,0 +1,19 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate aoc2015_lib;
+extern crate day04;
+
+use aoc2015_lib::Solver;
+use day04::Day04;
+use std::str;
+
+// Any text as a secret key.  Only the parsing: mining on a key can take
+// as long as it likes.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   let _ = Day04::parse(text);
+});
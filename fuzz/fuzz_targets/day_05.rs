@@ -0,0 +1,18 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate day05;
+
+use std::str;
+
+// Every line of any text checked against both sets of rules.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   for line in text.lines() {
+      let _ = day05::part1_good(line);
+      let _ = day05::part2_good(line);
+   }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate day06;
+
+use std::str;
+
+// Any text as instructions for the lights; coordinates off the grid are
+// errors rather than out of bounds.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   let _ = day06::run(text);
+});
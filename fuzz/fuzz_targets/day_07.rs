@@ -0,0 +1,21 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate aoc2015_lib;
+extern crate circuit;
+
+use aoc2015_lib::Solver;
+use circuit::Day07;
+use std::str;
+
+// Any text as a netlist, and the signal on a if it loaded.  7/fuzz goes
+// much further into the circuit itself.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   if let Ok(circuit) = Day07::parse(text) {
+      let _ = Day07::part1(&circuit);
+   }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate day09;
+
+use day09::Map;
+use std::str;
+
+// Any text as distances between places, and the routes through them
+// when there are few enough places to try every order of.
+fuzz_target!(|data: &[u8]| {
+   let text = match str::from_utf8(data) {
+      Ok(text) => text,
+      Err(_) => return,
+   };
+   if let Ok(map) = Map::parse(text) {
+      if map.places.len() <= 8 {
+         let _ = map.routes();
+      }
+   }
+});